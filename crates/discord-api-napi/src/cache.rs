@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use discord_api::id::{ChannelId, GuildId, RoleId, UserId};
+use fujinoki_websocket::cache::{
+    backend::{CacheBackend, CacheKey, EntityKind},
+    redis::RedisBackend,
+};
+use serde_json::Value as JsonValue;
+
+use crate::rest::parse_id;
+
+/// Looks up the entities the bot has cached in a shared backend, e.g.
+/// `cache.guild(id)`. Unlike `client`, which only has the entities of the
+/// event, every cached entity can be read.
+#[napi(js_name = "Cache")]
+pub struct NapiCache {
+    backend: Arc<dyn CacheBackend>,
+}
+
+/// Connects to the Redis backend of `cache.backend` in the config, with the
+/// same `url` and `prefix`
+#[napi]
+pub async fn connect_cache(url: String, prefix: Option<String>) -> napi::Result<NapiCache> {
+    let backend = RedisBackend::connect(&url, prefix.as_deref())
+        .await
+        .map_err(|err| napi::Error::from_reason(format!("{err:#}")))?;
+
+    Ok(NapiCache {
+        backend: Arc::new(backend),
+    })
+}
+
+#[napi]
+impl NapiCache {
+    #[napi]
+    pub async fn guild(&self, guild_id: String) -> napi::Result<Option<JsonValue>> {
+        let key: GuildId = parse_id(&guild_id)?;
+        self.get(EntityKind::Guild, &key).await
+    }
+
+    #[napi]
+    pub async fn channel(&self, channel_id: String) -> napi::Result<Option<JsonValue>> {
+        let key: ChannelId = parse_id(&channel_id)?;
+        self.get(EntityKind::Channel, &key).await
+    }
+
+    #[napi]
+    pub async fn role(&self, guild_id: String, role_id: String) -> napi::Result<Option<JsonValue>> {
+        let key: (GuildId, RoleId) = (parse_id(&guild_id)?, parse_id(&role_id)?);
+        self.get(EntityKind::Role, &key).await
+    }
+
+    #[napi]
+    pub async fn member(
+        &self,
+        guild_id: String,
+        user_id: String,
+    ) -> napi::Result<Option<JsonValue>> {
+        let key: (GuildId, UserId) = (parse_id(&guild_id)?, parse_id(&user_id)?);
+        self.get(EntityKind::Member, &key).await
+    }
+
+    async fn get(&self, kind: EntityKind, key: &impl CacheKey) -> napi::Result<Option<JsonValue>> {
+        let entity = self
+            .backend
+            .get(kind, &key.cache_key())
+            .await
+            .map_err(|err| napi::Error::from_reason(format!("{err:#}")))?;
+
+        entity
+            .map(|entity| serde_json::from_slice(&entity))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
@@ -1,6 +1,8 @@
 #[macro_use]
 extern crate napi_derive;
 
+pub mod cache;
+pub mod permissions;
 // TODO(kijv) finish implementation
 pub mod rest;
 
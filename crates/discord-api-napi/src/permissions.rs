@@ -0,0 +1,44 @@
+use discord_api::{
+    channel::Channel,
+    guild::{Guild, GuildMember},
+    permissions::{base_permissions, channel_permissions, member_can, PermissionFlags},
+};
+
+/// The permissions of `member` as a bit set string, in `channel` or in the
+/// guild when there is none
+#[napi]
+pub fn member_permissions(
+    guild: serde_json::Value,
+    member: serde_json::Value,
+    channel: Option<serde_json::Value>,
+) -> napi::Result<String> {
+    let guild: Guild = serde_json::from_value(guild)?;
+    let member: GuildMember = serde_json::from_value(member)?;
+    let permissions = match channel {
+        Some(channel) => {
+            let channel: Channel = serde_json::from_value(channel)?;
+            channel_permissions(&guild, &channel, &member)
+        }
+        None => base_permissions(&guild, &member),
+    };
+
+    Ok(permissions.bits().to_string())
+}
+
+/// Whether `member` has every permission of the `permissions` bit set string,
+/// in `channel` or in the guild when there is none
+#[napi(js_name = "memberCan")]
+pub fn napi_member_can(
+    guild: serde_json::Value,
+    member: serde_json::Value,
+    permissions: String,
+    channel: Option<serde_json::Value>,
+) -> napi::Result<bool> {
+    let guild: Guild = serde_json::from_value(guild)?;
+    let member: GuildMember = serde_json::from_value(member)?;
+    let channel: Option<Channel> = channel.map(serde_json::from_value).transpose()?;
+    let permissions: PermissionFlags =
+        serde_json::from_value(serde_json::Value::String(permissions))?;
+
+    Ok(member_can(&guild, channel.as_ref(), &member, permissions))
+}
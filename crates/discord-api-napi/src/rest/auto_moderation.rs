@@ -0,0 +1,101 @@
+use discord_api::{
+    id::{GuildId, RuleId},
+    rest::auto_moderation,
+};
+
+use super::{parse_id, NapiREST};
+
+#[napi]
+impl NapiREST {
+    #[napi]
+    pub async fn list_auto_moderation_rules(
+        &self,
+        guild_id: String,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                auto_moderation::list_auto_moderation_rules(guild_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_auto_moderation_rule(
+        &self,
+        guild_id: String,
+        rule_id: String,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let rule_id: RuleId = parse_id(&rule_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                auto_moderation::get_auto_moderation_rule(guild_id, rule_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn create_auto_moderation_rule(
+        &self,
+        guild_id: String,
+        rule: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                auto_moderation::create_auto_moderation_rule(guild_id, &rule, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn modify_auto_moderation_rule(
+        &self,
+        guild_id: String,
+        rule_id: String,
+        rule: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let rule_id: RuleId = parse_id(&rule_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = auto_moderation::modify_auto_moderation_rule(
+                guild_id, rule_id, &rule, &token, None,
+            )
+            .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_auto_moderation_rule(
+        &self,
+        guild_id: String,
+        rule_id: String,
+    ) -> napi::Result<bool> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let rule_id: RuleId = parse_id(&rule_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            auto_moderation::delete_auto_moderation_rule(guild_id, rule_id, &token, None).await
+        })
+        .await
+    }
+}
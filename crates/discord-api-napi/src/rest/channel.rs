@@ -0,0 +1,425 @@
+use discord_api::{
+    id::{ChannelId, MessageId, UserId},
+    rest::{channel, routes::ArchivedStatus},
+};
+
+use super::{into_http_files, parse_id, parse_limit, NapiHttpFile, NapiREST};
+
+#[napi]
+impl NapiREST {
+    #[napi]
+    pub async fn get_channel(&self, channel_id: String) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = channel::get_channel(channel_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn modify_channel(
+        &self,
+        channel_id: String,
+        channel: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = channel::modify_channel(channel_id, &channel, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_channel(&self, channel_id: String) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = channel::delete_channel(channel_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_channel_messages(
+        &self,
+        channel_id: String,
+        before: Option<String>,
+        after: Option<String>,
+        limit: Option<u32>,
+    ) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let before: Option<MessageId> = before.as_deref().map(parse_id).transpose()?;
+        let after: Option<MessageId> = after.as_deref().map(parse_id).transpose()?;
+        let limit = parse_limit::<u8>(limit)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                channel::get_channel_messages(channel_id, before, after, limit, &token, None)
+                    .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_channel_message(
+        &self,
+        channel_id: String,
+        message_id: String,
+    ) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                channel::get_channel_message(channel_id, message_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn create_message(
+        &self,
+        channel_id: String,
+        message: serde_json::Value,
+        files: Option<Vec<NapiHttpFile>>,
+    ) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let files = into_http_files(files);
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                channel::create_message(channel_id, &message, &files, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn crosspost_message(
+        &self,
+        channel_id: String,
+        message_id: String,
+    ) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = channel::crosspost_message(channel_id, message_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn edit_message(
+        &self,
+        channel_id: String,
+        message_id: String,
+        message: serde_json::Value,
+        files: Option<Vec<NapiHttpFile>>,
+    ) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let files = into_http_files(files);
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                channel::edit_message(channel_id, message_id, &message, &files, &token, None)
+                    .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_message(
+        &self,
+        channel_id: String,
+        message_id: String,
+    ) -> napi::Result<bool> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let token = self.token()?;
+
+        self.run(async move { channel::delete_message(channel_id, message_id, &token, None).await })
+            .await
+    }
+
+    #[napi]
+    pub async fn bulk_delete_messages(
+        &self,
+        channel_id: String,
+        message_ids: Vec<String>,
+    ) -> napi::Result<bool> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_ids = message_ids
+            .iter()
+            .map(|id| parse_id::<MessageId>(id))
+            .collect::<napi::Result<Vec<_>>>()?;
+        let token = self.token()?;
+
+        self.run(async move {
+            channel::bulk_delete_messages(channel_id, &message_ids, &token, None).await
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn create_reaction(
+        &self,
+        channel_id: String,
+        message_id: String,
+        emoji: String,
+    ) -> napi::Result<bool> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            channel::create_reaction(channel_id, message_id, &emoji, &token, None).await
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_own_reaction(
+        &self,
+        channel_id: String,
+        message_id: String,
+        emoji: String,
+    ) -> napi::Result<bool> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            channel::delete_own_reaction(channel_id, message_id, &emoji, &token, None).await
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_user_reaction(
+        &self,
+        channel_id: String,
+        message_id: String,
+        emoji: String,
+        user_id: String,
+    ) -> napi::Result<bool> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let user_id: UserId = parse_id(&user_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            channel::delete_user_reaction(channel_id, message_id, &emoji, user_id, &token, None)
+                .await
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_reactions(
+        &self,
+        channel_id: String,
+        message_id: String,
+        emoji: String,
+        after: Option<String>,
+        limit: Option<u32>,
+    ) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let after: Option<UserId> = after.as_deref().map(parse_id).transpose()?;
+        let limit = parse_limit::<u8>(limit)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                channel::get_reactions(channel_id, message_id, &emoji, after, limit, &token, None)
+                    .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_all_reactions(
+        &self,
+        channel_id: String,
+        message_id: String,
+        emoji: Option<String>,
+    ) -> napi::Result<bool> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            channel::delete_all_reactions(channel_id, message_id, emoji.as_deref(), &token, None)
+                .await
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn trigger_typing_indicator(&self, channel_id: String) -> napi::Result<bool> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let token = self.token()?;
+
+        self.run(async move { channel::trigger_typing_indicator(channel_id, &token, None).await })
+            .await
+    }
+
+    #[napi]
+    pub async fn get_pinned_messages(&self, channel_id: String) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = channel::get_pinned_messages(channel_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn pin_message(&self, channel_id: String, message_id: String) -> napi::Result<bool> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let token = self.token()?;
+
+        self.run(async move { channel::pin_message(channel_id, message_id, &token, None).await })
+            .await
+    }
+
+    #[napi]
+    pub async fn unpin_message(
+        &self,
+        channel_id: String,
+        message_id: String,
+    ) -> napi::Result<bool> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let token = self.token()?;
+
+        self.run(async move { channel::unpin_message(channel_id, message_id, &token, None).await })
+            .await
+    }
+
+    #[napi]
+    pub async fn start_thread(
+        &self,
+        channel_id: String,
+        message_id: Option<String>,
+        thread: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let message_id: Option<MessageId> = message_id.as_deref().map(parse_id).transpose()?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                channel::start_thread(channel_id, message_id, &thread, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn add_thread_member(
+        &self,
+        thread_id: String,
+        user_id: Option<String>,
+    ) -> napi::Result<bool> {
+        let thread_id: ChannelId = parse_id(&thread_id)?;
+        let user_id: Option<UserId> = user_id.as_deref().map(parse_id).transpose()?;
+        let token = self.token()?;
+
+        self.run(async move { channel::add_thread_member(thread_id, user_id, &token, None).await })
+            .await
+    }
+
+    #[napi]
+    pub async fn remove_thread_member(
+        &self,
+        thread_id: String,
+        user_id: Option<String>,
+    ) -> napi::Result<bool> {
+        let thread_id: ChannelId = parse_id(&thread_id)?;
+        let user_id: Option<UserId> = user_id.as_deref().map(parse_id).transpose()?;
+        let token = self.token()?;
+
+        self.run(
+            async move { channel::remove_thread_member(thread_id, user_id, &token, None).await },
+        )
+        .await
+    }
+
+    #[napi]
+    pub async fn get_thread_members(
+        &self,
+        thread_id: String,
+        with_member: bool,
+    ) -> napi::Result<serde_json::Value> {
+        let thread_id: ChannelId = parse_id(&thread_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                channel::get_thread_members(thread_id, with_member, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_archived_threads(
+        &self,
+        channel_id: String,
+        public: bool,
+        limit: Option<u32>,
+    ) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let archived_status = match public {
+            true => ArchivedStatus::Public,
+            false => ArchivedStatus::Private,
+        };
+        let limit = parse_limit::<u8>(limit)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                channel::get_archived_threads(channel_id, archived_status, limit, &token, None)
+                    .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+}
@@ -0,0 +1,91 @@
+use discord_api::{
+    id::{EmojiId, GuildId},
+    rest::emoji,
+};
+
+use super::{parse_id, NapiREST};
+
+#[napi]
+impl NapiREST {
+    #[napi]
+    pub async fn list_guild_emojis(&self, guild_id: String) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = emoji::list_guild_emojis(guild_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_guild_emoji(
+        &self,
+        guild_id: String,
+        emoji_id: String,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let emoji_id: EmojiId = parse_id(&emoji_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = emoji::get_guild_emoji(guild_id, emoji_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn create_guild_emoji(
+        &self,
+        guild_id: String,
+        emoji: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = emoji::create_guild_emoji(guild_id, &emoji, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn modify_guild_emoji(
+        &self,
+        guild_id: String,
+        emoji_id: String,
+        emoji: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let emoji_id: EmojiId = parse_id(&emoji_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                emoji::modify_guild_emoji(guild_id, emoji_id, &emoji, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_guild_emoji(
+        &self,
+        guild_id: String,
+        emoji_id: String,
+    ) -> napi::Result<bool> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let emoji_id: EmojiId = parse_id(&emoji_id)?;
+        let token = self.token()?;
+
+        self.run(async move { emoji::delete_guild_emoji(guild_id, emoji_id, &token, None).await })
+            .await
+    }
+}
@@ -0,0 +1,344 @@
+use discord_api::{
+    id::{GuildId, RoleId, UserId},
+    rest::guild,
+};
+
+use super::{parse_id, parse_limit, NapiREST};
+
+#[napi]
+impl NapiREST {
+    #[napi]
+    pub async fn get_guild(
+        &self,
+        guild_id: String,
+        with_counts: bool,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = guild::get_guild(guild_id, with_counts, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn modify_guild(
+        &self,
+        guild_id: String,
+        guild: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = guild::modify_guild(guild_id, &guild, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_guild_channels(&self, guild_id: String) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = guild::get_guild_channels(guild_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn create_guild_channel(
+        &self,
+        guild_id: String,
+        channel: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = guild::create_guild_channel(guild_id, &channel, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn list_active_guild_threads(
+        &self,
+        guild_id: String,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = guild::list_active_guild_threads(guild_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_guild_member(
+        &self,
+        guild_id: String,
+        user_id: String,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let user_id: UserId = parse_id(&user_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = guild::get_guild_member(guild_id, user_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn list_guild_members(
+        &self,
+        guild_id: String,
+        after: Option<String>,
+        limit: Option<u32>,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let after: Option<UserId> = after.as_deref().map(parse_id).transpose()?;
+        let limit = parse_limit::<u16>(limit)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = guild::list_guild_members(guild_id, after, limit, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn search_guild_members(
+        &self,
+        guild_id: String,
+        query: String,
+        limit: Option<u32>,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let limit = parse_limit::<u16>(limit)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                guild::search_guild_members(guild_id, &query, limit, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn modify_guild_member(
+        &self,
+        guild_id: String,
+        user_id: Option<String>,
+        member: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let user_id: Option<UserId> = user_id.as_deref().map(parse_id).transpose()?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                guild::modify_guild_member(guild_id, user_id, &member, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn remove_guild_member(
+        &self,
+        guild_id: String,
+        user_id: String,
+    ) -> napi::Result<bool> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let user_id: UserId = parse_id(&user_id)?;
+        let token = self.token()?;
+
+        self.run(async move { guild::remove_guild_member(guild_id, user_id, &token, None).await })
+            .await
+    }
+
+    #[napi]
+    pub async fn add_guild_member_role(
+        &self,
+        guild_id: String,
+        user_id: String,
+        role_id: String,
+    ) -> napi::Result<bool> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let user_id: UserId = parse_id(&user_id)?;
+        let role_id: RoleId = parse_id(&role_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            guild::add_guild_member_role(guild_id, user_id, role_id, &token, None).await
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn remove_guild_member_role(
+        &self,
+        guild_id: String,
+        user_id: String,
+        role_id: String,
+    ) -> napi::Result<bool> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let user_id: UserId = parse_id(&user_id)?;
+        let role_id: RoleId = parse_id(&role_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            guild::remove_guild_member_role(guild_id, user_id, role_id, &token, None).await
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_guild_bans(
+        &self,
+        guild_id: String,
+        before: Option<String>,
+        after: Option<String>,
+        limit: Option<u32>,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let before: Option<UserId> = before.as_deref().map(parse_id).transpose()?;
+        let after: Option<UserId> = after.as_deref().map(parse_id).transpose()?;
+        let limit = parse_limit::<u16>(limit)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                guild::get_guild_bans(guild_id, before, after, limit, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_guild_ban(
+        &self,
+        guild_id: String,
+        user_id: String,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let user_id: UserId = parse_id(&user_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = guild::get_guild_ban(guild_id, user_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn create_guild_ban(
+        &self,
+        guild_id: String,
+        user_id: String,
+        delete_message_seconds: Option<u32>,
+    ) -> napi::Result<bool> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let user_id: UserId = parse_id(&user_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            guild::create_guild_ban(guild_id, user_id, delete_message_seconds, &token, None).await
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn remove_guild_ban(&self, guild_id: String, user_id: String) -> napi::Result<bool> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let user_id: UserId = parse_id(&user_id)?;
+        let token = self.token()?;
+
+        self.run(async move { guild::remove_guild_ban(guild_id, user_id, &token, None).await })
+            .await
+    }
+
+    #[napi]
+    pub async fn get_guild_roles(&self, guild_id: String) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = guild::get_guild_roles(guild_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn create_guild_role(
+        &self,
+        guild_id: String,
+        role: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = guild::create_guild_role(guild_id, &role, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn modify_guild_role(
+        &self,
+        guild_id: String,
+        role_id: String,
+        role: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let role_id: RoleId = parse_id(&role_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = guild::modify_guild_role(guild_id, role_id, &role, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_guild_role(&self, guild_id: String, role_id: String) -> napi::Result<bool> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let role_id: RoleId = parse_id(&role_id)?;
+        let token = self.token()?;
+
+        self.run(async move { guild::delete_guild_role(guild_id, role_id, &token, None).await })
+            .await
+    }
+}
@@ -1,6 +1,7 @@
-use std::{str::FromStr, sync::Arc};
+use std::{fmt::Display, future::Future, str::FromStr, sync::Arc};
 
-use discord_api::{id::InteractionId, interactions::InteractionResponse};
+use discord_api::{id::InteractionId, interactions::InteractionResponse, rest::http::HttpFile};
+use napi::bindgen_prelude::Buffer;
 use turbopack_binding::turbo::{
     tasks::{TurboTasks, Vc},
     tasks_memory::MemoryBackend,
@@ -9,11 +10,40 @@ use turbopack_binding::turbo::{
 use self::interactions::NapiCreateInteractionResponseOptions;
 use crate::register;
 
+mod auto_moderation;
+mod channel;
+mod emoji;
+mod guild;
 mod interactions;
+mod scheduled_event;
+mod sticker;
+mod webhook;
 
+/// Requests that fail resolve to `null`, or `false` for those without a
+/// response body
 #[napi(js_name = "REST")]
 pub struct NapiREST {
     turbo_tasks: Arc<TurboTasks<MemoryBackend>>,
+    token: Option<String>,
+}
+
+/// A file uploaded with a message, `attachments` of the message can refer to
+/// it by its index
+#[napi(object)]
+pub struct NapiHttpFile {
+    pub name: String,
+    pub data: Buffer,
+    pub content_type: Option<String>,
+}
+
+impl From<NapiHttpFile> for HttpFile {
+    fn from(file: NapiHttpFile) -> Self {
+        HttpFile {
+            filename: file.name.into(),
+            content_type: file.content_type.map(Into::into),
+            content: file.data.to_vec(),
+        }
+    }
 }
 
 #[napi(object)]
@@ -36,7 +66,16 @@ impl NapiREST {
             .unwrap_or(usize::MAX);
         let turbo_tasks = TurboTasks::new(MemoryBackend::new(turbo_tasks_memory_limit));
 
-        NapiREST { turbo_tasks }
+        NapiREST {
+            turbo_tasks,
+            token: None,
+        }
+    }
+
+    /// Sets the bot token that requests are authorized with
+    #[napi]
+    pub fn set_token(&mut self, token: String) {
+        self.token = Some(token);
     }
 
     #[napi]
@@ -56,6 +95,7 @@ impl NapiREST {
                         .cell(),
                     Vc::cell(options.interaction_token.into()),
                     interaction_response.cell(),
+                    Vc::cell(vec![]),
                     None,
                 )
                 .await
@@ -66,3 +106,45 @@ impl NapiREST {
         Ok(())
     }
 }
+
+impl NapiREST {
+    fn token(&self) -> napi::Result<String> {
+        self.token
+            .clone()
+            .ok_or_else(|| napi::Error::from_reason("no token was set, call setToken first"))
+    }
+
+    async fn run<T: Send + 'static>(
+        &self,
+        request: impl Future<Output = anyhow::Result<T>> + Send + 'static,
+    ) -> napi::Result<T> {
+        self.turbo_tasks
+            .run_once(request)
+            .await
+            .map_err(|err| napi::Error::from_reason(format!("{err:#}")))
+    }
+}
+
+pub(crate) fn parse_id<T: FromStr>(id: &str) -> napi::Result<T>
+where
+    T::Err: Display,
+{
+    T::from_str(id).map_err(|err| napi::Error::from_reason(format!("invalid id `{id}`: {err}")))
+}
+
+fn parse_limit<T: TryFrom<u32>>(limit: Option<u32>) -> napi::Result<Option<T>> {
+    limit
+        .map(|limit| {
+            T::try_from(limit)
+                .map_err(|_| napi::Error::from_reason(format!("limit {limit} is too large")))
+        })
+        .transpose()
+}
+
+fn into_http_files(files: Option<Vec<NapiHttpFile>>) -> Vec<HttpFile> {
+    files
+        .unwrap_or_default()
+        .into_iter()
+        .map(HttpFile::from)
+        .collect()
+}
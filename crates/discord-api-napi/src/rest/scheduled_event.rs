@@ -0,0 +1,144 @@
+use discord_api::{
+    id::{GuildId, ScheduledEventId},
+    rest::scheduled_event,
+};
+
+use super::{parse_id, parse_limit, NapiREST};
+
+#[napi]
+impl NapiREST {
+    #[napi]
+    pub async fn list_guild_scheduled_events(
+        &self,
+        guild_id: String,
+        with_user_count: bool,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = scheduled_event::list_guild_scheduled_events(
+                guild_id,
+                with_user_count,
+                &token,
+                None,
+            )
+            .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_guild_scheduled_event(
+        &self,
+        guild_id: String,
+        event_id: String,
+        with_user_count: bool,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let event_id: ScheduledEventId = parse_id(&event_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = scheduled_event::get_guild_scheduled_event(
+                guild_id,
+                event_id,
+                with_user_count,
+                &token,
+                None,
+            )
+            .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn create_guild_scheduled_event(
+        &self,
+        guild_id: String,
+        event: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                scheduled_event::create_guild_scheduled_event(guild_id, &event, &token, None)
+                    .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn modify_guild_scheduled_event(
+        &self,
+        guild_id: String,
+        event_id: String,
+        event: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let event_id: ScheduledEventId = parse_id(&event_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = scheduled_event::modify_guild_scheduled_event(
+                guild_id, event_id, &event, &token, None,
+            )
+            .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_guild_scheduled_event(
+        &self,
+        guild_id: String,
+        event_id: String,
+    ) -> napi::Result<bool> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let event_id: ScheduledEventId = parse_id(&event_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            scheduled_event::delete_guild_scheduled_event(guild_id, event_id, &token, None).await
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_guild_scheduled_event_users(
+        &self,
+        guild_id: String,
+        event_id: String,
+        limit: Option<u32>,
+        with_member: bool,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let event_id: ScheduledEventId = parse_id(&event_id)?;
+        let limit = parse_limit::<u8>(limit)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = scheduled_event::get_guild_scheduled_event_users(
+                guild_id,
+                event_id,
+                limit,
+                with_member,
+                &token,
+                None,
+            )
+            .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+}
@@ -0,0 +1,119 @@
+use discord_api::{
+    id::{GuildId, StickerId},
+    rest::{http::HttpFile, sticker},
+};
+
+use super::{parse_id, NapiHttpFile, NapiREST};
+
+#[napi]
+impl NapiREST {
+    #[napi]
+    pub async fn get_sticker(&self, sticker_id: String) -> napi::Result<serde_json::Value> {
+        let sticker_id: StickerId = parse_id(&sticker_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = sticker::get_sticker(sticker_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn list_guild_stickers(&self, guild_id: String) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = sticker::list_guild_stickers(guild_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_guild_sticker(
+        &self,
+        guild_id: String,
+        sticker_id: String,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let sticker_id: StickerId = parse_id(&sticker_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = sticker::get_guild_sticker(guild_id, sticker_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn create_guild_sticker(
+        &self,
+        guild_id: String,
+        name: String,
+        description: String,
+        tags: String,
+        file: NapiHttpFile,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let file = HttpFile::from(file);
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = sticker::create_guild_sticker(
+                guild_id,
+                name.into(),
+                description.into(),
+                tags.into(),
+                &file,
+                &token,
+                None,
+            )
+            .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn modify_guild_sticker(
+        &self,
+        guild_id: String,
+        sticker_id: String,
+        sticker: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let sticker_id: StickerId = parse_id(&sticker_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                sticker::modify_guild_sticker(guild_id, sticker_id, &sticker, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_guild_sticker(
+        &self,
+        guild_id: String,
+        sticker_id: String,
+    ) -> napi::Result<bool> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let sticker_id: StickerId = parse_id(&sticker_id)?;
+        let token = self.token()?;
+
+        self.run(
+            async move { sticker::delete_guild_sticker(guild_id, sticker_id, &token, None).await },
+        )
+        .await
+    }
+}
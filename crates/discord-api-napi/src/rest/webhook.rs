@@ -0,0 +1,202 @@
+use discord_api::{
+    id::{ChannelId, GuildId, MessageId, WebhookId},
+    rest::webhook,
+};
+
+use super::{into_http_files, parse_id, NapiHttpFile, NapiREST};
+
+#[napi]
+impl NapiREST {
+    #[napi]
+    pub async fn create_webhook(
+        &self,
+        channel_id: String,
+        webhook: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = webhook::create_webhook(channel_id, &webhook, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_channel_webhooks(
+        &self,
+        channel_id: String,
+    ) -> napi::Result<serde_json::Value> {
+        let channel_id: ChannelId = parse_id(&channel_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = webhook::get_channel_webhooks(channel_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_guild_webhooks(&self, guild_id: String) -> napi::Result<serde_json::Value> {
+        let guild_id: GuildId = parse_id(&guild_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = webhook::get_guild_webhooks(guild_id, &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_webhook(
+        &self,
+        webhook_id: String,
+        webhook_token: Option<String>,
+    ) -> napi::Result<serde_json::Value> {
+        let webhook_id: WebhookId = parse_id(&webhook_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                webhook::get_webhook(webhook_id, webhook_token.as_deref(), &token, None).await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn modify_webhook(
+        &self,
+        webhook_id: String,
+        webhook_token: Option<String>,
+        webhook: serde_json::Value,
+    ) -> napi::Result<serde_json::Value> {
+        let webhook_id: WebhookId = parse_id(&webhook_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = webhook::modify_webhook(
+                webhook_id,
+                webhook_token.as_deref(),
+                &webhook,
+                &token,
+                None,
+            )
+            .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_webhook(
+        &self,
+        webhook_id: String,
+        webhook_token: Option<String>,
+    ) -> napi::Result<bool> {
+        let webhook_id: WebhookId = parse_id(&webhook_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            webhook::delete_webhook(webhook_id, webhook_token.as_deref(), &token, None).await
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn execute_webhook(
+        &self,
+        webhook_id: String,
+        webhook_token: String,
+        message: serde_json::Value,
+        files: Option<Vec<NapiHttpFile>>,
+    ) -> napi::Result<bool> {
+        let webhook_id: WebhookId = parse_id(&webhook_id)?;
+        let files = into_http_files(files);
+        let token = self.token()?;
+
+        self.run(async move {
+            webhook::execute_webhook(webhook_id, &webhook_token, &message, &files, &token, None)
+                .await
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn get_webhook_message(
+        &self,
+        webhook_id: String,
+        webhook_token: String,
+        message_id: String,
+    ) -> napi::Result<serde_json::Value> {
+        let webhook_id: WebhookId = parse_id(&webhook_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            let response =
+                webhook::get_webhook_message(webhook_id, &webhook_token, message_id, &token, None)
+                    .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn edit_webhook_message(
+        &self,
+        webhook_id: String,
+        webhook_token: String,
+        message_id: String,
+        message: serde_json::Value,
+        files: Option<Vec<NapiHttpFile>>,
+    ) -> napi::Result<serde_json::Value> {
+        let webhook_id: WebhookId = parse_id(&webhook_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let files = into_http_files(files);
+        let token = self.token()?;
+
+        self.run(async move {
+            let response = webhook::edit_webhook_message(
+                webhook_id,
+                &webhook_token,
+                message_id,
+                &message,
+                &files,
+                &token,
+                None,
+            )
+            .await?;
+
+            Ok(serde_json::to_value(response)?)
+        })
+        .await
+    }
+
+    #[napi]
+    pub async fn delete_webhook_message(
+        &self,
+        webhook_id: String,
+        webhook_token: String,
+        message_id: String,
+    ) -> napi::Result<bool> {
+        let webhook_id: WebhookId = parse_id(&webhook_id)?;
+        let message_id: MessageId = parse_id(&message_id)?;
+        let token = self.token()?;
+
+        self.run(async move {
+            webhook::delete_webhook_message(webhook_id, &webhook_token, message_id, &token, None)
+                .await
+        })
+        .await
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use lazy_regex::{lazy_regex, Lazy};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -5,10 +7,14 @@ use turbopack_binding::turbo::tasks as turbo_tasks;
 
 use crate::{
     channel::ChannelTypes,
-    id::{ApplicationId, CommandId, GenericId, GuildId},
+    id::{ApplicationId, CommandId, CommandPermissionId, GenericId, GuildId},
+    impl_serde_for_integer_enum,
     permissions::PermissionFlags,
 };
 
+/// Map of [locale codes](crate::locales::LOCALE_CODES) to a localized string
+pub type Localizations = BTreeMap<String, String>;
+
 pub static CHAT_INPUT_NAME: Lazy<Regex> =
     lazy_regex!(r"^[-_\p{L}\p{N}\p{sc=Deva}\p{sc=Thai}]{1,32}$");
 
@@ -55,20 +61,31 @@ impl<'de> serde::Deserialize<'de> for IntegerOrDouble {
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct ApplicationCommand {
+    /// Assigned by Discord, missing from local command definitions
+    #[serde(default)]
     pub id: CommandId,
+    #[serde(default)]
     pub r#type: ApplicationCommandType,
+    /// Assigned by Discord, missing from local command definitions
+    #[serde(default)]
     pub application_id: ApplicationId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub guild_id: Option<GuildId>,
     pub name: String,
-    // name_localizations: Locales,
+    /// Map of locale codes to the name in that locale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_localizations: Option<Localizations>,
+    /// Empty for user and message commands
+    #[serde(default)]
     pub description: String,
-    // description_localizations: Locales,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_localizations: Option<Localizations>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<Vec<ApplicationCommandOption>>,
-    /// `0` to disable the command for everyone except admins by default,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    default_member_permissions: Option<PermissionFlags>,
+    /// The permissions a member needs to use the command by default, `0` to
+    /// disable the command for everyone except admins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_member_permissions: Option<PermissionFlags>,
     /// Indicates whether the command is available in DMs with the app, only for
     /// globally-scoped commands. By default, commands are visible.
     #[deprecated = "use contexts instead"]
@@ -82,16 +99,18 @@ pub struct ApplicationCommand {
         skip_serializing_if = "Option::is_none",
         default = "ApplicationIntegration::default"
     )]
-    integration_type: Option<Vec<ApplicationIntegration>>,
+    pub integration_types: Option<Vec<ApplicationIntegration>>,
     /// [In preview](https://discord.com/developers/docs/change-log#userinstallable-apps-preview). [Installation context(s)](https://discord.com/developers/docs/resources/application#installation-context) where the command can be used, only for globally-scoped commands. By default, all interaction context types included for new commands.
     #[serde(
         skip_serializing_if = "Option::is_none",
         default = "InteractionContext::all"
     )]
-    contexts: Option<Vec<InteractionContext>>,
+    pub contexts: Option<Vec<InteractionContext>>,
     /// Indicates whether the command is [age-restricted](https://discord.com/developers/docs/interactions/application-commands#agerestricted-commands), defaults to `false``
     #[serde(skip_serializing_if = "Option::is_none", default = "some_false")]
     pub nsfw: Option<bool>,
+    /// Assigned by Discord, missing from local command definitions
+    #[serde(default)]
     pub version: GenericId,
 }
 
@@ -138,20 +157,23 @@ impl<'se> serde::Serialize for ApplicationCommandType {
 pub struct ApplicationCommandOption {
     pub r#type: ApplicationCommandOptionType,
     pub name: String,
-    // name_localizations: Locales,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_localizations: Option<Localizations>,
     pub description: String,
-    // description_localizations: Locales,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_localizations: Option<Localizations>,
     #[serde(skip_serializing_if = "Option::is_none", default = "some_false")]
     pub required: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub choices: Vec<ApplicationCommandOptionChoice>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<Vec<ApplicationCommandOption>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub channel_types: Option<ChannelTypes>,
+    pub channel_types: Option<Vec<ChannelTypes>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub min_value: Option<u32>,
+    pub min_value: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub max_value: Option<u32>,
+    pub max_value: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_length: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -161,7 +183,7 @@ pub struct ApplicationCommandOption {
 }
 
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ApplicationCommandOptionType {
     SubCommand = 1,
     SubCommandGroup = 2,
@@ -180,24 +202,44 @@ pub enum ApplicationCommandOptionType {
     Attachment = 11,
 }
 
+impl_serde_for_integer_enum!(
+    ApplicationCommandOptionType,
+    u8,
+    [
+        SubCommand,
+        SubCommandGroup,
+        String,
+        Integer,
+        Boolean,
+        User,
+        Channel,
+        Role,
+        Mentionable,
+        Number,
+        Attachment,
+    ]
+);
+
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct ApplicationCommandOptionChoice {
-    name: String,
-    // name_localizations: Locales,
-    value: ApplicationCommandOptionChoiceValue,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_localizations: Option<Localizations>,
+    pub value: ApplicationCommandOptionChoiceValue,
 }
 
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
-enum ApplicationCommandOptionChoiceValue {
+#[serde(untagged)]
+pub enum ApplicationCommandOptionChoiceValue {
     String(String),
-    Integer(u32),
+    Integer(i64),
     Double(f64),
 }
 
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ApplicationIntegration {
     /// App is installable to servers
     GuildInstall = 0,
@@ -205,6 +247,8 @@ pub enum ApplicationIntegration {
     UserInstall = 1,
 }
 
+impl_serde_for_integer_enum!(ApplicationIntegration, u8, [GuildInstall, UserInstall]);
+
 impl ApplicationIntegration {
     pub fn default() -> Option<Vec<ApplicationIntegration>> {
         Some(vec![ApplicationIntegration::GuildInstall])
@@ -212,7 +256,7 @@ impl ApplicationIntegration {
 }
 
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum InteractionContext {
     /// Interaction can be used within servers
     Guild = 0,
@@ -223,6 +267,8 @@ pub enum InteractionContext {
     PrivateChannel = 2,
 }
 
+impl_serde_for_integer_enum!(InteractionContext, u8, [Guild, BotDm, PrivateChannel]);
+
 impl InteractionContext {
     pub fn all() -> Option<Vec<InteractionContext>> {
         Some(vec![
@@ -232,3 +278,40 @@ impl InteractionContext {
         ])
     }
 }
+
+/// The permission overwrites of a command in a guild, which take precedence
+/// over its `default_member_permissions`
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GuildApplicationCommandPermissions {
+    /// The id of the command, or the id of the application when the
+    /// permissions apply to all of its commands
+    pub id: GenericId,
+    pub application_id: ApplicationId,
+    pub guild_id: GuildId,
+    pub permissions: Vec<ApplicationCommandPermission>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct ApplicationCommandPermissions(Vec<ApplicationCommandPermission>);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ApplicationCommandPermission {
+    /// The id of the role, user or channel. The guild id stands for
+    /// `@everyone` and the guild id minus `1` for all channels
+    pub id: CommandPermissionId,
+    pub r#type: ApplicationCommandPermissionType,
+    /// `true` to allow, `false` to disallow
+    pub permission: bool,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApplicationCommandPermissionType {
+    Role = 1,
+    User = 2,
+    Channel = 3,
+}
+
+impl_serde_for_integer_enum!(ApplicationCommandPermissionType, u8, [Role, User, Channel]);
@@ -4,9 +4,11 @@ use serde::{Deserialize, Deserializer, Serialize};
 use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr, Vc};
 
 pub mod command;
+pub mod sync;
+pub mod validation;
 
 use crate::{
-    guild::Guild,
+    guild::PartialGuild,
     id::{ApplicationId, GuildId, SkuId},
     team::Team,
     user::User,
@@ -67,7 +69,7 @@ pub struct Application {
     pub guild_id: Option<GuildId>,
     /// a partial object of the associated guild
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub guild: Option<Guild>,
+    pub guild: Option<PartialGuild>,
     /// if this application is a game sold on Discord, this field will be the id
     /// of the "Game SKU" that is created, if exists
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -0,0 +1,345 @@
+//! Compares local command definitions with the commands registered on Discord,
+//! so a bulk overwrite is only sent when something actually changed.
+//!
+//! Both sides are normalized first: read-only fields (`id`, `version`, ...) are
+//! dropped, fields equal to Discord's defaults are removed and unordered lists
+//! are sorted.
+
+use std::fmt;
+
+use serde_json::{Map, Value as JsonValue};
+use turbopack_binding::turbo::tasks as turbo_tasks;
+
+const CHAT_INPUT: u64 = 1;
+
+/// Commands are identified by their name and type, as a user and a chat input
+/// command can share a name
+fn command_key(command: &JsonValue) -> (u64, &str) {
+    (
+        command
+            .get("type")
+            .and_then(JsonValue::as_u64)
+            .unwrap_or(CHAT_INPUT),
+        command
+            .get("name")
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default(),
+    )
+}
+
+pub fn normalize_command(command: &JsonValue) -> JsonValue {
+    let Some(command) = command.as_object() else {
+        return command.clone();
+    };
+    let mut normalized = Map::new();
+
+    normalized.insert(
+        "type".into(),
+        command
+            .get("type")
+            .and_then(JsonValue::as_u64)
+            .unwrap_or(CHAT_INPUT)
+            .into(),
+    );
+    copy(&mut normalized, command, "name");
+    // Discord returns an empty description for user and message commands
+    normalized.insert(
+        "description".into(),
+        command
+            .get("description")
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default()
+            .into(),
+    );
+    copy_localizations(&mut normalized, command, "name_localizations");
+    copy_localizations(&mut normalized, command, "description_localizations");
+    copy_options(&mut normalized, command);
+
+    // Permissions are serialized as a string but can be given as a number
+    match command.get("default_member_permissions") {
+        Some(JsonValue::String(permissions)) => {
+            normalized.insert(
+                "default_member_permissions".into(),
+                permissions.clone().into(),
+            );
+        }
+        Some(JsonValue::Number(permissions)) => {
+            normalized.insert(
+                "default_member_permissions".into(),
+                permissions.to_string().into(),
+            );
+        }
+        _ => {}
+    }
+    copy_unless_default(&mut normalized, command, "dm_permission", true.into());
+    copy_unless_default(&mut normalized, command, "nsfw", false.into());
+    copy_sorted_unless_default(
+        &mut normalized,
+        command,
+        "integration_types",
+        JsonValue::Array(vec![0.into()]),
+    );
+    copy_sorted_unless_default(&mut normalized, command, "contexts", JsonValue::Null);
+
+    JsonValue::Object(normalized)
+}
+
+fn normalize_option(option: &JsonValue) -> JsonValue {
+    let Some(option) = option.as_object() else {
+        return option.clone();
+    };
+    let mut normalized = Map::new();
+
+    copy(&mut normalized, option, "type");
+    copy(&mut normalized, option, "name");
+    copy(&mut normalized, option, "description");
+    copy_localizations(&mut normalized, option, "name_localizations");
+    copy_localizations(&mut normalized, option, "description_localizations");
+    copy_unless_default(&mut normalized, option, "required", false.into());
+    copy_unless_default(&mut normalized, option, "autocomplete", false.into());
+    copy_options(&mut normalized, option);
+    copy_sorted_unless_default(
+        &mut normalized,
+        option,
+        "channel_types",
+        JsonValue::Array(vec![]),
+    );
+
+    if let Some(JsonValue::Array(choices)) = option.get("choices") {
+        if !choices.is_empty() {
+            let choices = choices
+                .iter()
+                .map(|choice| {
+                    let mut normalized = Map::new();
+                    if let Some(choice) = choice.as_object() {
+                        copy(&mut normalized, choice, "name");
+                        copy_localizations(&mut normalized, choice, "name_localizations");
+                        copy(&mut normalized, choice, "value");
+                    }
+                    JsonValue::Object(normalized)
+                })
+                .collect();
+            normalized.insert("choices".into(), JsonValue::Array(choices));
+        }
+    }
+
+    // `1` and `1.0` are the same value, but not the same JSON number
+    for key in ["min_value", "max_value"] {
+        if let Some(value) = option.get(key).and_then(JsonValue::as_f64) {
+            normalized.insert(key.into(), value.into());
+        }
+    }
+    for key in ["min_length", "max_length"] {
+        if let Some(value) = option.get(key).and_then(JsonValue::as_u64) {
+            normalized.insert(key.into(), value.into());
+        }
+    }
+
+    JsonValue::Object(normalized)
+}
+
+fn copy(normalized: &mut Map<String, JsonValue>, source: &Map<String, JsonValue>, key: &str) {
+    if let Some(value) = source.get(key).filter(|value| !value.is_null()) {
+        normalized.insert(key.into(), value.clone());
+    }
+}
+
+fn copy_unless_default(
+    normalized: &mut Map<String, JsonValue>,
+    source: &Map<String, JsonValue>,
+    key: &str,
+    default: JsonValue,
+) {
+    if let Some(value) = source
+        .get(key)
+        .filter(|value| !value.is_null() && **value != default)
+    {
+        normalized.insert(key.into(), value.clone());
+    }
+}
+
+fn copy_sorted_unless_default(
+    normalized: &mut Map<String, JsonValue>,
+    source: &Map<String, JsonValue>,
+    key: &str,
+    default: JsonValue,
+) {
+    if let Some(JsonValue::Array(values)) = source.get(key) {
+        let mut values = values.clone();
+        values.sort_by_key(|value| value.as_u64());
+
+        let values = JsonValue::Array(values);
+        if values != default {
+            normalized.insert(key.into(), values);
+        }
+    }
+}
+
+fn copy_localizations(
+    normalized: &mut Map<String, JsonValue>,
+    source: &Map<String, JsonValue>,
+    key: &str,
+) {
+    if let Some(JsonValue::Object(localizations)) = source.get(key) {
+        if !localizations.is_empty() {
+            normalized.insert(key.into(), JsonValue::Object(localizations.clone()));
+        }
+    }
+}
+
+fn copy_options(normalized: &mut Map<String, JsonValue>, source: &Map<String, JsonValue>) {
+    if let Some(JsonValue::Array(options)) = source.get("options") {
+        if !options.is_empty() {
+            normalized.insert(
+                "options".into(),
+                JsonValue::Array(options.iter().map(normalize_option).collect()),
+            );
+        }
+    }
+}
+
+/// The names of the commands that a bulk overwrite would create, update or
+/// delete
+#[turbo_tasks::value(shared, eq = "manual")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandsDiff {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+impl CommandsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+    }
+}
+
+impl fmt::Display for CommandsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "  no changes");
+        }
+
+        for name in &self.created {
+            writeln!(f, "  + {name}")?;
+        }
+        for name in &self.updated {
+            writeln!(f, "  ~ {name}")?;
+        }
+        for name in &self.deleted {
+            writeln!(f, "  - {name}")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn diff_commands(local: &[JsonValue], registered: &[JsonValue]) -> CommandsDiff {
+    let mut diff = CommandsDiff::default();
+
+    for command in local {
+        let key = command_key(command);
+
+        match registered
+            .iter()
+            .find(|registered| command_key(registered) == key)
+        {
+            Some(registered) => {
+                if normalize_command(command) != normalize_command(registered) {
+                    diff.updated.push(key.1.to_string());
+                }
+            }
+            None => diff.created.push(key.1.to_string()),
+        }
+    }
+
+    for command in registered {
+        let key = command_key(command);
+
+        if !local.iter().any(|local| command_key(local) == key) {
+            diff.deleted.push(key.1.to_string());
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{diff_commands, normalize_command, CommandsDiff};
+
+    #[test]
+    fn test_normalize_command_ignores_defaults_and_read_only_fields() {
+        let local = json!({
+            "name": "ping",
+            "description": "Replies with pong",
+            "options": [{ "type": 4, "name": "times", "description": "How often", "min_value": 1 }]
+        });
+        let registered = json!({
+            "id": "1234567890123456789",
+            "application_id": "1234567890123456789",
+            "version": "1234567890123456789",
+            "type": 1,
+            "name": "ping",
+            "description": "Replies with pong",
+            "name_localizations": null,
+            "default_member_permissions": null,
+            "dm_permission": true,
+            "nsfw": false,
+            "integration_types": [0],
+            "contexts": null,
+            "options": [{
+                "type": 4,
+                "name": "times",
+                "description": "How often",
+                "required": false,
+                "min_value": 1.0
+            }]
+        });
+
+        assert_eq!(normalize_command(&local), normalize_command(&registered));
+    }
+
+    #[test]
+    fn test_diff_commands() {
+        let local = vec![
+            json!({ "name": "ping", "description": "Replies with pong" }),
+            json!({ "name": "ban", "description": "Bans a member" }),
+            json!({ "name": "kick", "description": "Kicks a member" }),
+        ];
+        let registered = vec![
+            json!({ "id": "1", "type": 1, "name": "ping", "description": "Replies with pong" }),
+            json!({ "id": "2", "type": 1, "name": "ban", "description": "Hello world!" }),
+            json!({ "id": "3", "type": 1, "name": "echo", "description": "Echoes" }),
+        ];
+
+        assert_eq!(
+            diff_commands(&local, &registered),
+            CommandsDiff {
+                created: vec!["kick".into()],
+                updated: vec!["ban".into()],
+                deleted: vec!["echo".into()],
+            }
+        );
+        assert!(diff_commands(&local[..1], &registered[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_diff_commands_with_same_name_different_type() {
+        let local = vec![
+            json!({ "name": "info" , "description": "Shows info" }),
+            json!({ "type": 2, "name": "info" }),
+        ];
+        let registered = vec![json!({ "type": 1, "name": "info", "description": "Shows info" })];
+
+        assert_eq!(
+            diff_commands(&local, &registered),
+            CommandsDiff {
+                created: vec!["info".into()],
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,582 @@
+//! Checks application commands against the limits Discord enforces, so that
+//! mistakes can be reported before the commands are registered.
+//!
+//! See <https://discord.com/developers/docs/interactions/application-commands#application-command-object>
+
+use super::command::{
+    ApplicationCommand, ApplicationCommandOption, ApplicationCommandOptionChoiceValue,
+    ApplicationCommandOptionType, ApplicationCommandType, Localizations, CHAT_INPUT_NAME,
+};
+use crate::locales::is_locale;
+
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_DESCRIPTION_LENGTH: usize = 100;
+pub const MAX_OPTIONS: usize = 25;
+pub const MAX_CHOICES: usize = 25;
+pub const MAX_CHOICE_NAME_LENGTH: usize = 100;
+pub const MAX_CHOICE_STRING_LENGTH: usize = 100;
+pub const MAX_STRING_LENGTH: u32 = 6000;
+/// Combined length of all names, descriptions and choice values of a command
+pub const MAX_COMMAND_LENGTH: usize = 4000;
+
+/// Returns a message for every limit the command violates, an empty list means
+/// the command is valid
+pub fn validate_application_command(command: &ApplicationCommand) -> Vec<String> {
+    let mut errors = vec![];
+
+    match command.r#type {
+        ApplicationCommandType::ChatInput => {
+            validate_chat_input_name(&command.name, "name", &mut errors);
+            validate_localizations(
+                command.name_localizations.as_ref(),
+                "name_localizations",
+                validate_chat_input_name,
+                &mut errors,
+            );
+            validate_description(&command.description, "description", &mut errors);
+            validate_localizations(
+                command.description_localizations.as_ref(),
+                "description_localizations",
+                validate_description,
+                &mut errors,
+            );
+
+            if let Some(options) = &command.options {
+                validate_options(options, "options", 0, &mut errors);
+            }
+        }
+        ApplicationCommandType::User | ApplicationCommandType::Message => {
+            validate_name_length(&command.name, "name", &mut errors);
+            validate_localizations(
+                command.name_localizations.as_ref(),
+                "name_localizations",
+                validate_name_length,
+                &mut errors,
+            );
+            if !command.description.is_empty() {
+                errors
+                    .push("`description` must be empty for user and message commands".to_string());
+            }
+            if command
+                .options
+                .as_ref()
+                .is_some_and(|options| !options.is_empty())
+            {
+                errors.push("`options` are only allowed for chat input commands".to_string());
+            }
+        }
+    }
+
+    let length = command_length(command);
+    if length > MAX_COMMAND_LENGTH {
+        errors.push(format!(
+            "the combined length of all names, descriptions and choices is {length}, which is \
+             more than {MAX_COMMAND_LENGTH}"
+        ));
+    }
+
+    errors
+}
+
+fn validate_chat_input_name(name: &str, path: &str, errors: &mut Vec<String>) {
+    if !CHAT_INPUT_NAME.is_match(name) {
+        errors.push(format!(
+            "`{path}` must be 1 to {MAX_NAME_LENGTH} letters, numbers, `-` or `_`, got \"{name}\""
+        ));
+    } else if name.to_lowercase() != name {
+        errors.push(format!("`{path}` must be lowercase, got \"{name}\""));
+    }
+}
+
+fn validate_name_length(name: &str, path: &str, errors: &mut Vec<String>) {
+    let length = name.chars().count();
+    if length == 0 || length > MAX_NAME_LENGTH {
+        errors.push(format!(
+            "`{path}` must be between 1 and {MAX_NAME_LENGTH} characters long"
+        ));
+    }
+}
+
+fn validate_choice_name(name: &str, path: &str, errors: &mut Vec<String>) {
+    let length = name.chars().count();
+    if length == 0 || length > MAX_CHOICE_NAME_LENGTH {
+        errors.push(format!(
+            "`{path}` must be between 1 and {MAX_CHOICE_NAME_LENGTH} characters long"
+        ));
+    }
+}
+
+/// Localized strings are held to the same rules as the string they translate
+fn validate_localizations(
+    localizations: Option<&Localizations>,
+    path: &str,
+    validate: fn(&str, &str, &mut Vec<String>),
+    errors: &mut Vec<String>,
+) {
+    for (locale, value) in localizations.into_iter().flatten() {
+        if is_locale(locale) {
+            validate(value, &format!("{path}.{locale}"), errors);
+        } else {
+            errors.push(format!("`{path}` has an unknown locale \"{locale}\""));
+        }
+    }
+}
+
+fn validate_description(description: &str, path: &str, errors: &mut Vec<String>) {
+    let length = description.chars().count();
+    if length == 0 || length > MAX_DESCRIPTION_LENGTH {
+        errors.push(format!(
+            "`{path}` must be between 1 and {MAX_DESCRIPTION_LENGTH} characters long"
+        ));
+    }
+}
+
+/// `depth` is `0` for the options of the command, `1` for the options of a
+/// subcommand (group) and `2` for the options of a subcommand in a group
+fn validate_options(
+    options: &[ApplicationCommandOption],
+    path: &str,
+    depth: u8,
+    errors: &mut Vec<String>,
+) {
+    if options.len() > MAX_OPTIONS {
+        errors.push(format!(
+            "`{path}` can't have more than {MAX_OPTIONS} options, got {}",
+            options.len()
+        ));
+    }
+
+    let subcommands = options
+        .iter()
+        .filter(|option| is_subcommand(option.r#type))
+        .count();
+    if subcommands > 0 && subcommands < options.len() {
+        errors.push(format!("`{path}` can't mix subcommands with other options"));
+    }
+
+    let mut optional_seen = false;
+    for (index, option) in options.iter().enumerate() {
+        let path = format!("{path}[{index}]");
+
+        validate_chat_input_name(&option.name, &format!("{path}.name"), errors);
+        validate_localizations(
+            option.name_localizations.as_ref(),
+            &format!("{path}.name_localizations"),
+            validate_chat_input_name,
+            errors,
+        );
+        validate_description(&option.description, &format!("{path}.description"), errors);
+        validate_localizations(
+            option.description_localizations.as_ref(),
+            &format!("{path}.description_localizations"),
+            validate_description,
+            errors,
+        );
+
+        if options[..index]
+            .iter()
+            .any(|other| other.name == option.name)
+        {
+            errors.push(format!(
+                "`{path}.name` \"{}\" is used by another option",
+                option.name
+            ));
+        }
+
+        if is_subcommand(option.r#type) {
+            validate_subcommand(option, &path, depth, errors);
+            continue;
+        }
+
+        if option.required.unwrap_or(false) {
+            if optional_seen {
+                errors.push(format!(
+                    "`{path}` is required, so it must come before all optional options"
+                ));
+            }
+        } else {
+            optional_seen = true;
+        }
+
+        validate_option_values(option, &path, errors);
+    }
+}
+
+fn validate_subcommand(
+    option: &ApplicationCommandOption,
+    path: &str,
+    depth: u8,
+    errors: &mut Vec<String>,
+) {
+    let is_group = option.r#type == ApplicationCommandOptionType::SubCommandGroup;
+
+    // Subcommand groups can only be used at the top level and subcommands at most
+    // inside of a group
+    if (is_group && depth > 0) || depth > 1 {
+        errors.push(format!("`{path}` is nested too deeply"));
+        return;
+    }
+
+    let options = option.options.as_deref().unwrap_or_default();
+    if is_group {
+        if options.is_empty() {
+            errors.push(format!(
+                "`{path}` is a subcommand group without subcommands"
+            ));
+        }
+        if options
+            .iter()
+            .any(|option| option.r#type != ApplicationCommandOptionType::SubCommand)
+        {
+            errors.push(format!(
+                "`{path}.options` of a subcommand group can only be subcommands"
+            ));
+            return;
+        }
+    } else if options.iter().any(|option| is_subcommand(option.r#type)) {
+        errors.push(format!(
+            "`{path}.options` of a subcommand can't contain subcommands"
+        ));
+        return;
+    }
+
+    validate_options(options, &format!("{path}.options"), depth + 1, errors);
+}
+
+fn validate_option_values(option: &ApplicationCommandOption, path: &str, errors: &mut Vec<String>) {
+    let is_numeric = matches!(
+        option.r#type,
+        ApplicationCommandOptionType::Integer | ApplicationCommandOptionType::Number
+    );
+    let is_string = option.r#type == ApplicationCommandOptionType::String;
+
+    if !option.choices.is_empty() {
+        if !is_numeric && !is_string {
+            errors.push(format!(
+                "`{path}.choices` are only allowed for string, integer and number options"
+            ));
+        }
+        if option.autocomplete.unwrap_or(false) {
+            errors.push(format!(
+                "`{path}` can't have both `choices` and `autocomplete`"
+            ));
+        }
+        if option.choices.len() > MAX_CHOICES {
+            errors.push(format!(
+                "`{path}.choices` can't have more than {MAX_CHOICES} choices, got {}",
+                option.choices.len()
+            ));
+        }
+
+        for (index, choice) in option.choices.iter().enumerate() {
+            let path = format!("{path}.choices[{index}]");
+
+            validate_choice_name(&choice.name, &format!("{path}.name"), errors);
+            validate_localizations(
+                choice.name_localizations.as_ref(),
+                &format!("{path}.name_localizations"),
+                validate_choice_name,
+                errors,
+            );
+
+            let matches_type = match &choice.value {
+                ApplicationCommandOptionChoiceValue::String(value) => {
+                    if value.chars().count() > MAX_CHOICE_STRING_LENGTH {
+                        errors.push(format!(
+                            "`{path}.value` can't be longer than {MAX_CHOICE_STRING_LENGTH} \
+                             characters"
+                        ));
+                    }
+                    is_string
+                }
+                ApplicationCommandOptionChoiceValue::Integer(_) => is_numeric,
+                ApplicationCommandOptionChoiceValue::Double(_) => {
+                    option.r#type == ApplicationCommandOptionType::Number
+                }
+            };
+            if !matches_type {
+                errors.push(format!(
+                    "`{path}.value` doesn't match the type of the option"
+                ));
+            }
+        }
+    }
+
+    if option.min_value.is_some() || option.max_value.is_some() {
+        if !is_numeric {
+            errors.push(format!(
+                "`{path}.min_value` and `{path}.max_value` are only allowed for integer and \
+                 number options"
+            ));
+        } else if option.r#type == ApplicationCommandOptionType::Integer
+            && [option.min_value, option.max_value]
+                .iter()
+                .flatten()
+                .any(|value| value.fract() != 0.0)
+        {
+            errors.push(format!(
+                "`{path}.min_value` and `{path}.max_value` must be whole numbers for integer \
+                 options"
+            ));
+        }
+
+        if let (Some(min), Some(max)) = (option.min_value, option.max_value) {
+            if min > max {
+                errors.push(format!(
+                    "`{path}.min_value` can't be greater than `{path}.max_value`"
+                ));
+            }
+        }
+    }
+
+    if option.min_length.is_some() || option.max_length.is_some() {
+        if !is_string {
+            errors.push(format!(
+                "`{path}.min_length` and `{path}.max_length` are only allowed for string options"
+            ));
+        }
+        if option
+            .min_length
+            .is_some_and(|min_length| min_length > MAX_STRING_LENGTH)
+        {
+            errors.push(format!(
+                "`{path}.min_length` must be between 0 and {MAX_STRING_LENGTH}"
+            ));
+        }
+        if option
+            .max_length
+            .is_some_and(|max_length| max_length == 0 || max_length > MAX_STRING_LENGTH)
+        {
+            errors.push(format!(
+                "`{path}.max_length` must be between 1 and {MAX_STRING_LENGTH}"
+            ));
+        }
+        if let (Some(min), Some(max)) = (option.min_length, option.max_length) {
+            if min > max {
+                errors.push(format!(
+                    "`{path}.min_length` can't be greater than `{path}.max_length`"
+                ));
+            }
+        }
+    }
+
+    if option.channel_types.is_some() && option.r#type != ApplicationCommandOptionType::Channel {
+        errors.push(format!(
+            "`{path}.channel_types` are only allowed for channel options"
+        ));
+    }
+}
+
+fn is_subcommand(r#type: ApplicationCommandOptionType) -> bool {
+    matches!(
+        r#type,
+        ApplicationCommandOptionType::SubCommand | ApplicationCommandOptionType::SubCommandGroup
+    )
+}
+
+fn command_length(command: &ApplicationCommand) -> usize {
+    fn options_length(options: &[ApplicationCommandOption]) -> usize {
+        options
+            .iter()
+            .map(|option| {
+                option.name.chars().count()
+                    + option.description.chars().count()
+                    + option
+                        .choices
+                        .iter()
+                        .map(|choice| {
+                            choice.name.chars().count()
+                                + match &choice.value {
+                                    ApplicationCommandOptionChoiceValue::String(value) => {
+                                        value.chars().count()
+                                    }
+                                    value => serde_json::to_string(value)
+                                        .map(|value| value.len())
+                                        .unwrap_or_default(),
+                                }
+                        })
+                        .sum::<usize>()
+                    + options_length(option.options.as_deref().unwrap_or_default())
+            })
+            .sum()
+    }
+
+    command.name.chars().count()
+        + command.description.chars().count()
+        + options_length(command.options.as_deref().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::validate_application_command;
+    use crate::application::command::ApplicationCommand;
+
+    fn validate(command: serde_json::Value) -> Vec<String> {
+        let command = serde_json::from_value::<ApplicationCommand>(command).unwrap();
+        validate_application_command(&command)
+    }
+
+    #[test]
+    fn test_valid_command() {
+        let errors = validate(json!({
+            "name": "ban",
+            "description": "Bans a member",
+            "options": [
+                { "type": 6, "name": "member", "description": "The member", "required": true },
+                {
+                    "type": 4,
+                    "name": "days",
+                    "description": "Days of messages to delete",
+                    "min_value": 0,
+                    "max_value": 7,
+                    "choices": [{ "name": "None", "value": 0 }, { "name": "Week", "value": 7 }]
+                },
+                { "type": 3, "name": "reason", "description": "Why", "max_length": 512 }
+            ]
+        }));
+
+        assert_eq!(errors, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_names_and_descriptions() {
+        let errors = validate(json!({
+            "name": "Ban",
+            "description": "",
+            "options": [{ "type": 3, "name": "has spaces", "description": "x".repeat(101) }]
+        }));
+
+        assert_eq!(
+            errors,
+            vec![
+                "`name` must be lowercase, got \"Ban\"",
+                "`description` must be between 1 and 100 characters long",
+                "`options[0].name` must be 1 to 32 letters, numbers, `-` or `_`, got \"has \
+                 spaces\"",
+                "`options[0].description` must be between 1 and 100 characters long",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_user_command() {
+        assert!(validate(json!({ "type": 2, "name": "Show Profile" })).is_empty());
+        assert_eq!(
+            validate(json!({ "type": 3, "name": "Report", "description": "Reports" })),
+            vec!["`description` must be empty for user and message commands"]
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let options = (0..26)
+            .map(|index| json!({ "type": 5, "name": format!("o{index}"), "description": "d" }))
+            .collect::<Vec<_>>();
+        let choices = (0..26)
+            .map(|index| json!({ "name": format!("c{index}"), "value": format!("c{index}") }))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            validate(json!({ "name": "many", "description": "d", "options": options })),
+            vec!["`options` can't have more than 25 options, got 26"]
+        );
+        assert_eq!(
+            validate(json!({
+                "name": "many",
+                "description": "d",
+                "options": [{ "type": 3, "name": "o", "description": "d", "choices": choices }]
+            })),
+            vec!["`options[0].choices` can't have more than 25 choices, got 26"]
+        );
+    }
+
+    #[test]
+    fn test_option_values() {
+        let errors = validate(json!({
+            "name": "values",
+            "description": "d",
+            "options": [
+                { "type": 3, "name": "optional", "description": "d" },
+                { "type": 4, "name": "required", "description": "d", "required": true, "min_value": 10, "max_value": 1.5 },
+                { "type": 5, "name": "flag", "description": "d", "min_length": 1 },
+                { "type": 4, "name": "choice", "description": "d", "choices": [{ "name": "a", "value": "a" }] }
+            ]
+        }));
+
+        assert_eq!(
+            errors,
+            vec![
+                "`options[1]` is required, so it must come before all optional options",
+                "`options[1].min_value` and `options[1].max_value` must be whole numbers for \
+                 integer options",
+                "`options[1].min_value` can't be greater than `options[1].max_value`",
+                "`options[2].min_length` and `options[2].max_length` are only allowed for string \
+                 options",
+                "`options[3].choices[0].value` doesn't match the type of the option",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_localizations() {
+        assert!(validate(json!({
+            "name": "ban",
+            "name_localizations": { "de": "bannen", "es-419": "banear" },
+            "description": "Bans a member",
+            "description_localizations": { "de": "Bannt ein Mitglied" }
+        }))
+        .is_empty());
+        assert_eq!(
+            validate(json!({
+                "name": "ban",
+                "name_localizations": { "de": "Bannen", "german": "bannen" },
+                "description": "Bans a member",
+                "options": [{
+                    "type": 3,
+                    "name": "reason",
+                    "description": "Why",
+                    "description_localizations": { "en-GB": "" },
+                    "choices": [{ "name": "Spam", "name_localizations": { "fr": "" }, "value": "spam" }]
+                }]
+            })),
+            vec![
+                "`name_localizations.de` must be lowercase, got \"Bannen\"",
+                "`name_localizations` has an unknown locale \"german\"",
+                "`options[0].description_localizations.en-GB` must be between 1 and 100 \
+                 characters long",
+                "`options[0].choices[0].name_localizations.fr` must be between 1 and 100 \
+                 characters long",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subcommands() {
+        let subcommand = json!({ "type": 1, "name": "add", "description": "d" });
+
+        assert!(validate(json!({
+            "name": "role",
+            "description": "d",
+            "options": [{ "type": 2, "name": "group", "description": "d", "options": [subcommand] }]
+        }))
+        .is_empty());
+        assert_eq!(
+            validate(json!({
+                "name": "role",
+                "description": "d",
+                "options": [subcommand, { "type": 3, "name": "name", "description": "d" }]
+            })),
+            vec!["`options` can't mix subcommands with other options"]
+        );
+        assert_eq!(
+            validate(json!({
+                "name": "role",
+                "description": "d",
+                "options": [{ "type": 1, "name": "add", "description": "d", "options": [subcommand] }]
+            })),
+            vec!["`options[0].options` of a subcommand can't contain subcommands"]
+        );
+    }
+}
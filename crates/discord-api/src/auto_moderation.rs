@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr};
+
+use crate::{
+    id::{ChannelId, GuildId, RoleId, RuleId, UserId},
+    impl_serde_for_integer_enum,
+};
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AutoModerationRule {
+    /// the id of this rule
+    pub id: RuleId,
+    /// the id of the guild which this rule belongs to
+    pub guild_id: GuildId,
+    /// the rule name
+    pub name: RcStr,
+    /// the user which first created this rule
+    pub creator_id: UserId,
+    /// the rule event type
+    pub event_type: AutoModerationEventType,
+    /// the rule trigger type
+    pub trigger_type: AutoModerationTriggerType,
+    /// the rule trigger metadata
+    pub trigger_metadata: AutoModerationTriggerMetadata,
+    /// the actions which will execute when the rule is triggered
+    pub actions: Vec<AutoModerationAction>,
+    /// whether the rule is enabled
+    pub enabled: bool,
+    /// the role ids that should not be affected by the rule (Maximum of 20)
+    pub exempt_roles: Vec<RoleId>,
+    /// the channel ids that should not be affected by the rule (Maximum of 50)
+    pub exempt_channels: Vec<ChannelId>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AutoModerationTriggerMetadata {
+    /// substrings which will be searched for in content (Maximum of 1000)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keyword_filter: Vec<RcStr>,
+    /// regular expression patterns which will be matched against content
+    /// (Maximum of 10)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regex_patterns: Vec<RcStr>,
+    /// the internally pre-defined wordsets which will be searched for in
+    /// content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub presets: Vec<AutoModerationKeywordPresetType>,
+    /// substrings which should not trigger the rule (Maximum of 100 or 1000)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_list: Vec<RcStr>,
+    /// total number of unique role and user mentions allowed per message
+    /// (Maximum of 50)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mention_total_limit: Option<u8>,
+    /// whether to automatically detect mention raids
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mention_raid_protection_enabled: Option<bool>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AutoModerationAction {
+    /// the type of action
+    pub r#type: AutoModerationActionType,
+    /// additional metadata needed during execution for this specific action
+    /// type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<AutoModerationActionMetadata>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AutoModerationActionMetadata {
+    /// channel to which user content should be logged
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<ChannelId>,
+    /// timeout duration in seconds (Maximum of 2419200 seconds (4 weeks))
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<u32>,
+    /// additional explanation that will be shown to members whenever their
+    /// message is blocked (Maximum of 150 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_message: Option<RcStr>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoModerationEventType {
+    /// when a member sends or edits a message in the guild
+    MessageSend = 1,
+    /// when a member edits their profile
+    MemberUpdate = 2,
+}
+
+impl_serde_for_integer_enum!(AutoModerationEventType, u8, [MessageSend, MemberUpdate]);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoModerationTriggerType {
+    /// check if content contains words from a user defined list of keywords
+    Keyword = 1,
+    /// check if content represents generic spam
+    Spam = 3,
+    /// check if content contains words from internal pre-defined wordsets
+    KeywordPreset = 4,
+    /// check if content contains more unique mentions than allowed
+    MentionSpam = 5,
+    /// check if member profile contains words from a user defined list of
+    /// keywords
+    MemberProfile = 6,
+}
+
+impl_serde_for_integer_enum!(
+    AutoModerationTriggerType,
+    u8,
+    [Keyword, Spam, KeywordPreset, MentionSpam, MemberProfile]
+);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoModerationKeywordPresetType {
+    /// words that may be considered forms of swearing or cursing
+    Profanity = 1,
+    /// words that refer to sexually explicit behavior or activity
+    SexualContent = 2,
+    /// personal insults or words that may be considered hate speech
+    Slurs = 3,
+}
+
+impl_serde_for_integer_enum!(
+    AutoModerationKeywordPresetType,
+    u8,
+    [Profanity, SexualContent, Slurs]
+);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoModerationActionType {
+    /// blocks a member's message and prevents it from being posted
+    BlockMessage = 1,
+    /// logs user content to a specified channel
+    SendAlertMessage = 2,
+    /// timeout user for a specified duration
+    Timeout = 3,
+    /// prevents a member from using text, voice, or other interactions
+    BlockMemberInteraction = 4,
+}
+
+impl_serde_for_integer_enum!(
+    AutoModerationActionType,
+    u8,
+    [
+        BlockMessage,
+        SendAlertMessage,
+        Timeout,
+        BlockMemberInteraction
+    ]
+);
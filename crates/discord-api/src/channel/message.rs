@@ -3,11 +3,14 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr, TaskInput};
 
-use super::{embed::Embed, Attachment};
+use super::{embed::Embed, AttachmentOther, Channel};
 use crate::{
+    emoji::Emoji,
     id::{ChannelId, InteractionId, MessageId, RoleId},
-    impl_deserialize_from_bits,
+    impl_deserialize_from_bits, impl_serialize_to_bits,
     interactions::InteractionType,
+    poll::Poll,
+    sticker::{Sticker, StickerItem},
     user::User,
 };
 
@@ -39,7 +42,7 @@ pub struct Message {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mention_channels: Option<Vec<ChannelMention>>,
     /// any attached files
-    pub attachments: Vec<Attachment>,
+    pub attachments: Vec<AttachmentOther>,
     /// any embedded content
     pub embeds: Vec<Embed>,
     /// reactions to the message
@@ -47,7 +50,7 @@ pub struct Message {
     pub reactions: Option<Vec<Reaction>>,
     /// used for validating a message was sent
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub nonce: Option<RcStr>,
+    pub nonce: Option<Nonce>,
     /// whether this message is pinned
     pub pinned: bool,
     /// if the message is generated by a webhook, this is the webhook's id
@@ -93,7 +96,7 @@ pub struct Message {
     pub components: Option<Vec<MessageComponent>>,
     /// sent if the message contains stickers
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub sticker_items: Option<Vec<MessageStickerItem>>,
+    pub sticker_items: Option<Vec<StickerItem>>,
     /// Deprecated the stickers sent with the message
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stickers: Option<Vec<Sticker>>,
@@ -117,67 +120,71 @@ pub struct Message {
     pub call: Option<MessageCall>,
 }
 
-// TODO implement these (this only exists so deserialization works)
+/// Discord sends back the nonce in the type it was given
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
-pub struct MessageComponent {
-    value: bool,
-}
-#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
-pub struct ChannelMention {
-    value: bool,
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Nonce {
+    Integer(i64),
+    String(RcStr),
 }
+
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Reaction {
-    value: bool,
+    /// total number of times this emoji has been used to react (including super
+    /// reacts)
+    pub count: u32,
+    /// reaction count details object
+    pub count_details: ReactionCountDetails,
+    /// whether the current user reacted using this emoji
+    pub me: bool,
+    /// whether the current user super-reacted using this emoji
+    pub me_burst: bool,
+    /// emoji information
+    pub emoji: Emoji,
+    /// HEX colors used for super reaction
+    pub burst_colors: Vec<RcStr>,
 }
+
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
-pub struct MessageReference {
-    value: bool,
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReactionCountDetails {
+    /// count of super reactions
+    pub burst: u32,
+    /// count of normal reactions
+    pub normal: u32,
 }
+
+// TODO implement these, the received JSON is kept as is until then
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
-pub struct MessageApplication {
-    value: bool,
-}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct MessageComponent(pub JsonValue);
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
-pub struct Channel {
-    value: bool,
-}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ChannelMention(pub JsonValue);
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
-pub struct MessageStickerItem {
-    value: bool,
-}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct MessageReference(pub JsonValue);
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
-pub struct Sticker {
-    value: bool,
-}
-#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
-pub struct RoleSubscriptionData {
-    value: bool,
-}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct MessageApplication(pub JsonValue);
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
-pub struct ResolvedData {
-    value: bool,
-}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct RoleSubscriptionData(pub JsonValue);
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
-pub struct Poll {
-    value: bool,
-}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ResolvedData(pub JsonValue);
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
-pub struct MessageCall {
-    value: bool,
-}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct MessageCall(pub JsonValue);
 
 bitflags! {
     #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
@@ -248,7 +255,7 @@ impl_deserialize_from_bits!(MessageActivityType, u8);
 
 bitflags! {
   #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-  #[derive(Serialize, Default, TaskInput)]
+  #[derive(Default, TaskInput)]
   pub struct MessageFlags: u32 {
       /// this message has been published to subscribed channels (via Channel Following)
       const CROSSPOSTED = 1 << 0;
@@ -276,6 +283,7 @@ bitflags! {
 }
 
 impl_deserialize_from_bits!(MessageFlags, u32);
+impl_serialize_to_bits!(MessageFlags);
 
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
@@ -304,3 +312,20 @@ pub struct InnerMessageInteractionMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     interacted_message_id: Option<MessageId>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Reaction;
+    use crate::utils::assert_round_trip;
+
+    #[test]
+    fn test_reaction_round_trip() {
+        let reactions =
+            assert_round_trip::<Vec<Reaction>>(include_str!("../../fixtures/reactions.json"));
+
+        assert_eq!(reactions[0].count, 3);
+        assert_eq!(reactions[0].count_details.burst, 1);
+        assert_eq!(reactions[0].emoji.name.as_deref(), Some("🔥"));
+        assert!(reactions[1].emoji.id.is_some());
+    }
+}
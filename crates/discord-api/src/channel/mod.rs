@@ -1,46 +1,410 @@
 use std::fmt::Display;
 
+use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr, TaskInput, Vc};
 
 pub mod embed;
 pub mod message;
 
-use crate::id::{AttachmentId, RoleId, UserId};
+use crate::{
+    guild::GuildMember,
+    id::{
+        ApplicationId, AttachmentId, ChannelId, EmojiId, ForumTagId, GenericId, GuildId, MessageId,
+        RoleId, UserId,
+    },
+    impl_deserialize_from_bits, impl_serde_for_integer_enum, impl_serialize_to_bits,
+    permissions::PermissionFlags,
+    user::User,
+};
 
+/// Every type of channel is represented by this struct, the fields that are
+/// set depend on the type. Threads are channels too, with `thread_metadata`
+/// and, for threads the current user has joined, `member`.
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, TaskInput, Hash)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Channel {
+    /// the id of this channel
+    pub id: ChannelId,
+    /// the type of channel
+    pub r#type: ChannelTypes,
+    /// the id of the guild (may be missing for some channel objects received
+    /// over gateway guild dispatches)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<GuildId>,
+    /// sorting position of the channel (channels with the same position are
+    /// sorted by id)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<i32>,
+    /// explicit permission overwrites for members and roles
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission_overwrites: Option<Vec<PermissionOverwrite>>,
+    /// the name of the channel (1-100 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<RcStr>,
+    /// the channel topic (0-4096 characters for GUILD_FORUM and GUILD_MEDIA
+    /// channels, 0-1024 characters for all others)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<RcStr>,
+    /// whether the channel is nsfw
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nsfw: Option<bool>,
+    /// the id of the last message sent in this channel (or thread for
+    /// GUILD_FORUM or GUILD_MEDIA channels) (may not point to an existing or
+    /// valid message or thread)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_message_id: Option<MessageId>,
+    /// the bitrate (in bits) of the voice channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bitrate: Option<u32>,
+    /// the user limit of the voice channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_limit: Option<u32>,
+    /// amount of seconds a user has to wait before sending another message
+    /// (0-21600)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_per_user: Option<u32>,
+    /// the recipients of the DM
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipients: Option<Vec<User>>,
+    /// icon hash of the group DM
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<RcStr>,
+    /// id of the creator of the group DM or thread
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<UserId>,
+    /// application id of the group DM creator if it is bot-created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_id: Option<ApplicationId>,
+    /// for group DM channels: whether the channel is managed by an application
+    /// via the gdm.join OAuth2 scope
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub managed: Option<bool>,
+    /// for guild channels: id of the parent category for a channel (each parent
+    /// category can contain up to 50 channels), for threads: id of the text
+    /// channel this thread was created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<ChannelId>,
+    /// when the last pinned message was pinned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_pin_timestamp: Option<RcStr>,
+    /// voice region id for the voice channel, automatic when set to null
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rtc_region: Option<RcStr>,
+    /// the camera video quality mode of the voice channel, 1 when not present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video_quality_mode: Option<VideoQualityMode>,
+    /// number of messages (not including the initial message or deleted
+    /// messages) in a thread
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_count: Option<u32>,
+    /// an approximate count of users in a thread, stops counting at 50
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member_count: Option<u32>,
+    /// thread-specific fields not needed by other channels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_metadata: Option<ThreadMetadata>,
+    /// thread member object for the current user, if they have joined the
+    /// thread, only included on certain API endpoints
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<ThreadMember>,
+    /// default duration, copied onto newly created threads, in minutes,
+    /// threads will stop showing in the channel list after the specified
+    /// period of inactivity, can be set to: 60, 1440, 4320, 10080
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_auto_archive_duration: Option<u32>,
+    /// computed permissions for the invoking user in the channel, including
+    /// overwrites, only included when part of the resolved data received on a
+    /// slash command interaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<PermissionFlags>,
+    /// channel flags combined as a bitfield
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<ChannelFlags>,
+    /// number of messages ever sent in a thread, it's similar to message_count
+    /// on message creation, but will not decrement the number when a message
+    /// is deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_message_sent: Option<u32>,
+    /// the set of tags that can be used in a GUILD_FORUM or a GUILD_MEDIA
+    /// channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_tags: Option<Vec<ForumTag>>,
+    /// the IDs of the set of tags that have been applied to a thread in a
+    /// GUILD_FORUM or a GUILD_MEDIA channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applied_tags: Option<Vec<ForumTagId>>,
+    /// the emoji to show in the add reaction button on a thread in a
+    /// GUILD_FORUM or a GUILD_MEDIA channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_reaction_emoji: Option<DefaultReaction>,
+    /// the initial rate_limit_per_user to set on newly created threads in a
+    /// channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_thread_rate_limit_per_user: Option<u32>,
+    /// the default sort order type used to order posts in GUILD_FORUM and
+    /// GUILD_MEDIA channels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_sort_order: Option<SortOrderType>,
+    /// the default forum layout view used to display posts in GUILD_FORUM
+    /// channels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_forum_layout: Option<ForumLayoutType>,
+}
+
+impl Channel {
+    pub fn is_thread(&self) -> bool {
+        self.r#type.is_thread()
+    }
+}
+
+impl ChannelTypes {
+    pub fn is_thread(&self) -> bool {
+        matches!(
+            self,
+            ChannelTypes::AnnouncementThread
+                | ChannelTypes::PublicThread
+                | ChannelTypes::PrivateThread
+        )
+    }
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PermissionOverwrite {
+    /// role or user id
+    pub id: GenericId,
+    /// either 0 (role) or 1 (member)
+    pub r#type: PermissionOverwriteType,
+    /// permission bit set
+    pub allow: PermissionFlags,
+    /// permission bit set
+    pub deny: PermissionFlags,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PermissionOverwriteType {
+    Role,
+    Member,
+    /// a value that was added after this was written
+    Unknown(u8),
+}
+
+impl_serde_for_integer_enum!(PermissionOverwriteType, u8, [Role = 0, Member = 1], Unknown);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VideoQualityMode {
+    /// Discord chooses the quality for optimal performance
+    Auto,
+    /// 720p
+    Full,
+    /// a value that was added after this was written
+    Unknown(u8),
+}
+
+impl_serde_for_integer_enum!(VideoQualityMode, u8, [Auto = 1, Full = 2], Unknown);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrderType {
+    /// Sort forum posts by activity
+    LatestActivity,
+    /// Sort forum posts by creation time (from most recent to oldest)
+    CreationDate,
+    /// a value that was added after this was written
+    Unknown(u8),
+}
+
+impl_serde_for_integer_enum!(
+    SortOrderType,
+    u8,
+    [LatestActivity = 0, CreationDate = 1],
+    Unknown
+);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForumLayoutType {
+    /// No default has been set for forum channel
+    NotSet,
+    /// Display posts as a list
+    ListView,
+    /// Display posts as a collection of tiles
+    GalleryView,
+    /// a value that was added after this was written
+    Unknown(u8),
+}
+
+impl_serde_for_integer_enum!(
+    ForumLayoutType,
+    u8,
+    [NotSet = 0, ListView = 1, GalleryView = 2],
+    Unknown
+);
+
+bitflags! {
+    #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+    #[derive(Default)]
+    pub struct ChannelFlags: u32 {
+        /// this thread is pinned to the top of its parent GUILD_FORUM or
+        /// GUILD_MEDIA channel
+        const PINNED = 1 << 1;
+        /// whether a tag is required to be specified when creating a thread in
+        /// a GUILD_FORUM or a GUILD_MEDIA channel
+        const REQUIRE_TAG = 1 << 4;
+        /// when set hides the embedded media download options, available only
+        /// for media channels
+        const HIDE_MEDIA_DOWNLOAD_OPTIONS = 1 << 15;
+    }
+}
+
+impl_deserialize_from_bits!(ChannelFlags, u32);
+impl_serialize_to_bits!(ChannelFlags);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThreadMetadata {
+    /// whether the thread is archived
+    pub archived: bool,
+    /// the thread will stop showing in the channel list after
+    /// auto_archive_duration minutes of inactivity, can be set to: 60, 1440,
+    /// 4320, 10080
+    pub auto_archive_duration: u32,
+    /// timestamp when the thread's archive status was last changed, used for
+    /// calculating recent activity
+    pub archive_timestamp: RcStr,
+    /// whether the thread is locked; when a thread is locked, only users with
+    /// MANAGE_THREADS can unarchive it
+    pub locked: bool,
+    /// whether non-moderators can add other non-moderators to a thread; only
+    /// available on private threads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invitable: Option<bool>,
+    /// timestamp when the thread was created; only populated for threads
+    /// created after 2022-01-09
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_timestamp: Option<RcStr>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThreadMember {
+    /// the id of the thread, omitted on the member sent within each thread in
+    /// the GUILD_CREATE event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<ChannelId>,
+    /// the id of the user, omitted on the member sent within each thread in the
+    /// GUILD_CREATE event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<UserId>,
+    /// the time the user last joined the thread
+    pub join_timestamp: RcStr,
+    /// any user-thread settings, currently only used for notifications
+    pub flags: u32,
+    /// additional information about the user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<GuildMember>,
+}
+
+/// Threads returned by the list threads endpoints, along with the thread
+/// members of the current user
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThreadList {
+    /// the threads
+    pub threads: Vec<Channel>,
+    /// a thread member object for each returned thread the current user has
+    /// joined
+    pub members: Vec<ThreadMember>,
+    /// whether there are potentially additional threads that could be
+    /// returned on a subsequent call, missing when listing active threads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_more: Option<bool>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ForumTag {
+    /// the id of the tag
+    pub id: ForumTagId,
+    /// the name of the tag (0-20 characters)
+    pub name: RcStr,
+    /// whether this tag can only be added to or removed from threads by a
+    /// member with the MANAGE_THREADS permission
+    pub moderated: bool,
+    /// the id of a guild's custom emoji
+    pub emoji_id: Option<EmojiId>,
+    /// the unicode character of the emoji
+    pub emoji_name: Option<RcStr>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DefaultReaction {
+    /// the id of a guild's custom emoji
+    pub emoji_id: Option<EmojiId>,
+    /// the unicode character of the emoji
+    pub emoji_name: Option<RcStr>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TaskInput, Hash)]
 pub enum ChannelTypes {
     /// a text channel within a server
-    GuildText = 0,
+    GuildText,
     /// a direct message between users
-    DM = 1,
+    DM,
     /// a voice channel within a server
-    GuildVoice = 2,
+    GuildVoice,
     /// a direct message between multiple users
-    GroupDM = 3,
+    GroupDM,
     /// an organizational category that contains up to 50 channels
-    GuildCategory = 4,
+    GuildCategory,
     /// a channel that users can follow and crosspost into their own server
     /// (formerly news channels)
-    GuildAnnouncement = 5,
+    GuildAnnouncement,
     /// a temporary sub-channel within a GUILD_ANNOUNCEMENT channel
-    AnnouncementThread = 10,
+    AnnouncementThread,
     /// a temporary sub-channel within a GUILD_TEXT or GUILD_FORUM channel
-    PublicThread = 11,
+    PublicThread,
     /// a temporary sub-channel within a GUILD_TEXT channel that is only
     /// viewable by those invited and those with the MANAGE_THREADS permission
-    PrivateThread = 12,
+    PrivateThread,
     /// a voice channel for hosting events with an audience
-    GuildStageVoice = 13,
+    GuildStageVoice,
     /// the channel in a hub containing the listed servers
-    GuildDirectory = 14,
+    GuildDirectory,
     /// Channel that can only contain threads
-    GuildForum = 15,
+    GuildForum,
     /// Channel that can only contain threads, similar to GUILD_FORUM channels
-    GuildMedia = 16,
+    GuildMedia,
+    /// a value that was added after this was written
+    Unknown(u8),
 }
 
+impl_serde_for_integer_enum!(
+    ChannelTypes,
+    u8,
+    [
+        GuildText = 0,
+        DM = 1,
+        GuildVoice = 2,
+        GroupDM = 3,
+        GuildCategory = 4,
+        GuildAnnouncement = 5,
+        AnnouncementThread = 10,
+        PublicThread = 11,
+        PrivateThread = 12,
+        GuildStageVoice = 13,
+        GuildDirectory = 14,
+        GuildForum = 15,
+        GuildMedia = 16
+    ],
+    Unknown
+);
+
 #[turbo_tasks::value]
 pub struct ChannelTypesVc(Vc<ChannelTypes>);
 
@@ -59,6 +423,7 @@ macro_rules! extend_struct_mark_rest_optional {
             /// attachment id
             pub id: AttachmentId,
             $(
+                #[serde(skip_serializing_if = "Option::is_none")]
                 pub $field_name: Option<$field_type>,
             )*
         }
@@ -72,6 +437,7 @@ pub enum Attachment {
     Other(AttachmentOther),
 }
 
+/// An attachment as it is received, e.g. in the attachments of a message
 #[turbo_tasks::value(shared, serialization = "custom")]
 #[derive(Clone, Debug, Serialize, Deserialize, TaskInput, Hash)]
 pub struct AttachmentOther {
@@ -112,6 +478,8 @@ pub struct AttachmentOther {
     pub flags: Option<i32>,
 }
 
+// An attachment of a message that is created or edited, which refers to the
+// uploaded file `files[id]` of the multipart request
 extend_struct_mark_rest_optional!(AttachmentForMessageCreateOrEdit {
     filename: RcStr,
     description: RcStr,
@@ -129,6 +497,7 @@ extend_struct_mark_rest_optional!(AttachmentForMessageCreateOrEdit {
 
 #[turbo_tasks::value(shared, serialization = "custom")]
 #[derive(Clone, Debug, Serialize, Deserialize, TaskInput, Hash)]
+#[serde(rename_all = "lowercase")]
 pub enum AllowedMentionTypes {
     /// Controls role mentions
     Roles,
@@ -152,12 +521,84 @@ impl Display for AllowedMentionTypes {
 #[derive(Clone, Debug, Serialize, Deserialize, TaskInput, Hash)]
 pub struct AllowedMentions {
     /// An array of allowed mention types to parse from the content.
+    #[serde(default)]
     pub parse: Vec<AllowedMentionTypes>,
     /// Array of role_ids to mention (Max size of 100)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub roles: Vec<RoleId>,
     /// Array of user_ids to mention (Max size of 100)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub users: Vec<UserId>,
     /// For replies, whether to mention the author of the message being replied
     /// to (default false)
+    #[serde(default)]
     pub replied_user: bool,
 }
+
+/// Users and roles can be mentioned, but @everyone and @here are never parsed
+/// unless they're explicitly allowed.
+impl Default for AllowedMentions {
+    fn default() -> Self {
+        AllowedMentions {
+            parse: vec![AllowedMentionTypes::Users, AllowedMentionTypes::Roles],
+            roles: vec![],
+            users: vec![],
+            replied_user: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{Channel, ChannelFlags, ChannelTypes, PermissionOverwriteType};
+    use crate::{permissions::PermissionFlags, utils::assert_round_trip};
+
+    #[test]
+    fn test_guild_channel_round_trip() {
+        let channels =
+            assert_round_trip::<Vec<Channel>>(include_str!("../../fixtures/channels.json"));
+        let types = channels
+            .iter()
+            .map(|channel| channel.r#type)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            types,
+            vec![
+                ChannelTypes::GuildText,
+                ChannelTypes::GuildVoice,
+                ChannelTypes::GuildCategory,
+                ChannelTypes::GuildAnnouncement,
+                ChannelTypes::GuildStageVoice,
+                ChannelTypes::GuildForum,
+                ChannelTypes::DM,
+                ChannelTypes::GroupDM,
+            ]
+        );
+
+        let overwrite = &channels[0].permission_overwrites.as_ref().unwrap()[0];
+        assert_eq!(overwrite.r#type, PermissionOverwriteType::Role);
+        assert_eq!(overwrite.deny, PermissionFlags::SEND_MESSAGES);
+        assert_eq!(channels[5].flags, Some(ChannelFlags::REQUIRE_TAG));
+        assert!(channels.iter().all(|channel| !channel.is_thread()));
+    }
+
+    #[test]
+    fn test_thread_round_trip() {
+        let thread = assert_round_trip::<Channel>(include_str!("../../fixtures/thread.json"));
+
+        assert!(thread.is_thread());
+        assert!(!thread.thread_metadata.unwrap().archived);
+        assert!(thread.member.unwrap().member.is_some());
+    }
+
+    #[test]
+    fn test_unknown_channel_type() {
+        let channel = serde_json::from_value::<Channel>(json!({ "id": "1", "type": 99 })).unwrap();
+
+        assert_eq!(channel.r#type, ChannelTypes::Unknown(99));
+        assert_eq!(serde_json::to_value(&channel).unwrap()["type"], 99);
+    }
+}
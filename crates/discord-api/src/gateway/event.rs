@@ -0,0 +1,529 @@
+//! The events that are received with the `Dispatch` opcode, deserialized
+//! according to their name.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use turbopack_binding::turbo::tasks::RcStr;
+
+use crate::{
+    application::PartialApplication,
+    channel::{message::Message, Channel},
+    emoji::Emoji,
+    guild::{member::GuildMemberFlags, Guild, GuildMember, Role, UnavailableGuild},
+    id::{ChannelId, GuildId, MessageId, RoleId, UserId},
+    interactions::Interaction,
+    user::User,
+    voice::VoiceState,
+};
+
+/// The names of every event Discord dispatches, including the ones that aren't
+/// modelled yet, see <https://discord.com/developers/docs/topics/gateway-events#receive-events>
+pub const DISPATCH_EVENT_NAMES: &[&str] = &[
+    "READY",
+    "RESUMED",
+    "APPLICATION_COMMAND_PERMISSIONS_UPDATE",
+    "AUTO_MODERATION_RULE_CREATE",
+    "AUTO_MODERATION_RULE_UPDATE",
+    "AUTO_MODERATION_RULE_DELETE",
+    "AUTO_MODERATION_ACTION_EXECUTION",
+    "CHANNEL_CREATE",
+    "CHANNEL_UPDATE",
+    "CHANNEL_DELETE",
+    "CHANNEL_PINS_UPDATE",
+    "THREAD_CREATE",
+    "THREAD_UPDATE",
+    "THREAD_DELETE",
+    "THREAD_LIST_SYNC",
+    "THREAD_MEMBER_UPDATE",
+    "THREAD_MEMBERS_UPDATE",
+    "ENTITLEMENT_CREATE",
+    "ENTITLEMENT_UPDATE",
+    "ENTITLEMENT_DELETE",
+    "GUILD_CREATE",
+    "GUILD_UPDATE",
+    "GUILD_DELETE",
+    "GUILD_AUDIT_LOG_ENTRY_CREATE",
+    "GUILD_BAN_ADD",
+    "GUILD_BAN_REMOVE",
+    "GUILD_EMOJIS_UPDATE",
+    "GUILD_STICKERS_UPDATE",
+    "GUILD_INTEGRATIONS_UPDATE",
+    "GUILD_MEMBER_ADD",
+    "GUILD_MEMBER_REMOVE",
+    "GUILD_MEMBER_UPDATE",
+    "GUILD_MEMBERS_CHUNK",
+    "GUILD_ROLE_CREATE",
+    "GUILD_ROLE_UPDATE",
+    "GUILD_ROLE_DELETE",
+    "GUILD_SCHEDULED_EVENT_CREATE",
+    "GUILD_SCHEDULED_EVENT_UPDATE",
+    "GUILD_SCHEDULED_EVENT_DELETE",
+    "GUILD_SCHEDULED_EVENT_USER_ADD",
+    "GUILD_SCHEDULED_EVENT_USER_REMOVE",
+    "INTEGRATION_CREATE",
+    "INTEGRATION_UPDATE",
+    "INTEGRATION_DELETE",
+    "INTERACTION_CREATE",
+    "INVITE_CREATE",
+    "INVITE_DELETE",
+    "MESSAGE_CREATE",
+    "MESSAGE_UPDATE",
+    "MESSAGE_DELETE",
+    "MESSAGE_DELETE_BULK",
+    "MESSAGE_REACTION_ADD",
+    "MESSAGE_REACTION_REMOVE",
+    "MESSAGE_REACTION_REMOVE_ALL",
+    "MESSAGE_REACTION_REMOVE_EMOJI",
+    "MESSAGE_POLL_VOTE_ADD",
+    "MESSAGE_POLL_VOTE_REMOVE",
+    "PRESENCE_UPDATE",
+    "STAGE_INSTANCE_CREATE",
+    "STAGE_INSTANCE_UPDATE",
+    "STAGE_INSTANCE_DELETE",
+    "TYPING_START",
+    "USER_UPDATE",
+    "VOICE_CHANNEL_EFFECT_SEND",
+    "VOICE_STATE_UPDATE",
+    "VOICE_SERVER_UPDATE",
+    "WEBHOOKS_UPDATE",
+];
+
+/// A dispatched event, events that aren't modelled yet are kept as
+/// [`DispatchEvent::Unknown`]
+#[derive(Clone, Debug)]
+pub enum DispatchEvent {
+    Ready(Box<ReadyEvent>),
+    Resumed,
+    GuildCreate(Box<Guild>),
+    GuildUpdate(Box<Guild>),
+    GuildDelete(UnavailableGuild),
+    GuildMemberAdd(Box<GuildMemberAddEvent>),
+    GuildMemberUpdate(Box<GuildMemberUpdateEvent>),
+    GuildMemberRemove(GuildMemberRemoveEvent),
+    GuildRoleCreate(Box<GuildRoleEvent>),
+    GuildRoleUpdate(Box<GuildRoleEvent>),
+    GuildRoleDelete(GuildRoleDeleteEvent),
+    ChannelCreate(Box<Channel>),
+    ChannelUpdate(Box<Channel>),
+    ChannelDelete(Box<Channel>),
+    ThreadCreate(Box<Channel>),
+    ThreadUpdate(Box<Channel>),
+    /// only the `id`, `guild_id`, `parent_id` and `type` of the thread are
+    /// sent
+    ThreadDelete(Box<Channel>),
+    MessageCreate(Box<MessageCreateEvent>),
+    MessageUpdate(Box<MessageCreateEvent>),
+    MessageDelete(MessageDeleteEvent),
+    MessageReactionAdd(Box<MessageReactionAddEvent>),
+    MessageReactionRemove(Box<MessageReactionRemoveEvent>),
+    InteractionCreate(Box<Interaction>),
+    VoiceStateUpdate(Box<VoiceState>),
+    Unknown {
+        name: RcStr,
+        data: JsonValue,
+    },
+}
+
+impl DispatchEvent {
+    /// Deserializes the data `d` of a dispatch payload according to the event
+    /// name `t`
+    pub fn from_payload(name: &str, data: JsonValue) -> Result<Self, serde_json::Error> {
+        fn parse<T: DeserializeOwned>(data: JsonValue) -> Result<T, serde_json::Error> {
+            serde_json::from_value(data)
+        }
+
+        Ok(match name {
+            "READY" => DispatchEvent::Ready(parse(data)?),
+            "RESUMED" => DispatchEvent::Resumed,
+            "GUILD_CREATE" => DispatchEvent::GuildCreate(parse(data)?),
+            "GUILD_UPDATE" => DispatchEvent::GuildUpdate(parse(data)?),
+            "GUILD_DELETE" => DispatchEvent::GuildDelete(parse(data)?),
+            "GUILD_MEMBER_ADD" => DispatchEvent::GuildMemberAdd(parse(data)?),
+            "GUILD_MEMBER_UPDATE" => DispatchEvent::GuildMemberUpdate(parse(data)?),
+            "GUILD_MEMBER_REMOVE" => DispatchEvent::GuildMemberRemove(parse(data)?),
+            "GUILD_ROLE_CREATE" => DispatchEvent::GuildRoleCreate(parse(data)?),
+            "GUILD_ROLE_UPDATE" => DispatchEvent::GuildRoleUpdate(parse(data)?),
+            "GUILD_ROLE_DELETE" => DispatchEvent::GuildRoleDelete(parse(data)?),
+            "CHANNEL_CREATE" => DispatchEvent::ChannelCreate(parse(data)?),
+            "CHANNEL_UPDATE" => DispatchEvent::ChannelUpdate(parse(data)?),
+            "CHANNEL_DELETE" => DispatchEvent::ChannelDelete(parse(data)?),
+            "THREAD_CREATE" => DispatchEvent::ThreadCreate(parse(data)?),
+            "THREAD_UPDATE" => DispatchEvent::ThreadUpdate(parse(data)?),
+            "THREAD_DELETE" => DispatchEvent::ThreadDelete(parse(data)?),
+            "MESSAGE_CREATE" => DispatchEvent::MessageCreate(parse(data)?),
+            "MESSAGE_UPDATE" => DispatchEvent::MessageUpdate(parse(data)?),
+            "MESSAGE_DELETE" => DispatchEvent::MessageDelete(parse(data)?),
+            "MESSAGE_REACTION_ADD" => DispatchEvent::MessageReactionAdd(parse(data)?),
+            "MESSAGE_REACTION_REMOVE" => DispatchEvent::MessageReactionRemove(parse(data)?),
+            "INTERACTION_CREATE" => DispatchEvent::InteractionCreate(parse(data)?),
+            "VOICE_STATE_UPDATE" => DispatchEvent::VoiceStateUpdate(parse(data)?),
+            _ => DispatchEvent::Unknown {
+                name: name.into(),
+                data,
+            },
+        })
+    }
+
+    /// The name of the event as it is sent by Discord
+    pub fn name(&self) -> &str {
+        match self {
+            DispatchEvent::Ready(_) => "READY",
+            DispatchEvent::Resumed => "RESUMED",
+            DispatchEvent::GuildCreate(_) => "GUILD_CREATE",
+            DispatchEvent::GuildUpdate(_) => "GUILD_UPDATE",
+            DispatchEvent::GuildDelete(_) => "GUILD_DELETE",
+            DispatchEvent::GuildMemberAdd(_) => "GUILD_MEMBER_ADD",
+            DispatchEvent::GuildMemberUpdate(_) => "GUILD_MEMBER_UPDATE",
+            DispatchEvent::GuildMemberRemove(_) => "GUILD_MEMBER_REMOVE",
+            DispatchEvent::GuildRoleCreate(_) => "GUILD_ROLE_CREATE",
+            DispatchEvent::GuildRoleUpdate(_) => "GUILD_ROLE_UPDATE",
+            DispatchEvent::GuildRoleDelete(_) => "GUILD_ROLE_DELETE",
+            DispatchEvent::ChannelCreate(_) => "CHANNEL_CREATE",
+            DispatchEvent::ChannelUpdate(_) => "CHANNEL_UPDATE",
+            DispatchEvent::ChannelDelete(_) => "CHANNEL_DELETE",
+            DispatchEvent::ThreadCreate(_) => "THREAD_CREATE",
+            DispatchEvent::ThreadUpdate(_) => "THREAD_UPDATE",
+            DispatchEvent::ThreadDelete(_) => "THREAD_DELETE",
+            DispatchEvent::MessageCreate(_) => "MESSAGE_CREATE",
+            DispatchEvent::MessageUpdate(_) => "MESSAGE_UPDATE",
+            DispatchEvent::MessageDelete(_) => "MESSAGE_DELETE",
+            DispatchEvent::MessageReactionAdd(_) => "MESSAGE_REACTION_ADD",
+            DispatchEvent::MessageReactionRemove(_) => "MESSAGE_REACTION_REMOVE",
+            DispatchEvent::InteractionCreate(_) => "INTERACTION_CREATE",
+            DispatchEvent::VoiceStateUpdate(_) => "VOICE_STATE_UPDATE",
+            DispatchEvent::Unknown { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReadyEvent {
+    /// API version
+    pub v: u8,
+    /// Information about the user including email
+    pub user: User,
+    /// Guilds the user is in
+    pub guilds: Vec<UnavailableGuild>,
+    /// Used for resuming connections
+    pub session_id: String,
+    /// Gateway URL for resuming connections
+    pub resume_gateway_url: String,
+    /// Shard information associated with this session, if sent when identifying
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard: Option<(u32, u32)>,
+    /// Contains id and flags
+    pub application: PartialApplication,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuildMemberAddEvent {
+    /// ID of the guild
+    pub guild_id: GuildId,
+    #[serde(flatten)]
+    pub member: GuildMember,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuildMemberUpdateEvent {
+    /// ID of the guild
+    pub guild_id: GuildId,
+    /// User role ids
+    pub roles: Vec<RoleId>,
+    /// User
+    pub user: User,
+    /// Nickname of the user in the guild
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nick: Option<RcStr>,
+    /// Member's guild avatar hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<RcStr>,
+    /// When the user joined the guild
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub joined_at: Option<RcStr>,
+    /// When the user starting boosting the guild
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub premium_since: Option<RcStr>,
+    /// Whether the user is deafened in voice channels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deaf: Option<bool>,
+    /// Whether the user is muted in voice channels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mute: Option<bool>,
+    /// Whether the user has not yet passed the guild's Membership Screening
+    /// requirements
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending: Option<bool>,
+    /// When the user's timeout will expire and the user will be able to
+    /// communicate in the guild again, null or a time in the past if the user
+    /// is not timed out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub communication_disabled_until: Option<RcStr>,
+    /// Guild member flags represented as a bit set, defaults to 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flags: Option<GuildMemberFlags>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuildMemberRemoveEvent {
+    /// ID of the guild
+    pub guild_id: GuildId,
+    /// User who was removed
+    pub user: User,
+}
+
+/// Sent for both GUILD_ROLE_CREATE and GUILD_ROLE_UPDATE
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuildRoleEvent {
+    /// ID of the guild
+    pub guild_id: GuildId,
+    /// Role that was created or updated
+    pub role: Role,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuildRoleDeleteEvent {
+    /// ID of the guild
+    pub guild_id: GuildId,
+    /// ID of the role
+    pub role_id: RoleId,
+}
+
+/// Sent for both MESSAGE_CREATE and MESSAGE_UPDATE
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MessageCreateEvent {
+    #[serde(flatten)]
+    pub message: Message,
+    /// ID of the guild the message was sent in - unless it is an ephemeral
+    /// message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<GuildId>,
+    /// Member properties for this message's author. Missing for ephemeral
+    /// messages and messages from webhooks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<GuildMember>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MessageDeleteEvent {
+    /// ID of the message
+    pub id: MessageId,
+    /// ID of the channel
+    pub channel_id: ChannelId,
+    /// ID of the guild
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<GuildId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MessageReactionAddEvent {
+    /// ID of the user
+    pub user_id: UserId,
+    /// ID of the channel
+    pub channel_id: ChannelId,
+    /// ID of the message
+    pub message_id: MessageId,
+    /// ID of the guild
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<GuildId>,
+    /// Member who reacted if this happened in a guild
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<GuildMember>,
+    /// Emoji used to react
+    pub emoji: Emoji,
+    /// ID of the user who authored the message which was reacted to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_author_id: Option<UserId>,
+    /// true if this is a super-reaction
+    #[serde(default)]
+    pub burst: bool,
+    /// Colors used for super-reaction animation in "#rrggbb" format
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub burst_colors: Vec<RcStr>,
+    /// The type of reaction, 0 for normal and 1 for burst
+    #[serde(default)]
+    pub r#type: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MessageReactionRemoveEvent {
+    /// ID of the user
+    pub user_id: UserId,
+    /// ID of the channel
+    pub channel_id: ChannelId,
+    /// ID of the message
+    pub message_id: MessageId,
+    /// ID of the guild
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<GuildId>,
+    /// Emoji used to react
+    pub emoji: Emoji,
+    /// true if this was a super-reaction
+    #[serde(default)]
+    pub burst: bool,
+    /// The type of reaction, 0 for normal and 1 for burst
+    #[serde(default)]
+    pub r#type: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{DispatchEvent, DISPATCH_EVENT_NAMES};
+    use crate::id::{GuildId, InteractionId, MessageId};
+
+    fn user() -> serde_json::Value {
+        json!({
+            "id": "80351110224678912",
+            "username": "fujinoki",
+            "discriminator": "0",
+            "global_name": null,
+            "avatar": null
+        })
+    }
+
+    #[test]
+    fn test_modelled_events_are_known() {
+        for name in [
+            "READY",
+            "GUILD_CREATE",
+            "GUILD_MEMBER_ADD",
+            "THREAD_DELETE",
+            "MESSAGE_REACTION_REMOVE",
+            "INTERACTION_CREATE",
+            "VOICE_STATE_UPDATE",
+        ] {
+            assert!(DISPATCH_EVENT_NAMES.contains(&name), "{name}");
+        }
+
+        let event = DispatchEvent::from_payload("TYPING_START", json!({})).unwrap();
+        assert!(matches!(event, DispatchEvent::Unknown { .. }));
+        assert!(DISPATCH_EVENT_NAMES.contains(&event.name()));
+    }
+
+    #[test]
+    fn test_ready() {
+        let event = DispatchEvent::from_payload(
+            "READY",
+            json!({
+                "v": 10,
+                "user": user(),
+                "guilds": [{ "id": "41771983423143937", "unavailable": true }],
+                "session_id": "90326bd25d71d39b9ef95b299e3872ff",
+                "resume_gateway_url": "wss://gateway-us-east1-b.discord.gg",
+                "application": { "id": "159985870458322944", "flags": 0 },
+                "_trace": []
+            }),
+        )
+        .unwrap();
+
+        let DispatchEvent::Ready(ready) = event else {
+            panic!("expected READY, got {}", event.name());
+        };
+        assert_eq!(ready.session_id, "90326bd25d71d39b9ef95b299e3872ff");
+        assert_eq!(ready.guilds[0].id, GuildId(41771983423143937));
+        assert_eq!(ready.shard, None);
+    }
+
+    #[test]
+    fn test_message_create() {
+        let event = DispatchEvent::from_payload(
+            "MESSAGE_CREATE",
+            json!({
+                "id": "334385199974967042",
+                "channel_id": "290926798999357250",
+                "guild_id": "41771983423143937",
+                "author": user(),
+                "member": {
+                    "roles": [],
+                    "joined_at": "2015-04-26T06:26:56.936000+00:00",
+                    "deaf": false,
+                    "mute": false
+                },
+                "content": "hello",
+                "timestamp": "2017-07-11T17:27:07.299000+00:00",
+                "edited_timestamp": null,
+                "tts": false,
+                "mention_everyone": false,
+                "mentions": [],
+                "mention_roles": [],
+                "attachments": [],
+                "embeds": [],
+                "nonce": 1234567890,
+                "pinned": false,
+                "type": 0,
+                "components": [{ "type": 1, "components": [] }]
+            }),
+        )
+        .unwrap();
+
+        let DispatchEvent::MessageCreate(message) = event else {
+            panic!("expected MESSAGE_CREATE, got {}", event.name());
+        };
+        assert_eq!(message.message.id, MessageId(334385199974967042));
+        assert_eq!(message.message.content, "hello");
+        assert_eq!(message.guild_id, Some(GuildId(41771983423143937)));
+        assert!(message.member.is_some());
+    }
+
+    #[test]
+    fn test_interaction_create() {
+        let event = DispatchEvent::from_payload(
+            "INTERACTION_CREATE",
+            json!({
+                "id": "786008729715212338",
+                "application_id": "159985870458322944",
+                "type": 2,
+                "data": {
+                    "id": "771825006014889984",
+                    "name": "ping",
+                    "type": 1,
+                    "options": [{ "name": "ephemeral", "type": 5, "value": true }]
+                },
+                "guild_id": "41771983423143937",
+                "channel_id": "290926798999357250",
+                "user": user(),
+                "token": "A_UNIQUE_TOKEN",
+                "version": 1,
+                "locale": "en-US"
+            }),
+        )
+        .unwrap();
+
+        let DispatchEvent::InteractionCreate(interaction) = event else {
+            panic!("expected INTERACTION_CREATE, got {}", event.name());
+        };
+        assert_eq!(interaction.id, InteractionId(786008729715212338));
+        assert_eq!(interaction.command_name(), Some("ping"));
+        assert_eq!(interaction.token.as_str(), "A_UNIQUE_TOKEN");
+        assert_eq!(interaction.data.unwrap().options[0].name, "ephemeral");
+    }
+
+    #[test]
+    fn test_malformed_event() {
+        // The token is missing, which used to panic when responding
+        let result = DispatchEvent::from_payload(
+            "INTERACTION_CREATE",
+            json!({
+                "id": "786008729715212338",
+                "application_id": "159985870458322944",
+                "type": 2,
+                "version": 1
+            }),
+        );
+        assert!(result.is_err());
+
+        assert!(DispatchEvent::from_payload("READY", json!(null)).is_err());
+    }
+
+    #[test]
+    fn test_unknown_event() {
+        let event = DispatchEvent::from_payload("TYPING_START", json!({ "user_id": "1" })).unwrap();
+
+        assert_eq!(event.name(), "TYPING_START");
+        assert!(matches!(
+            event,
+            DispatchEvent::Unknown { data, .. } if data["user_id"] == "1"
+        ));
+    }
+}
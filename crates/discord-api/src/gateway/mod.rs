@@ -3,6 +3,7 @@ use std::{
     time::Duration,
 };
 
+use bitflags::bitflags;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value as JsonValue;
 use turbopack_binding::turbo::{
@@ -10,7 +11,11 @@ use turbopack_binding::turbo::{
     tasks::{RcStr, TaskInput},
 };
 
-use crate::{application::PartialApplication, guild::UnavailableGuild, user::User};
+use crate::{impl_deserialize_from_bits, impl_serialize_to_bits};
+
+pub mod event;
+
+pub use self::event::DispatchEvent;
 
 #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, TaskInput)]
@@ -131,6 +136,42 @@ impl TryFrom<u8> for OpCode {
     }
 }
 
+bitflags! {
+    /// https://discord.com/developers/docs/topics/gateway#gateway-intents
+    #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+    #[derive(Default, TaskInput)]
+    pub struct GatewayIntents: u32 {
+        const GUILDS = 1 << 0;
+        /// Privileged, it has to be enabled in the settings of the application
+        const GUILD_MEMBERS = 1 << 1;
+        const GUILD_MODERATION = 1 << 2;
+        const GUILD_EMOJIS_AND_STICKERS = 1 << 3;
+        const GUILD_INTEGRATIONS = 1 << 4;
+        const GUILD_WEBHOOKS = 1 << 5;
+        const GUILD_INVITES = 1 << 6;
+        const GUILD_VOICE_STATES = 1 << 7;
+        /// Privileged, it has to be enabled in the settings of the application
+        const GUILD_PRESENCES = 1 << 8;
+        const GUILD_MESSAGES = 1 << 9;
+        const GUILD_MESSAGE_REACTIONS = 1 << 10;
+        const GUILD_MESSAGE_TYPING = 1 << 11;
+        const DIRECT_MESSAGES = 1 << 12;
+        const DIRECT_MESSAGE_REACTIONS = 1 << 13;
+        const DIRECT_MESSAGE_TYPING = 1 << 14;
+        /// Privileged, without it the content, embeds, attachments and components of
+        /// messages are empty unless the bot is mentioned or it is a direct message
+        const MESSAGE_CONTENT = 1 << 15;
+        const GUILD_SCHEDULED_EVENTS = 1 << 16;
+        const AUTO_MODERATION_CONFIGURATION = 1 << 20;
+        const AUTO_MODERATION_EXECUTION = 1 << 21;
+        const GUILD_MESSAGE_POLLS = 1 << 24;
+        const DIRECT_MESSAGE_POLLS = 1 << 25;
+    }
+}
+
+impl_deserialize_from_bits!(GatewayIntents, u32);
+impl_serialize_to_bits!(GatewayIntents);
+
 #[turbo_tasks::value(shared, serialization = "custom")]
 #[derive(Clone, Debug, Serialize, Deserialize, Hash)]
 pub struct Payload {
@@ -153,35 +194,12 @@ impl PayloadToString for Payload {
     }
 }
 
-// TODO Do rest of these
-pub enum DispatchPayload {
-    Ready(ReadyEventPayload),
-}
-
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ReadyEventPayload {
     // TODO: add client struct to client.rs
     pub client: JsonValue,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ReadyEvent {
-    /// API version
-    v: u8,
-    /// Information about the user including email
-    user: User,
-    /// Guilds the user is in
-    guilds: Vec<UnavailableGuild>,
-    /// Used for resuming connections
-    session_id: String,
-    /// Gateway URL for resuming connections
-    resume_gateway_url: String,
-    /// Shard information associated with this session, if sent when identifying
-    shard: (i8, i8),
-    /// Contains id and flags
-    application: PartialApplication,
-}
-
 pub struct HelloPayload {
     pub op: u32,
     pub d: HelloPayloadData,
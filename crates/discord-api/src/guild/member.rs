@@ -0,0 +1,124 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr};
+
+use crate::{
+    id::RoleId, impl_deserialize_from_bits, impl_serialize_to_bits, permissions::PermissionFlags,
+    timestamp::Timestamp, user::User,
+};
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GuildMember {
+    /// the user this guild member represents, not included in the member
+    /// object attached to MESSAGE_CREATE and MESSAGE_UPDATE events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<User>,
+    /// this user's guild nickname
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nick: Option<RcStr>,
+    /// the member's guild avatar hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<RcStr>,
+    /// array of role object ids
+    pub roles: Vec<RoleId>,
+    /// when the user joined the guild
+    pub joined_at: RcStr,
+    /// when the user started boosting the guild
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub premium_since: Option<RcStr>,
+    /// whether the user is deafened in voice channels, missing from the members
+    /// of resolved interaction data
+    #[serde(default)]
+    pub deaf: bool,
+    /// whether the user is muted in voice channels, missing from the members of
+    /// resolved interaction data
+    #[serde(default)]
+    pub mute: bool,
+    /// guild member flags represented as a bit set, defaults to 0
+    #[serde(default)]
+    pub flags: GuildMemberFlags,
+    /// whether the user has not yet passed the guild's Membership Screening
+    /// requirements
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending: Option<bool>,
+    /// total permissions of the member in the channel, including overwrites,
+    /// returned when in the interaction object
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<PermissionFlags>,
+    /// when the user's timeout will expire and the user will be able to
+    /// communicate in the guild again, null or a time in the past if the user
+    /// is not timed out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub communication_disabled_until: Option<RcStr>,
+}
+
+impl GuildMember {
+    /// Whether the member is still timed out at `now`
+    pub fn is_timed_out(&self, now: Timestamp) -> bool {
+        self.communication_disabled_until
+            .as_deref()
+            .and_then(|until| Timestamp::parse(until).ok())
+            .is_some_and(|until| *until > *now)
+    }
+}
+
+bitflags! {
+    #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+    #[derive(Default)]
+    pub struct GuildMemberFlags: u32 {
+        /// Member has left and rejoined the guild
+        const DID_REJOIN = 1 << 0;
+        /// Member has completed onboarding
+        const COMPLETED_ONBOARDING = 1 << 1;
+        /// Member is exempt from guild verification requirements
+        const BYPASSES_VERIFICATION = 1 << 2;
+        /// Member has started onboarding
+        const STARTED_ONBOARDING = 1 << 3;
+        /// Member is a guest and can only access the voice channel they were
+        /// invited to
+        const IS_GUEST = 1 << 4;
+        /// Member has started Server Guide new member actions
+        const STARTED_HOME_ACTIONS = 1 << 5;
+        /// Member has completed Server Guide new member actions
+        const COMPLETED_HOME_ACTIONS = 1 << 6;
+        /// Member's username, display name, or nickname is blocked by AutoMod
+        const AUTOMOD_QUARANTINED_USERNAME = 1 << 7;
+        /// Member has dismissed the DM settings upsell
+        const DM_SETTINGS_UPSELL_ACKNOWLEDGED = 1 << 9;
+    }
+}
+
+impl_deserialize_from_bits!(GuildMemberFlags, u32);
+impl_serialize_to_bits!(GuildMemberFlags);
+
+#[cfg(test)]
+mod tests {
+    use super::{GuildMember, GuildMemberFlags};
+    use crate::{id::RoleId, timestamp::Timestamp, utils::assert_round_trip};
+
+    #[test]
+    fn test_guild_member_round_trip() {
+        let member =
+            assert_round_trip::<GuildMember>(include_str!("../../fixtures/guild_member.json"));
+
+        assert_eq!(member.user.unwrap().username.as_str(), "fujinoki");
+        assert_eq!(member.roles, vec![RoleId(41771983423143936)]);
+        assert_eq!(member.flags, GuildMemberFlags::COMPLETED_ONBOARDING);
+    }
+
+    #[test]
+    fn test_is_timed_out() {
+        let mut member: GuildMember =
+            serde_json::from_str(include_str!("../../fixtures/guild_member.json")).unwrap();
+        let now = Timestamp::parse("2024-01-01T00:00:00+00:00").unwrap();
+
+        assert!(!member.is_timed_out(now));
+
+        member.communication_disabled_until = Some("2024-01-01T00:10:00+00:00".into());
+        assert!(member.is_timed_out(now));
+
+        member.communication_disabled_until = Some("2023-12-31T23:50:00+00:00".into());
+        assert!(!member.is_timed_out(now));
+    }
+}
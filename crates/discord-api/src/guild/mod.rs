@@ -0,0 +1,411 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr};
+
+pub mod member;
+pub mod role;
+
+pub use self::{member::GuildMember, role::Role};
+use crate::{
+    channel::Channel,
+    emoji::Emoji,
+    id::{ApplicationId, ChannelId, EmojiId, GuildId, UserId},
+    impl_deserialize_from_bits, impl_serde_for_integer_enum, impl_serialize_to_bits,
+    permissions::PermissionFlags,
+    sticker::Sticker,
+    user::User,
+    voice::VoiceState,
+};
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UnavailableGuild {
+    pub id: GuildId,
+    /// missing from GUILD_DELETE when the user was removed from the guild
+    #[serde(default)]
+    pub unavailable: bool,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Guild {
+    /// guild id
+    pub id: GuildId,
+    /// guild name (2-100 characters, excluding trailing and leading whitespace)
+    pub name: RcStr,
+    /// icon hash
+    pub icon: Option<RcStr>,
+    /// icon hash, returned when in the template object
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_hash: Option<RcStr>,
+    /// splash hash
+    pub splash: Option<RcStr>,
+    /// discovery splash hash; only present for guilds with the "DISCOVERABLE"
+    /// feature
+    pub discovery_splash: Option<RcStr>,
+    /// true if the user is the owner of the guild, only sent when using the
+    /// GET Current User Guilds endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<bool>,
+    /// id of owner
+    pub owner_id: UserId,
+    /// total permissions for the user in the guild (excludes overwrites and
+    /// implicit permissions), only sent when using the GET Current User Guilds
+    /// endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<PermissionFlags>,
+    /// id of afk channel
+    pub afk_channel_id: Option<ChannelId>,
+    /// afk timeout in seconds
+    pub afk_timeout: u32,
+    /// true if the server widget is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub widget_enabled: Option<bool>,
+    /// the channel id that the widget will generate an invite to, or null if
+    /// set to no invite
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub widget_channel_id: Option<ChannelId>,
+    /// verification level required for the guild
+    pub verification_level: VerificationLevel,
+    /// default message notifications level
+    pub default_message_notifications: DefaultMessageNotificationLevel,
+    /// explicit content filter level
+    pub explicit_content_filter: ExplicitContentFilterLevel,
+    /// roles in the guild
+    pub roles: Vec<Role>,
+    /// custom guild emojis
+    pub emojis: Vec<Emoji>,
+    /// enabled guild features, kept as strings as new ones are added regularly
+    pub features: Vec<RcStr>,
+    /// required MFA level for the guild
+    pub mfa_level: MfaLevel,
+    /// application id of the guild creator if it is bot-created
+    pub application_id: Option<ApplicationId>,
+    /// the id of the channel where guild notices such as welcome messages and
+    /// boost events are posted
+    pub system_channel_id: Option<ChannelId>,
+    /// system channel flags
+    pub system_channel_flags: SystemChannelFlags,
+    /// the id of the channel where Community guilds can display rules and/or
+    /// guidelines
+    pub rules_channel_id: Option<ChannelId>,
+    /// the maximum number of presences for the guild (null is always returned,
+    /// apart from the largest of guilds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_presences: Option<u32>,
+    /// the maximum number of members for the guild
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_members: Option<u32>,
+    /// the vanity url code for the guild
+    pub vanity_url_code: Option<RcStr>,
+    /// the description of a guild
+    pub description: Option<RcStr>,
+    /// banner hash
+    pub banner: Option<RcStr>,
+    /// premium tier (Server Boost level)
+    pub premium_tier: PremiumTier,
+    /// the number of boosts this guild currently has
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub premium_subscription_count: Option<u32>,
+    /// the preferred locale of a Community guild; used in server discovery and
+    /// notices from Discord, and sent in interactions; defaults to "en-US"
+    pub preferred_locale: RcStr,
+    /// the id of the channel where admins and moderators of Community guilds
+    /// receive notices from Discord
+    pub public_updates_channel_id: Option<ChannelId>,
+    /// the maximum amount of users in a video channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_video_channel_users: Option<u32>,
+    /// the maximum amount of users in a stage video channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_stage_video_channel_users: Option<u32>,
+    /// approximate number of members in this guild, returned from the GET
+    /// /guilds/<id> and /users/@me/guilds endpoints when with_counts is true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approximate_member_count: Option<u32>,
+    /// approximate number of non-offline members in this guild, returned from
+    /// the GET /guilds/<id> and /users/@me/guilds endpoints when with_counts is
+    /// true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approximate_presence_count: Option<u32>,
+    /// the welcome screen of a Community guild, shown to new members, returned
+    /// in an Invite's guild object
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub welcome_screen: Option<WelcomeScreen>,
+    /// guild NSFW level
+    pub nsfw_level: NsfwLevel,
+    /// custom guild stickers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stickers: Option<Vec<Sticker>>,
+    /// whether the guild has the boost progress bar enabled
+    pub premium_progress_bar_enabled: bool,
+    /// the id of the channel where admins and moderators of Community guilds
+    /// receive safety alerts from Discord
+    pub safety_alerts_channel_id: Option<ChannelId>,
+
+    // The fields below are only sent in the GUILD_CREATE event
+    /// when this guild was joined at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub joined_at: Option<RcStr>,
+    /// true if this is considered a large guild
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub large: Option<bool>,
+    /// true if this guild is unavailable due to an outage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unavailable: Option<bool>,
+    /// total number of members in this guild
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member_count: Option<u32>,
+    /// states of members currently in voice channels; lacks the guild_id key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice_states: Option<Vec<VoiceState>>,
+    /// users in the guild
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<GuildMember>>,
+    /// channels in the guild
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<Vec<Channel>>,
+    /// all active threads in the guild that current user has permission to
+    /// view
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads: Option<Vec<Channel>>,
+}
+
+/// A guild as it is embedded in other objects, e.g. an application or an
+/// invite, which only includes some of the fields
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PartialGuild {
+    /// guild id
+    pub id: GuildId,
+    /// guild name
+    pub name: RcStr,
+    /// icon hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<RcStr>,
+    /// splash hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub splash: Option<RcStr>,
+    /// banner hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub banner: Option<RcStr>,
+    /// the description of a guild
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<RcStr>,
+    /// enabled guild features
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<RcStr>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationLevel {
+    /// unrestricted
+    None,
+    /// must have verified email on account
+    Low,
+    /// must be registered on Discord for longer than 5 minutes
+    Medium,
+    /// must be a member of the server for longer than 10 minutes
+    High,
+    /// must have a verified phone number
+    VeryHigh,
+    /// a value that was added after this was written
+    Unknown(u8),
+}
+
+impl_serde_for_integer_enum!(
+    VerificationLevel,
+    u8,
+    [None = 0, Low = 1, Medium = 2, High = 3, VeryHigh = 4],
+    Unknown
+);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DefaultMessageNotificationLevel {
+    /// members will receive notifications for all messages by default
+    AllMessages,
+    /// members will receive notifications only for messages that @mention them
+    /// by default
+    OnlyMentions,
+    /// a value that was added after this was written
+    Unknown(u8),
+}
+
+impl_serde_for_integer_enum!(
+    DefaultMessageNotificationLevel,
+    u8,
+    [AllMessages = 0, OnlyMentions = 1],
+    Unknown
+);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExplicitContentFilterLevel {
+    /// media content will not be scanned
+    Disabled,
+    /// media content sent by members without roles will be scanned
+    MembersWithoutRoles,
+    /// media content sent by all members will be scanned
+    AllMembers,
+    /// a value that was added after this was written
+    Unknown(u8),
+}
+
+impl_serde_for_integer_enum!(
+    ExplicitContentFilterLevel,
+    u8,
+    [Disabled = 0, MembersWithoutRoles = 1, AllMembers = 2],
+    Unknown
+);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MfaLevel {
+    /// guild has no MFA/2FA requirement for moderation actions
+    None,
+    /// guild has a 2FA requirement for moderation actions
+    Elevated,
+    /// a value that was added after this was written
+    Unknown(u8),
+}
+
+impl_serde_for_integer_enum!(MfaLevel, u8, [None = 0, Elevated = 1], Unknown);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NsfwLevel {
+    Default,
+    Explicit,
+    Safe,
+    AgeRestricted,
+    /// a value that was added after this was written
+    Unknown(u8),
+}
+
+impl_serde_for_integer_enum!(
+    NsfwLevel,
+    u8,
+    [Default = 0, Explicit = 1, Safe = 2, AgeRestricted = 3],
+    Unknown
+);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PremiumTier {
+    /// guild has not unlocked any Server Boost perks
+    None,
+    /// guild has unlocked Server Boost level 1 perks
+    Tier1,
+    /// guild has unlocked Server Boost level 2 perks
+    Tier2,
+    /// guild has unlocked Server Boost level 3 perks
+    Tier3,
+    /// a value that was added after this was written
+    Unknown(u8),
+}
+
+impl_serde_for_integer_enum!(
+    PremiumTier,
+    u8,
+    [None = 0, Tier1 = 1, Tier2 = 2, Tier3 = 3],
+    Unknown
+);
+
+bitflags! {
+    #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+    #[derive(Default)]
+    pub struct SystemChannelFlags: u32 {
+        /// Suppress member join notifications
+        const SUPPRESS_JOIN_NOTIFICATIONS = 1 << 0;
+        /// Suppress server boost notifications
+        const SUPPRESS_PREMIUM_SUBSCRIPTIONS = 1 << 1;
+        /// Suppress server setup tips
+        const SUPPRESS_GUILD_REMINDER_NOTIFICATIONS = 1 << 2;
+        /// Hide member join sticker reply buttons
+        const SUPPRESS_JOIN_NOTIFICATION_REPLIES = 1 << 3;
+        /// Suppress role subscription purchase and renewal notifications
+        const SUPPRESS_ROLE_SUBSCRIPTION_PURCHASE_NOTIFICATIONS = 1 << 4;
+        /// Hide role subscription sticker reply buttons
+        const SUPPRESS_ROLE_SUBSCRIPTION_PURCHASE_NOTIFICATION_REPLIES = 1 << 5;
+    }
+}
+
+impl_deserialize_from_bits!(SystemChannelFlags, u32);
+impl_serialize_to_bits!(SystemChannelFlags);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WelcomeScreen {
+    /// the server description shown in the welcome screen
+    pub description: Option<RcStr>,
+    /// the channels shown in the welcome screen, up to 5
+    pub welcome_channels: Vec<WelcomeScreenChannel>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WelcomeScreenChannel {
+    /// the channel's id
+    pub channel_id: ChannelId,
+    /// the description shown for the channel
+    pub description: RcStr,
+    /// the emoji id, if the emoji is custom
+    pub emoji_id: Option<EmojiId>,
+    /// the emoji name if custom, the unicode character if standard, or null if
+    /// no emoji is set
+    pub emoji_name: Option<RcStr>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Ban {
+    /// the reason for the ban
+    pub reason: Option<RcStr>,
+    /// the banned user
+    pub user: User,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Guild, SystemChannelFlags, VerificationLevel};
+    use crate::{permissions::PermissionFlags, utils::assert_round_trip};
+
+    #[test]
+    fn test_guild_round_trip() {
+        let guild = assert_round_trip::<Guild>(include_str!("../../fixtures/guild.json"));
+
+        assert_eq!(guild.verification_level, VerificationLevel::Medium);
+        assert_eq!(
+            guild.system_channel_flags,
+            SystemChannelFlags::SUPPRESS_JOIN_NOTIFICATIONS
+        );
+        assert_eq!(guild.roles.len(), 2);
+        assert!(guild.roles[1]
+            .permissions
+            .contains(PermissionFlags::BAN_MEMBERS));
+    }
+
+    #[test]
+    fn test_guild_create_round_trip() {
+        let guild = assert_round_trip::<Guild>(include_str!("../../fixtures/guild_create.json"));
+
+        assert_eq!(guild.member_count, Some(2));
+        assert_eq!(guild.members.unwrap().len(), 1);
+        assert_eq!(guild.channels.unwrap().len(), 2);
+        assert!(guild.threads.unwrap()[0].is_thread());
+    }
+
+    #[test]
+    fn test_unknown_verification_level() {
+        let level = serde_json::from_str::<VerificationLevel>("9").unwrap();
+
+        assert_eq!(level, VerificationLevel::Unknown(9));
+        assert_eq!(serde_json::to_string(&level).unwrap(), "9");
+        assert_eq!(
+            serde_json::from_str::<VerificationLevel>("2").unwrap(),
+            VerificationLevel::Medium
+        );
+    }
+}
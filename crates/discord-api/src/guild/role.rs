@@ -0,0 +1,124 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr};
+
+use crate::{
+    id::{GenericId, IntegrationId, RoleId, UserId},
+    impl_deserialize_from_bits, impl_serialize_to_bits,
+    permissions::PermissionFlags,
+};
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Role {
+    /// role id
+    pub id: RoleId,
+    /// role name
+    pub name: RcStr,
+    /// integer representation of hexadecimal color code
+    pub color: u32,
+    /// if this role is pinned in the user listing
+    pub hoist: bool,
+    /// role icon hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<RcStr>,
+    /// role unicode emoji
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unicode_emoji: Option<RcStr>,
+    /// position of this role (roles with the same position are sorted by id)
+    pub position: i32,
+    /// permission bit set
+    pub permissions: PermissionFlags,
+    /// whether this role is managed by an integration
+    pub managed: bool,
+    /// whether this role is mentionable
+    pub mentionable: bool,
+    /// the tags this role has
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<RoleTags>,
+    /// role flags combined as a bitfield
+    #[serde(default)]
+    pub flags: RoleFlags,
+}
+
+/// Tags that are set to `null` by Discord are `true` when present and `false`
+/// when missing
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RoleTags {
+    /// the id of the bot this role belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bot_id: Option<UserId>,
+    /// the id of the integration this role belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integration_id: Option<IntegrationId>,
+    /// whether this is the guild's Booster role
+    #[serde(default, with = "null_flag", skip_serializing_if = "is_false")]
+    pub premium_subscriber: bool,
+    /// the id of this role's subscription sku and listing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_listing_id: Option<GenericId>,
+    /// whether this role is available for purchase
+    #[serde(default, with = "null_flag", skip_serializing_if = "is_false")]
+    pub available_for_purchase: bool,
+    /// whether this role is a guild's linked role
+    #[serde(default, with = "null_flag", skip_serializing_if = "is_false")]
+    pub guild_connections: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+mod null_flag {
+    use super::*;
+
+    pub fn serialize<S>(_value: &bool, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_none()
+    }
+
+    /// Only called when the field is present
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<()>::deserialize(deserializer)?;
+        Ok(true)
+    }
+}
+
+bitflags! {
+    #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+    #[derive(Default)]
+    pub struct RoleFlags: u32 {
+        /// role can be selected by members in an onboarding prompt
+        const IN_PROMPT = 1 << 0;
+    }
+}
+
+impl_deserialize_from_bits!(RoleFlags, u32);
+impl_serialize_to_bits!(RoleFlags);
+
+#[cfg(test)]
+mod tests {
+    use super::Role;
+    use crate::{id::UserId, permissions::PermissionFlags, utils::assert_round_trip};
+
+    #[test]
+    fn test_role_round_trip() {
+        let roles = assert_round_trip::<Vec<Role>>(include_str!("../../fixtures/roles.json"));
+
+        assert_eq!(roles[0].permissions, PermissionFlags::empty());
+        assert!(roles[1]
+            .permissions
+            .contains(PermissionFlags::ADMINISTRATOR));
+
+        let tags = roles[1].tags.as_ref().unwrap();
+        assert_eq!(tags.bot_id, Some(UserId(159985870458322944)));
+        assert!(!tags.premium_subscriber);
+        assert!(roles[2].tags.as_ref().unwrap().premium_subscriber);
+    }
+}
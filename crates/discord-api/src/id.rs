@@ -240,6 +240,7 @@ pub struct MessageId(#[serde(with = "snowflake")] pub u64);
 pub struct RoleId(#[serde(with = "snowflake")] pub u64);
 
 /// An identifier for an auto moderation rule
+#[turbo_tasks::value(transparent, shared, serialization = "custom", eq = "manual")]
 #[derive(
     Copy,
     Clone,
@@ -257,6 +258,7 @@ pub struct RoleId(#[serde(with = "snowflake")] pub u64);
 pub struct RuleId(#[serde(with = "snowflake")] pub u64);
 
 /// An identifier for a Scheduled Event
+#[turbo_tasks::value(transparent, shared, serialization = "custom", eq = "manual")]
 #[derive(
     Copy,
     Clone,
@@ -1,16 +1,133 @@
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use turbopack_binding::turbo::{
     tasks as turbo_tasks,
     tasks::{RcStr, TaskInput},
 };
 
+pub mod options;
+
+use self::options::{InteractionDataOption, ResolvedData};
 use crate::{
-    channel::{embed::Embed, message::MessageFlags, AllowedMentions, Attachment},
+    application::command::{ApplicationCommandType, InteractionContext},
+    channel::{
+        embed::Embed,
+        message::{Message, MessageFlags},
+        AllowedMentions, AttachmentForMessageCreateOrEdit, Channel,
+    },
     emoji::Emoji,
+    guild::GuildMember,
+    id::{ApplicationId, ChannelId, CommandId, GuildId, InteractionId, TargetId},
     impl_deserialize_from_bits,
+    permissions::PermissionFlags,
+    user::User,
 };
 
+/// An interaction as it is received in INTERACTION_CREATE
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Interaction {
+    /// ID of the interaction
+    pub id: InteractionId,
+    /// ID of the application this interaction is for
+    pub application_id: ApplicationId,
+    /// type of interaction
+    pub r#type: InteractionType,
+    /// interaction data payload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<InteractionData>,
+    /// guild that the interaction was sent from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<GuildId>,
+    /// channel that the interaction was sent from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<Channel>,
+    /// channel that the interaction was sent from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<ChannelId>,
+    /// guild member data for the invoking user, including permissions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<GuildMember>,
+    /// user object for the invoking user, if invoked in a DM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<User>,
+    /// continuation token for responding to the interaction
+    pub token: RcStr,
+    /// read-only property, always `1`
+    pub version: u8,
+    /// for components, the message they were attached to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<Box<Message>>,
+    /// bitwise set of permissions the app has within the channel the
+    /// interaction was sent from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_permissions: Option<PermissionFlags>,
+    /// selected language of the invoking user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<RcStr>,
+    /// guild's preferred locale, if invoked in a guild
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guild_locale: Option<RcStr>,
+    /// context where the interaction was triggered from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<InteractionContext>,
+}
+
+impl Interaction {
+    /// The name of the invoked command, for application command and
+    /// autocomplete interactions
+    pub fn command_name(&self) -> Option<&str> {
+        // The types are numbered rather than actual flags, so they can't be combined
+        if self.r#type != InteractionType::APPLICATION_COMMAND
+            && self.r#type != InteractionType::APPLICATION_COMMAND_AUTOCOMPLETE
+        {
+            return None;
+        }
+
+        self.data.as_ref()?.name.as_deref()
+    }
+}
+
+/// The data of application command, component and modal interactions, which
+/// each only send some of these fields
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InteractionData {
+    /// the ID of the invoked command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<CommandId>,
+    /// the name of the invoked command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<RcStr>,
+    /// the type of the invoked command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<ApplicationCommandType>,
+    /// converted users + roles + channels + attachments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved: Option<ResolvedData>,
+    /// the params + values from the user
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<InteractionDataOption>,
+    /// the id of the guild the command is registered to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<GuildId>,
+    /// id of the user or message targeted by a user or message command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<TargetId>,
+    /// the custom_id of the component or modal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_id: Option<RcStr>,
+    /// the type of the component
+    // TODO use `ComponentType` once it (de)serializes as an integer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_type: Option<u8>,
+    /// values the user selected in a select menu component
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<RcStr>>,
+    /// the values submitted by the user in a modal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<JsonValue>>,
+}
+
 #[turbo_tasks::value(shared, serialization = "custom")]
 #[derive(Clone, Debug, Serialize, Deserialize, TaskInput, Hash)]
 pub struct InteractionResponse {
@@ -123,7 +240,7 @@ pub struct InteractionCallbackMessagesData {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Vec<Component>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub attachments: Option<Vec<Attachment>>,
+    pub attachments: Option<Vec<AttachmentForMessageCreateOrEdit>>,
 }
 
 #[turbo_tasks::value(shared, serialization = "custom")]
@@ -0,0 +1,297 @@
+//! Resolves the options of an application command interaction into values
+//! keyed by option name, so handlers don't have to walk `data.options` and
+//! `data.resolved` themselves.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value as JsonValue};
+
+use crate::{
+    application::command::{ApplicationCommandOption, ApplicationCommandOptionType},
+    channel::{AttachmentOther, Channel},
+    guild::{member::GuildMember, role::Role},
+    user::User,
+};
+
+/// An option as it is received in the data of an application command
+/// interaction
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct InteractionDataOption {
+    pub name: String,
+    pub r#type: ApplicationCommandOptionType,
+    /// the value given by the user, the id of the entity for users, roles,
+    /// channels and attachments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<JsonValue>,
+    /// the options of a subcommand or subcommand group
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<InteractionDataOption>,
+    /// whether this option is the one the user is typing in, for autocomplete
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focused: Option<bool>,
+}
+
+/// The entities referenced by the options of an interaction, keyed by their id
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ResolvedData {
+    #[serde(default)]
+    pub users: HashMap<String, User>,
+    /// partial members, without `user`, `deaf` and `mute`
+    #[serde(default)]
+    pub members: HashMap<String, GuildMember>,
+    #[serde(default)]
+    pub roles: HashMap<String, Role>,
+    /// partial channels, with only `id`, `name`, `type`, `permissions`,
+    /// `thread_metadata` and `parent_id`
+    #[serde(default)]
+    pub channels: HashMap<String, Channel>,
+    /// partial messages, which are kept as they are received
+    #[serde(default)]
+    pub messages: HashMap<String, JsonValue>,
+    #[serde(default)]
+    pub attachments: HashMap<String, AttachmentOther>,
+}
+
+/// The options that are passed to a command handler
+#[derive(Clone, Debug, Default, Serialize, PartialEq)]
+pub struct ResolvedOptions {
+    /// the invoked subcommand, prefixed by its group, e.g. `"role add"`
+    pub subcommand: Option<String>,
+    /// the options of the (sub)command keyed by name
+    pub options: Map<String, JsonValue>,
+}
+
+/// Resolves the received `options` with the entities from `resolved`.
+///
+/// When the `definition` of the command is known, options the user left out are
+/// included as `null`.
+pub fn resolve_options(
+    options: &[InteractionDataOption],
+    resolved: &ResolvedData,
+    definition: Option<&[ApplicationCommandOption]>,
+) -> ResolvedOptions {
+    let mut options = options;
+    let mut definition = definition;
+    let mut path = vec![];
+
+    // A subcommand (group) is always the only option of its parent
+    while let [option] = options {
+        if !is_subcommand(option.r#type) {
+            break;
+        }
+
+        path.push(option.name.clone());
+        definition = definition
+            .and_then(|definition| {
+                definition
+                    .iter()
+                    .find(|definition| definition.name == option.name)
+            })
+            .map(|definition| definition.options.as_deref().unwrap_or_default());
+        options = &option.options;
+    }
+
+    let mut resolved_options = Map::new();
+    for option in definition.unwrap_or_default() {
+        resolved_options.insert(option.name.clone(), JsonValue::Null);
+    }
+    for option in options {
+        resolved_options.insert(option.name.clone(), resolve_value(option, resolved));
+    }
+
+    ResolvedOptions {
+        subcommand: (!path.is_empty()).then(|| path.join(" ")),
+        options: resolved_options,
+    }
+}
+
+fn resolve_value(option: &InteractionDataOption, resolved: &ResolvedData) -> JsonValue {
+    let Some(value) = &option.value else {
+        return JsonValue::Null;
+    };
+
+    match option.r#type {
+        ApplicationCommandOptionType::User => resolve_user(value, resolved),
+        ApplicationCommandOptionType::Mentionable => {
+            if value
+                .as_str()
+                .is_some_and(|id| resolved.users.contains_key(id))
+            {
+                resolve_user(value, resolved)
+            } else {
+                resolve_entity(value, &resolved.roles)
+            }
+        }
+        ApplicationCommandOptionType::Role => resolve_entity(value, &resolved.roles),
+        ApplicationCommandOptionType::Channel => resolve_entity(value, &resolved.channels),
+        ApplicationCommandOptionType::Attachment => resolve_entity(value, &resolved.attachments),
+        _ => value.clone(),
+    }
+}
+
+/// Falls back to the id when the entity wasn't resolved
+fn resolve_entity<T: Serialize>(value: &JsonValue, entities: &HashMap<String, T>) -> JsonValue {
+    value
+        .as_str()
+        .and_then(|id| entities.get(id))
+        .and_then(|entity| serde_json::to_value(entity).ok())
+        .unwrap_or_else(|| value.clone())
+}
+
+/// Users are resolved together with their member, which is only available in
+/// guilds
+fn resolve_user(value: &JsonValue, resolved: &ResolvedData) -> JsonValue {
+    let Some(user) = value.as_str().and_then(|id| resolved.users.get(id)) else {
+        return value.clone();
+    };
+
+    json!({
+        "user": user,
+        "member": value.as_str().and_then(|id| resolved.members.get(id)),
+    })
+}
+
+fn is_subcommand(r#type: ApplicationCommandOptionType) -> bool {
+    matches!(
+        r#type,
+        ApplicationCommandOptionType::SubCommand | ApplicationCommandOptionType::SubCommandGroup
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value as JsonValue};
+
+    use super::{resolve_options, InteractionDataOption, ResolvedData};
+    use crate::application::command::ApplicationCommandOption;
+
+    fn resolve(
+        options: JsonValue,
+        resolved: JsonValue,
+        definition: Option<JsonValue>,
+    ) -> (Option<String>, JsonValue) {
+        let options = serde_json::from_value::<Vec<InteractionDataOption>>(options).unwrap();
+        let resolved = serde_json::from_value::<ResolvedData>(resolved).unwrap();
+        let definition = definition.map(|definition| {
+            serde_json::from_value::<Vec<ApplicationCommandOption>>(definition).unwrap()
+        });
+
+        let resolved = resolve_options(&options, &resolved, definition.as_deref());
+        (resolved.subcommand, JsonValue::Object(resolved.options))
+    }
+
+    #[test]
+    fn test_resolve_primitive_options() {
+        assert_eq!(
+            resolve(
+                json!([
+                    { "name": "reason", "type": 3, "value": "spam" },
+                    { "name": "days", "type": 4, "value": 7 },
+                    { "name": "silent", "type": 5, "value": true }
+                ]),
+                json!({}),
+                None
+            ),
+            (None, json!({ "reason": "spam", "days": 7, "silent": true }))
+        );
+    }
+
+    #[test]
+    fn test_resolve_entities() {
+        let resolved = json!({
+            "users": {
+                "1": { "id": "1", "username": "fujinoki", "discriminator": "0" }
+            },
+            "members": {
+                "1": { "nick": "fuji", "roles": ["2"], "joined_at": "2024-01-01T00:00:00+00:00" }
+            },
+            "roles": {
+                "2": {
+                    "id": "2",
+                    "name": "mod",
+                    "color": 0,
+                    "hoist": false,
+                    "position": 1,
+                    "permissions": "8",
+                    "managed": false,
+                    "mentionable": true
+                }
+            },
+            "channels": {
+                "3": { "id": "3", "type": 0, "name": "general", "permissions": "8" }
+            },
+            "attachments": {
+                "4": {
+                    "id": "4",
+                    "filename": "fujinoki.png",
+                    "size": 1024,
+                    "url": "https://cdn.discordapp.com/attachments/3/4/fujinoki.png",
+                    "proxy_url": "https://media.discordapp.net/attachments/3/4/fujinoki.png"
+                }
+            }
+        });
+
+        let (subcommand, options) = resolve(
+            json!([
+                { "name": "user", "type": 6, "value": "1" },
+                { "name": "role", "type": 8, "value": "2" },
+                { "name": "mention", "type": 9, "value": "2" },
+                { "name": "channel", "type": 7, "value": "3" },
+                { "name": "attachment", "type": 11, "value": "4" },
+                { "name": "missing", "type": 8, "value": "5" }
+            ]),
+            resolved,
+            None,
+        );
+
+        assert_eq!(subcommand, None);
+        assert_eq!(options["user"]["user"]["username"], "fujinoki");
+        assert_eq!(options["user"]["member"]["nick"], "fuji");
+        assert_eq!(options["role"]["name"], "mod");
+        assert_eq!(options["role"]["permissions"], "8");
+        assert_eq!(options["mention"], options["role"]);
+        assert_eq!(options["channel"]["name"], "general");
+        assert_eq!(options["channel"]["type"], 0);
+        assert_eq!(options["attachment"]["filename"], "fujinoki.png");
+        assert_eq!(options["missing"], "5");
+    }
+
+    #[test]
+    fn test_resolve_subcommand_with_definition() {
+        let definition = json!([{
+            "type": 2,
+            "name": "role",
+            "description": "Manage roles",
+            "options": [{
+                "type": 1,
+                "name": "add",
+                "description": "Add a role",
+                "options": [
+                    { "type": 8, "name": "role", "description": "The role", "required": true },
+                    { "type": 3, "name": "reason", "description": "Why" }
+                ]
+            }]
+        }]);
+
+        assert_eq!(
+            resolve(
+                json!([{
+                    "name": "role",
+                    "type": 2,
+                    "options": [{
+                        "name": "add",
+                        "type": 1,
+                        "options": [{ "name": "role", "type": 8, "value": "2" }]
+                    }]
+                }]),
+                json!({}),
+                Some(definition)
+            ),
+            (
+                Some("role add".to_string()),
+                json!({ "role": "2", "reason": null })
+            )
+        );
+    }
+}
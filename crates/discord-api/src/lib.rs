@@ -13,6 +13,7 @@
 #![allow(deprecated)]
 
 pub mod application;
+pub mod auto_moderation;
 pub mod channel;
 pub mod emoji;
 pub mod gateway;
@@ -22,11 +23,16 @@ pub mod interactions;
 pub mod issue;
 pub mod locales;
 pub mod permissions;
+pub mod poll;
 pub mod rest;
+pub mod scheduled_event;
+pub mod sticker;
 pub mod team;
 pub mod timestamp;
 pub mod user;
 mod utils;
+pub mod voice;
+pub mod webhook;
 
 pub use rest::routes::Routes;
 
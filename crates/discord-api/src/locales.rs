@@ -31,6 +31,10 @@ lazy_static! {
             name: "Spanish".into(),
             native_name: "Español".into(),
         },
+        es_419: Locale {
+            name: "Spanish, LATAM".into(),
+            native_name: "Español, LATAM".into(),
+        },
         fr: Locale {
             name: "French".into(),
             native_name: "Français".into(),
@@ -135,6 +139,18 @@ lazy_static! {
     .cell();
 }
 
+/// The codes Discord uses for the fields of [Locales], e.g. in
+/// `name_localizations` or the `locale` of an interaction
+pub const LOCALE_CODES: [&str; 32] = [
+    "id", "da", "de", "en-GB", "en-US", "es-ES", "es-419", "fr", "hr", "it", "lt", "hu", "nl",
+    "no", "pl", "pt-BR", "ro", "fi", "sv-SE", "vi", "tr", "cs", "el", "bg", "ru", "uk", "hi", "th",
+    "zh-CN", "ja", "zh-TW", "ko",
+];
+
+pub fn is_locale(code: &str) -> bool {
+    LOCALE_CODES.contains(&code)
+}
+
 #[turbo_tasks::value(shared, serialization = "custom")]
 #[derive(Clone, Debug, Serialize, Deserialize, TaskInput, Hash)]
 pub struct Locales {
@@ -144,6 +160,7 @@ pub struct Locales {
     pub en_gb: Locale,
     pub en_us: Locale,
     pub es_es: Locale,
+    pub es_419: Locale,
     pub fr: Locale,
     pub hr: Locale,
     pub it: Locale,
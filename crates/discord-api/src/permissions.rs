@@ -1,7 +1,14 @@
 use bitflags::bitflags;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use turbopack_binding::turbo::tasks as turbo_tasks;
 
+use crate::{
+    channel::{Channel, PermissionOverwrite, PermissionOverwriteType},
+    guild::{Guild, GuildMember},
+    id::GuildId,
+    timestamp::Timestamp,
+};
+
 #[derive(Debug, Default)]
 pub struct Permissions(pub u64);
 
@@ -66,7 +73,7 @@ impl Permissions {
 
 bitflags! {
     #[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
-    #[derive(Serialize, Default)]
+    #[derive(Default)]
     pub struct PermissionFlags: u64 {
         /// Allows creation of instant invites (T, V, S)
         const CREATE_INSTANT_INVITE = 1 << 0;
@@ -165,12 +172,457 @@ bitflags! {
     }
 }
 
+/// Discord sends permissions as a string, as they don't fit into a JavaScript
+/// number
+impl Serialize for PermissionFlags {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.bits().to_string())
+    }
+}
+
 impl<'de> Deserialize<'de> for PermissionFlags {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let value = u64::deserialize(deserializer)?;
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Bits {
+            String(String),
+            Integer(u64),
+        }
+
+        let value = match Bits::deserialize(deserializer)? {
+            Bits::String(value) => value.parse().map_err(serde::de::Error::custom)?,
+            Bits::Integer(value) => value,
+        };
         Ok(PermissionFlags::from_bits_truncate(value))
     }
 }
+
+/// The permissions of a member in the guild, before the overwrites of a channel
+/// are applied.
+///
+/// The owner of the guild and members with `ADMINISTRATOR` have every
+/// permission. The `user` of the member is needed to tell whether they own the
+/// guild. Timed out members only keep `VIEW_CHANNEL` and
+/// `READ_MESSAGE_HISTORY`.
+pub fn base_permissions(guild: &Guild, member: &GuildMember) -> PermissionFlags {
+    if member
+        .user
+        .as_ref()
+        .is_some_and(|user| user.id == guild.owner_id)
+    {
+        return PermissionFlags::all();
+    }
+
+    // The @everyone role has the same id as the guild
+    let mut permissions = guild
+        .roles
+        .iter()
+        .find(|role| role.id.0 == guild.id.0)
+        .map(|role| role.permissions)
+        .unwrap_or_default();
+
+    for role in guild
+        .roles
+        .iter()
+        .filter(|role| member.roles.contains(&role.id))
+    {
+        permissions |= role.permissions;
+    }
+
+    if permissions.contains(PermissionFlags::ADMINISTRATOR) {
+        return PermissionFlags::all();
+    }
+
+    without_timeout(permissions, member)
+}
+
+/// Timed out members can only view channels and read their history
+fn without_timeout(permissions: PermissionFlags, member: &GuildMember) -> PermissionFlags {
+    if member.is_timed_out(Timestamp::now()) {
+        permissions & (PermissionFlags::VIEW_CHANNEL | PermissionFlags::READ_MESSAGE_HISTORY)
+    } else {
+        permissions
+    }
+}
+
+/// Applies the permission overwrites of a channel to the `base` permissions of
+/// a member, in the order Discord does: the @everyone overwrite, then the
+/// overwrites of the member's roles, then the overwrite of the member itself.
+pub fn apply_overwrites(
+    base: PermissionFlags,
+    guild_id: GuildId,
+    member: &GuildMember,
+    overwrites: &[PermissionOverwrite],
+) -> PermissionFlags {
+    if base.contains(PermissionFlags::ADMINISTRATOR) {
+        return PermissionFlags::all();
+    }
+
+    let mut permissions = base;
+
+    if let Some(everyone) = overwrites.iter().find(|overwrite| {
+        overwrite.r#type == PermissionOverwriteType::Role && overwrite.id.0 == guild_id.0
+    }) {
+        permissions &= !everyone.deny;
+        permissions |= everyone.allow;
+    }
+
+    // The overwrites of all roles are combined, so an allow of one role wins over
+    // a deny of another
+    let mut allow = PermissionFlags::empty();
+    let mut deny = PermissionFlags::empty();
+    for overwrite in overwrites.iter().filter(|overwrite| {
+        overwrite.r#type == PermissionOverwriteType::Role
+            && member.roles.iter().any(|role| role.0 == overwrite.id.0)
+    }) {
+        allow |= overwrite.allow;
+        deny |= overwrite.deny;
+    }
+    permissions &= !deny;
+    permissions |= allow;
+
+    if let Some(user) = &member.user {
+        if let Some(overwrite) = overwrites.iter().find(|overwrite| {
+            overwrite.r#type == PermissionOverwriteType::Member && overwrite.id.0 == user.id.0
+        }) {
+            permissions &= !overwrite.deny;
+            permissions |= overwrite.allow;
+        }
+    }
+
+    permissions
+}
+
+/// The permissions of a member in a channel of the guild, including the
+/// permissions that are implicitly denied: everything without `VIEW_CHANNEL`,
+/// and what depends on sending messages without `SEND_MESSAGES`.
+///
+/// Threads don't have overwrites, pass their parent channel instead.
+pub fn channel_permissions(
+    guild: &Guild,
+    channel: &Channel,
+    member: &GuildMember,
+) -> PermissionFlags {
+    let base = base_permissions(guild, member);
+    let mut permissions = apply_overwrites(
+        base,
+        guild.id,
+        member,
+        channel.permission_overwrites.as_deref().unwrap_or_default(),
+    );
+
+    if permissions.contains(PermissionFlags::ADMINISTRATOR) {
+        return permissions;
+    }
+
+    // The overwrites of a channel can't lift a timeout
+    permissions = without_timeout(permissions, member);
+    if !permissions.contains(PermissionFlags::VIEW_CHANNEL) {
+        return PermissionFlags::empty();
+    }
+
+    if !permissions.contains(PermissionFlags::SEND_MESSAGES) {
+        permissions -= PermissionFlags::SEND_TTS_MESSAGES
+            | PermissionFlags::MENTION_EVERYONE
+            | PermissionFlags::EMBED_LINKS
+            | PermissionFlags::ATTACH_FILES;
+    }
+
+    permissions
+}
+
+/// The permissions out of `flags` that the member is missing, in the `channel`
+/// or in the guild when there is none
+pub fn missing_permissions(
+    guild: &Guild,
+    channel: Option<&Channel>,
+    member: &GuildMember,
+    flags: PermissionFlags,
+) -> PermissionFlags {
+    let permissions = match channel {
+        Some(channel) => channel_permissions(guild, channel, member),
+        None => base_permissions(guild, member),
+    };
+
+    flags - permissions
+}
+
+/// Whether the member has all of the permissions in `flags`, in the `channel`
+/// or in the guild when there is none
+pub fn member_can(
+    guild: &Guild,
+    channel: Option<&Channel>,
+    member: &GuildMember,
+    flags: PermissionFlags,
+) -> bool {
+    missing_permissions(guild, channel, member, flags).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value as JsonValue};
+
+    use super::{
+        apply_overwrites, base_permissions, channel_permissions, member_can, missing_permissions,
+        PermissionFlags,
+    };
+    use crate::{
+        channel::Channel,
+        guild::{Guild, GuildMember, Role},
+        id::GuildId,
+    };
+
+    const GUILD_ID: u64 = 197038439483310086;
+    const OWNER_ID: u64 = 73193882359173120;
+    const USER_ID: u64 = 80351110224678912;
+    const MODERATOR_ID: u64 = 197039217807687680;
+    const MUTED_ID: u64 = 197039217807687681;
+
+    fn role(id: u64, permissions: PermissionFlags) -> Role {
+        serde_json::from_value(json!({
+            "id": id.to_string(),
+            "name": "role",
+            "color": 0,
+            "hoist": false,
+            "position": 0,
+            "permissions": permissions,
+            "managed": false,
+            "mentionable": false
+        }))
+        .unwrap()
+    }
+
+    fn guild(everyone: PermissionFlags) -> Guild {
+        let mut guild: Guild =
+            serde_json::from_str(include_str!("../fixtures/guild.json")).unwrap();
+        guild.roles = vec![
+            role(GUILD_ID, everyone),
+            role(
+                MODERATOR_ID,
+                PermissionFlags::MANAGE_MESSAGES | PermissionFlags::KICK_MEMBERS,
+            ),
+            role(MUTED_ID, PermissionFlags::empty()),
+        ];
+        guild
+    }
+
+    fn member(user_id: u64, roles: &[u64]) -> GuildMember {
+        serde_json::from_value(json!({
+            "user": {
+                "id": user_id.to_string(),
+                "username": "fujinoki",
+                "discriminator": "0"
+            },
+            "roles": roles.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+            "joined_at": "2015-04-26T06:26:56.936000+00:00"
+        }))
+        .unwrap()
+    }
+
+    fn channel(overwrites: JsonValue) -> Channel {
+        serde_json::from_value(json!({
+            "id": "41771983423143937",
+            "type": 0,
+            "guild_id": GUILD_ID.to_string(),
+            "permission_overwrites": overwrites
+        }))
+        .unwrap()
+    }
+
+    fn default_permissions() -> PermissionFlags {
+        PermissionFlags::VIEW_CHANNEL
+            | PermissionFlags::SEND_MESSAGES
+            | PermissionFlags::EMBED_LINKS
+            | PermissionFlags::ATTACH_FILES
+    }
+
+    #[test]
+    fn test_base_permissions() {
+        let guild = guild(default_permissions());
+
+        assert_eq!(
+            base_permissions(&guild, &member(USER_ID, &[])),
+            default_permissions()
+        );
+        assert_eq!(
+            base_permissions(&guild, &member(USER_ID, &[MODERATOR_ID])),
+            default_permissions()
+                | PermissionFlags::MANAGE_MESSAGES
+                | PermissionFlags::KICK_MEMBERS
+        );
+        // Roles the guild doesn't have are ignored
+        assert_eq!(
+            base_permissions(&guild, &member(USER_ID, &[1])),
+            default_permissions()
+        );
+    }
+
+    #[test]
+    fn test_owner_and_administrator() {
+        let mut guild = guild(PermissionFlags::empty());
+        assert_eq!(
+            base_permissions(&guild, &member(OWNER_ID, &[])),
+            PermissionFlags::all()
+        );
+
+        guild.roles[1].permissions = PermissionFlags::ADMINISTRATOR;
+        let administrator = member(USER_ID, &[MODERATOR_ID]);
+        assert_eq!(
+            base_permissions(&guild, &administrator),
+            PermissionFlags::all()
+        );
+
+        // Overwrites don't apply to administrators
+        let channel = channel(json!([
+            { "id": GUILD_ID.to_string(), "type": 0, "allow": "0", "deny": "1024" },
+            { "id": USER_ID.to_string(), "type": 1, "allow": "0", "deny": "1024" }
+        ]));
+        assert_eq!(
+            channel_permissions(&guild, &channel, &administrator),
+            PermissionFlags::all()
+        );
+    }
+
+    #[test]
+    fn test_overwrite_order() {
+        let guild = guild(default_permissions());
+        let base = default_permissions();
+        let send = PermissionFlags::SEND_MESSAGES;
+
+        // @everyone denies, a role allows
+        let overwrites = channel(json!([
+            { "id": GUILD_ID.to_string(), "type": 0, "allow": "0", "deny": send },
+            { "id": MODERATOR_ID.to_string(), "type": 0, "allow": send, "deny": "0" }
+        ]))
+        .permission_overwrites
+        .unwrap();
+        assert!(
+            !apply_overwrites(base, GuildId(GUILD_ID), &member(USER_ID, &[]), &overwrites)
+                .contains(send)
+        );
+        assert!(apply_overwrites(
+            base,
+            GuildId(GUILD_ID),
+            &member(USER_ID, &[MODERATOR_ID]),
+            &overwrites
+        )
+        .contains(send));
+
+        // An allow of one role wins over a deny of another
+        let overwrites = channel(json!([
+            { "id": MUTED_ID.to_string(), "type": 0, "allow": "0", "deny": send },
+            { "id": MODERATOR_ID.to_string(), "type": 0, "allow": send, "deny": "0" }
+        ]))
+        .permission_overwrites
+        .unwrap();
+        assert!(apply_overwrites(
+            base,
+            GuildId(GUILD_ID),
+            &member(USER_ID, &[MUTED_ID, MODERATOR_ID]),
+            &overwrites
+        )
+        .contains(send));
+
+        // The member overwrite wins over the overwrites of its roles
+        let overwrites = channel(json!([
+            { "id": MODERATOR_ID.to_string(), "type": 0, "allow": send, "deny": "0" },
+            { "id": USER_ID.to_string(), "type": 1, "allow": "0", "deny": send }
+        ]))
+        .permission_overwrites
+        .unwrap();
+        assert!(!apply_overwrites(
+            base,
+            GuildId(GUILD_ID),
+            &member(USER_ID, &[MODERATOR_ID]),
+            &overwrites
+        )
+        .contains(send));
+    }
+
+    #[test]
+    fn test_implicit_permissions() {
+        let guild = guild(default_permissions());
+        let member = member(USER_ID, &[]);
+
+        let hidden = channel(json!([
+            { "id": GUILD_ID.to_string(), "type": 0, "allow": "0", "deny": PermissionFlags::VIEW_CHANNEL }
+        ]));
+        assert_eq!(
+            channel_permissions(&guild, &hidden, &member),
+            PermissionFlags::empty()
+        );
+
+        let read_only = channel(json!([
+            { "id": GUILD_ID.to_string(), "type": 0, "allow": "0", "deny": PermissionFlags::SEND_MESSAGES }
+        ]));
+        assert_eq!(
+            channel_permissions(&guild, &read_only, &member),
+            PermissionFlags::VIEW_CHANNEL
+        );
+    }
+
+    #[test]
+    fn test_member_can() {
+        let guild = guild(default_permissions());
+        let member = member(USER_ID, &[]);
+        let channel = channel(json!([]));
+
+        assert!(member_can(
+            &guild,
+            Some(&channel),
+            &member,
+            PermissionFlags::SEND_MESSAGES
+        ));
+        assert!(!member_can(
+            &guild,
+            None,
+            &member,
+            PermissionFlags::SEND_MESSAGES | PermissionFlags::BAN_MEMBERS
+        ));
+        assert_eq!(
+            missing_permissions(
+                &guild,
+                None,
+                &member,
+                PermissionFlags::SEND_MESSAGES | PermissionFlags::BAN_MEMBERS
+            ),
+            PermissionFlags::BAN_MEMBERS
+        );
+    }
+
+    #[test]
+    fn test_timed_out_member() {
+        let guild = guild(default_permissions() | PermissionFlags::READ_MESSAGE_HISTORY);
+        let mut member = member(USER_ID, &[MODERATOR_ID]);
+        member.communication_disabled_until = Some("2999-01-01T00:00:00+00:00".into());
+        let timed_out = PermissionFlags::VIEW_CHANNEL | PermissionFlags::READ_MESSAGE_HISTORY;
+
+        assert_eq!(base_permissions(&guild, &member), timed_out);
+        // An overwrite that allows sending messages doesn't lift the timeout
+        let channel = channel(json!([
+            { "id": USER_ID.to_string(), "type": 1, "allow": PermissionFlags::SEND_MESSAGES, "deny": "0" }
+        ]));
+        assert_eq!(channel_permissions(&guild, &channel, &member), timed_out);
+
+        // The timeout is over
+        member.communication_disabled_until = Some("2015-01-01T00:00:00+00:00".into());
+        assert!(member_can(
+            &guild,
+            Some(&channel),
+            &member,
+            PermissionFlags::SEND_MESSAGES | PermissionFlags::MANAGE_MESSAGES
+        ));
+
+        // Administrators aren't affected by timeouts
+        let mut guild = guild;
+        guild.roles[1].permissions = PermissionFlags::ADMINISTRATOR;
+        member.communication_disabled_until = Some("2999-01-01T00:00:00+00:00".into());
+        assert_eq!(base_permissions(&guild, &member), PermissionFlags::all());
+    }
+}
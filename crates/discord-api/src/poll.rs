@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr};
+
+use crate::{emoji::Emoji, impl_serde_for_integer_enum};
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Poll {
+    /// the question of the poll, only `text` is supported
+    pub question: PollMedia,
+    /// each of the answers available in the poll
+    pub answers: Vec<PollAnswer>,
+    /// the time when the poll ends, can be null for polls that never expire
+    pub expiry: Option<RcStr>,
+    /// whether a user can select multiple answers
+    pub allow_multiselect: bool,
+    /// the layout type of the poll
+    pub layout_type: PollLayoutType,
+    /// the results of the poll, only present once votes have been counted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub results: Option<PollResults>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PollLayoutType {
+    /// the default layout type
+    Default = 1,
+}
+
+impl_serde_for_integer_enum!(PollLayoutType, u8, [Default]);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PollMedia {
+    /// the text of the field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<RcStr>,
+    /// the emoji of the field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<Emoji>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PollAnswer {
+    /// the id of the answer, only sent as part of responses from Discord's API
+    /// or Gateway
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer_id: Option<u32>,
+    /// the data of the answer
+    pub poll_media: PollMedia,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PollResults {
+    /// whether the votes have been precisely counted
+    pub is_finalized: bool,
+    /// the counts for each answer, answers without votes are left out
+    pub answer_counts: Vec<PollAnswerCount>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PollAnswerCount {
+    /// the answer_id
+    pub id: u32,
+    /// the number of votes for this answer
+    pub count: u32,
+    /// whether the current user voted for this answer
+    pub me_voted: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Poll;
+    use crate::utils::assert_round_trip;
+
+    #[test]
+    fn test_poll_round_trip() {
+        let poll = assert_round_trip::<Poll>(include_str!("../fixtures/poll.json"));
+
+        assert_eq!(poll.answers.len(), 2);
+        assert_eq!(poll.answers[1].answer_id, Some(2));
+
+        let results = poll.results.unwrap();
+        assert!(!results.is_finalized);
+        assert_eq!(results.answer_counts[0].count, 3);
+    }
+}
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
 use anyhow::Result;
-use reqwest::header;
+use reqwest::{header, Method};
+use serde_json::Value as JsonValue;
 use turbopack_binding::{
     turbo::{
         tasks as turbo_tasks,
@@ -13,9 +14,13 @@ use turbopack_binding::{
 
 use crate::{
     application::command::ApplicationCommand,
-    id::ApplicationId,
+    id::{ApplicationId, GuildId},
     issue::DiscordApiIssue,
-    rest::{http::fetch_error_to_string, HTTP},
+    rest::{
+        http::{fetch_error_to_string, RequestBody},
+        request::{authorization, expect_success, parse_response, queries, request},
+        HTTP,
+    },
     Routes,
 };
 
@@ -103,3 +108,100 @@ pub async fn get_global_application_commands(
         }
     }
 }
+
+/// Same as [`get_global_application_commands`], but keeps the commands as they
+/// were received so they can be compared with local definitions. Unlike it the
+/// commands are requested every time, so they are never out of date.
+pub async fn get_global_application_commands_json(
+    application_id: ApplicationId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Vec<JsonValue>>> {
+    let response = request(
+        Method::GET,
+        Routes::application_commands(application_id),
+        authorization(token),
+        queries([("with_localizations", Some(true.to_string()))]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "Get global application commands",
+        file_path,
+    ))
+}
+
+/// Replaces all global commands of the application with `commands`, commands
+/// that are not included are deleted
+pub async fn bulk_overwrite_global_application_commands(
+    application_id: ApplicationId,
+    commands: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::PUT,
+        Routes::application_commands(application_id),
+        authorization(token),
+        None,
+        RequestBody::Json(commands),
+    )
+    .await?;
+
+    Ok(expect_success(
+        response,
+        "Bulk overwrite global application commands",
+        file_path,
+    ))
+}
+
+/// Same as [`get_global_application_commands_json`], but for the commands
+/// registered to a single guild
+pub async fn get_guild_application_commands_json(
+    application_id: ApplicationId,
+    guild_id: GuildId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Vec<JsonValue>>> {
+    let response = request(
+        Method::GET,
+        Routes::application_guild_commands(application_id, guild_id),
+        authorization(token),
+        queries([("with_localizations", Some(true.to_string()))]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "Get guild application commands",
+        file_path,
+    ))
+}
+
+/// Replaces all commands of the application in a guild with `commands`,
+/// commands that are not included are deleted
+pub async fn bulk_overwrite_guild_application_commands(
+    application_id: ApplicationId,
+    guild_id: GuildId,
+    commands: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::PUT,
+        Routes::application_guild_commands(application_id, guild_id),
+        authorization(token),
+        None,
+        RequestBody::Json(commands),
+    )
+    .await?;
+
+    Ok(expect_success(
+        response,
+        "Bulk overwrite guild application commands",
+        file_path,
+    ))
+}
@@ -14,6 +14,7 @@ use turbopack_binding::{
 
 pub mod command;
 pub mod commands;
+pub mod permissions;
 
 use super::{http::fetch_error_to_string, HTTP};
 use crate::{application::Application, issue::DiscordApiIssue, Routes};
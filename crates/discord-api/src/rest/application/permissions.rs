@@ -0,0 +1,91 @@
+use anyhow::Result;
+use reqwest::Method;
+use serde_json::json;
+use turbopack_binding::turbo::{tasks::Vc, tasks_fs::FileSystemPath};
+
+use crate::{
+    application::command::{ApplicationCommandPermission, GuildApplicationCommandPermissions},
+    id::{ApplicationId, CommandId, GuildId},
+    rest::{
+        http::RequestBody,
+        request::{authorization, bearer_authorization, parse_response, request},
+    },
+    Routes,
+};
+
+/// The permission overwrites of all commands of the application in a guild,
+/// commands without overwrites are left out
+pub async fn get_guild_application_command_permissions(
+    application_id: ApplicationId,
+    guild_id: GuildId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<GuildApplicationCommandPermissions>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_application_commands_permissions(application_id, guild_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "Get guild application command permissions",
+        file_path,
+    )
+    .unwrap_or_default())
+}
+
+pub async fn get_application_command_permissions(
+    application_id: ApplicationId,
+    guild_id: GuildId,
+    command_id: CommandId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<GuildApplicationCommandPermissions>> {
+    let response = request(
+        Method::GET,
+        Routes::application_command_permissions(application_id, guild_id, command_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "Get application command permissions",
+        file_path,
+    ))
+}
+
+/// Replaces the permission overwrites of a command in a guild.
+///
+/// Discord only allows this with the OAuth2 access token of a user that can
+/// manage the guild and its roles, which needs the
+/// `applications.commands.permissions.update` scope. A bot token is rejected.
+pub async fn edit_application_command_permissions(
+    application_id: ApplicationId,
+    guild_id: GuildId,
+    command_id: CommandId,
+    permissions: &[ApplicationCommandPermission],
+    access_token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<GuildApplicationCommandPermissions>> {
+    let response = request(
+        Method::PUT,
+        Routes::application_command_permissions(application_id, guild_id, command_id),
+        bearer_authorization(access_token),
+        None,
+        RequestBody::Json(&json!({ "permissions": permissions })),
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "Edit application command permissions",
+        file_path,
+    ))
+}
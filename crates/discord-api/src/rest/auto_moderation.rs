@@ -0,0 +1,120 @@
+use anyhow::Result;
+use reqwest::Method;
+use serde_json::Value as JsonValue;
+use turbopack_binding::turbo::{tasks::Vc, tasks_fs::FileSystemPath};
+
+use super::{
+    http::RequestBody,
+    request::{authorization, expect_success, parse_response, request},
+};
+use crate::{
+    auto_moderation::AutoModerationRule,
+    id::{GenericId, GuildId, RuleId},
+    Routes,
+};
+
+pub async fn list_auto_moderation_rules(
+    guild_id: GuildId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<AutoModerationRule>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_auto_moderation_rules(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "List auto moderation rules", file_path).unwrap_or_default())
+}
+
+pub async fn get_auto_moderation_rule(
+    guild_id: GuildId,
+    rule_id: RuleId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<AutoModerationRule>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_auto_moderation_rule(guild_id, GenericId(rule_id.0)),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "Get auto moderation rule",
+        file_path,
+    ))
+}
+
+pub async fn create_auto_moderation_rule(
+    guild_id: GuildId,
+    rule: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<AutoModerationRule>> {
+    let response = request(
+        Method::POST,
+        Routes::guild_auto_moderation_rules(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Json(rule),
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "Create auto moderation rule",
+        file_path,
+    ))
+}
+
+pub async fn modify_auto_moderation_rule(
+    guild_id: GuildId,
+    rule_id: RuleId,
+    rule: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<AutoModerationRule>> {
+    let response = request(
+        Method::PATCH,
+        Routes::guild_auto_moderation_rule(guild_id, GenericId(rule_id.0)),
+        authorization(token),
+        None,
+        RequestBody::Json(rule),
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "Modify auto moderation rule",
+        file_path,
+    ))
+}
+
+pub async fn delete_auto_moderation_rule(
+    guild_id: GuildId,
+    rule_id: RuleId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::guild_auto_moderation_rule(guild_id, GenericId(rule_id.0)),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(
+        response,
+        "Delete auto moderation rule",
+        file_path,
+    ))
+}
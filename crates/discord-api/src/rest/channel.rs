@@ -0,0 +1,498 @@
+//! Channels, messages, reactions, pins and threads
+use anyhow::Result;
+use reqwest::Method;
+use serde_json::{json, Value as JsonValue};
+use turbopack_binding::turbo::{
+    tasks::{RcStr, Vc},
+    tasks_fs::FileSystemPath,
+};
+
+use super::{
+    http::{HttpFile, RequestBody},
+    request::{authorization, expect_success, parse_response, queries, request},
+    routes::{ArchivedStatus, UserIdOrMe},
+};
+use crate::{
+    channel::{message::Message, Channel, ThreadList, ThreadMember},
+    id::{ChannelId, GenericId, MessageId, UserId},
+    user::User,
+    Routes,
+};
+
+/// Emojis are either unicode emojis or `name:id` for custom emojis
+fn encode_emoji(emoji: &str) -> RcStr {
+    url::form_urlencoded::byte_serialize(emoji.as_bytes())
+        .collect::<String>()
+        .into()
+}
+
+pub async fn get_channel(
+    channel_id: ChannelId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Channel>> {
+    let response = request(
+        Method::GET,
+        Routes::channel(channel_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get channel", file_path))
+}
+
+pub async fn modify_channel(
+    channel_id: ChannelId,
+    channel: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Channel>> {
+    let response = request(
+        Method::PATCH,
+        Routes::channel(channel_id),
+        authorization(token),
+        None,
+        RequestBody::Json(channel),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Modify channel", file_path))
+}
+
+pub async fn delete_channel(
+    channel_id: ChannelId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Channel>> {
+    let response = request(
+        Method::DELETE,
+        Routes::channel(channel_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Delete channel", file_path))
+}
+
+pub async fn get_channel_messages(
+    channel_id: ChannelId,
+    before: Option<MessageId>,
+    after: Option<MessageId>,
+    limit: Option<u8>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<Message>> {
+    let response = request(
+        Method::GET,
+        Routes::channel_messages(channel_id),
+        authorization(token),
+        queries([
+            ("before", before.map(|id| id.to_string())),
+            ("after", after.map(|id| id.to_string())),
+            ("limit", limit.map(|limit| limit.to_string())),
+        ]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get channel messages", file_path).unwrap_or_default())
+}
+
+pub async fn get_channel_message(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Message>> {
+    let response = request(
+        Method::GET,
+        Routes::channel_message(channel_id, message_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get channel message", file_path))
+}
+
+/// Files are uploaded as `files[n]`, which `attachments` in `message` can refer
+/// to by index
+pub async fn create_message(
+    channel_id: ChannelId,
+    message: &JsonValue,
+    files: &[HttpFile],
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Message>> {
+    let response = request(
+        Method::POST,
+        Routes::channel_messages(channel_id),
+        authorization(token),
+        None,
+        RequestBody::with_files(message, files),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Create message", file_path))
+}
+
+pub async fn crosspost_message(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Message>> {
+    let response = request(
+        Method::POST,
+        Routes::channel_message_crosspost(channel_id, message_id),
+        authorization(token),
+        None,
+        RequestBody::Json(&json!({})),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Crosspost message", file_path))
+}
+
+pub async fn edit_message(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    message: &JsonValue,
+    files: &[HttpFile],
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Message>> {
+    let response = request(
+        Method::PATCH,
+        Routes::channel_message(channel_id, message_id),
+        authorization(token),
+        None,
+        RequestBody::with_files(message, files),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Edit message", file_path))
+}
+
+pub async fn delete_message(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::channel_message(channel_id, message_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Delete message", file_path))
+}
+
+/// Deletes 2-100 messages that are no older than 2 weeks
+pub async fn bulk_delete_messages(
+    channel_id: ChannelId,
+    message_ids: &[MessageId],
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::POST,
+        Routes::channel_bulk_delete(channel_id),
+        authorization(token),
+        None,
+        RequestBody::Json(&json!({ "messages": message_ids })),
+    )
+    .await?;
+
+    Ok(expect_success(response, "Bulk delete messages", file_path))
+}
+
+pub async fn create_reaction(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    emoji: &str,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::PUT,
+        Routes::channel_message_own_reaction(channel_id, message_id, encode_emoji(emoji)),
+        authorization(token),
+        None,
+        RequestBody::Json(&json!({})),
+    )
+    .await?;
+
+    Ok(expect_success(response, "Create reaction", file_path))
+}
+
+pub async fn delete_own_reaction(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    emoji: &str,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::channel_message_own_reaction(channel_id, message_id, encode_emoji(emoji)),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Delete own reaction", file_path))
+}
+
+pub async fn delete_user_reaction(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    emoji: &str,
+    user_id: UserId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::channel_message_user_reaction(channel_id, message_id, encode_emoji(emoji), user_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Delete user reaction", file_path))
+}
+
+pub async fn get_reactions(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    emoji: &str,
+    after: Option<UserId>,
+    limit: Option<u8>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<User>> {
+    let response = request(
+        Method::GET,
+        Routes::channel_message_reaction(channel_id, message_id, encode_emoji(emoji)),
+        authorization(token),
+        queries([
+            ("after", after.map(|id| id.to_string())),
+            ("limit", limit.map(|limit| limit.to_string())),
+        ]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get reactions", file_path).unwrap_or_default())
+}
+
+/// Deletes every reaction on a message, or only the reactions for `emoji`
+pub async fn delete_all_reactions(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    emoji: Option<&str>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let route = match emoji {
+        Some(emoji) => {
+            Routes::channel_message_reaction(channel_id, message_id, encode_emoji(emoji))
+        }
+        None => Routes::channel_message_all_reactions(channel_id, message_id),
+    };
+    let response = request(
+        Method::DELETE,
+        route,
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Delete all reactions", file_path))
+}
+
+pub async fn trigger_typing_indicator(
+    channel_id: ChannelId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::POST,
+        Routes::channel_typing(channel_id),
+        authorization(token),
+        None,
+        RequestBody::Json(&json!({})),
+    )
+    .await?;
+
+    Ok(expect_success(
+        response,
+        "Trigger typing indicator",
+        file_path,
+    ))
+}
+
+pub async fn get_pinned_messages(
+    channel_id: ChannelId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<Message>> {
+    let response = request(
+        Method::GET,
+        Routes::channel_pins(channel_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get pinned messages", file_path).unwrap_or_default())
+}
+
+pub async fn pin_message(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::PUT,
+        Routes::channel_pin(channel_id, message_id),
+        authorization(token),
+        None,
+        RequestBody::Json(&json!({})),
+    )
+    .await?;
+
+    Ok(expect_success(response, "Pin message", file_path))
+}
+
+pub async fn unpin_message(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::channel_pin(channel_id, message_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Unpin message", file_path))
+}
+
+/// Starts a thread from `message_id`, or a thread that isn't connected to a
+/// message when it's `None`
+pub async fn start_thread(
+    channel_id: ChannelId,
+    message_id: Option<MessageId>,
+    thread: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Channel>> {
+    let response = request(
+        Method::POST,
+        Routes::threads(GenericId(channel_id.0), message_id),
+        authorization(token),
+        None,
+        RequestBody::Json(thread),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Start thread", file_path))
+}
+
+/// Adds `user_id` to a thread, or the current user when it's `None`
+pub async fn add_thread_member(
+    thread_id: ChannelId,
+    user_id: Option<UserId>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let user_id = user_id.map_or(UserIdOrMe::Me, UserIdOrMe::UserId);
+    let response = request(
+        Method::PUT,
+        Routes::thread_members(GenericId(thread_id.0), Some(user_id)),
+        authorization(token),
+        None,
+        RequestBody::Json(&json!({})),
+    )
+    .await?;
+
+    Ok(expect_success(response, "Add thread member", file_path))
+}
+
+/// Removes `user_id` from a thread, or the current user when it's `None`
+pub async fn remove_thread_member(
+    thread_id: ChannelId,
+    user_id: Option<UserId>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let user_id = user_id.map_or(UserIdOrMe::Me, UserIdOrMe::UserId);
+    let response = request(
+        Method::DELETE,
+        Routes::thread_members(GenericId(thread_id.0), Some(user_id)),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Remove thread member", file_path))
+}
+
+pub async fn get_thread_members(
+    thread_id: ChannelId,
+    with_member: bool,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<ThreadMember>> {
+    let response = request(
+        Method::GET,
+        Routes::thread_members(GenericId(thread_id.0), None),
+        authorization(token),
+        queries([("with_member", Some(with_member.to_string()))]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get thread members", file_path).unwrap_or_default())
+}
+
+/// Archived threads in a channel
+pub async fn get_archived_threads(
+    channel_id: ChannelId,
+    archived_status: ArchivedStatus,
+    limit: Option<u8>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<ThreadList>> {
+    let response = request(
+        Method::GET,
+        Routes::channel_threads(channel_id, archived_status),
+        authorization(token),
+        queries([("limit", limit.map(|limit| limit.to_string()))]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get archived threads", file_path))
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use reqwest::Method;
+use serde_json::Value as JsonValue;
+use turbopack_binding::turbo::{tasks::Vc, tasks_fs::FileSystemPath};
+
+use super::{
+    http::RequestBody,
+    request::{authorization, expect_success, parse_response, request},
+};
+use crate::{
+    emoji::Emoji,
+    id::{EmojiId, GenericId, GuildId},
+    Routes,
+};
+
+pub async fn list_guild_emojis(
+    guild_id: GuildId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<Emoji>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_emojis(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "List guild emojis", file_path).unwrap_or_default())
+}
+
+pub async fn get_guild_emoji(
+    guild_id: GuildId,
+    emoji_id: EmojiId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Emoji>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_emoji(guild_id, GenericId(emoji_id.0)),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get guild emoji", file_path))
+}
+
+/// `emoji` is `{ name, image, roles }` where `image` is a data URI
+pub async fn create_guild_emoji(
+    guild_id: GuildId,
+    emoji: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Emoji>> {
+    let response = request(
+        Method::POST,
+        Routes::guild_emojis(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Json(emoji),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Create guild emoji", file_path))
+}
+
+pub async fn modify_guild_emoji(
+    guild_id: GuildId,
+    emoji_id: EmojiId,
+    emoji: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Emoji>> {
+    let response = request(
+        Method::PATCH,
+        Routes::guild_emoji(guild_id, GenericId(emoji_id.0)),
+        authorization(token),
+        None,
+        RequestBody::Json(emoji),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Modify guild emoji", file_path))
+}
+
+pub async fn delete_guild_emoji(
+    guild_id: GuildId,
+    emoji_id: EmojiId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::guild_emoji(guild_id, GenericId(emoji_id.0)),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Delete guild emoji", file_path))
+}
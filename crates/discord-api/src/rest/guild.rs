@@ -0,0 +1,414 @@
+//! Guilds, members, roles and bans
+use anyhow::Result;
+use reqwest::Method;
+use serde_json::{json, Value as JsonValue};
+use turbopack_binding::turbo::{tasks::Vc, tasks_fs::FileSystemPath};
+
+use super::{
+    http::RequestBody,
+    request::{authorization, expect_success, parse_response, queries, request},
+    routes::UserIdOrMe,
+};
+use crate::{
+    channel::{Channel, ThreadList},
+    guild::{member::GuildMember, role::Role, Ban, Guild},
+    id::{GuildId, RoleId, UserId},
+    Routes,
+};
+
+pub async fn get_guild(
+    guild_id: GuildId,
+    with_counts: bool,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Guild>> {
+    let response = request(
+        Method::GET,
+        Routes::guild(guild_id),
+        authorization(token),
+        queries([("with_counts", Some(with_counts.to_string()))]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get guild", file_path))
+}
+
+pub async fn modify_guild(
+    guild_id: GuildId,
+    guild: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Guild>> {
+    let response = request(
+        Method::PATCH,
+        Routes::guild(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Json(guild),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Modify guild", file_path))
+}
+
+pub async fn get_guild_channels(
+    guild_id: GuildId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<Channel>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_channels(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get guild channels", file_path).unwrap_or_default())
+}
+
+pub async fn create_guild_channel(
+    guild_id: GuildId,
+    channel: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Channel>> {
+    let response = request(
+        Method::POST,
+        Routes::guild_channels(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Json(channel),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Create guild channel", file_path))
+}
+
+/// Every active thread in a guild
+pub async fn list_active_guild_threads(
+    guild_id: GuildId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<ThreadList>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_active_threads(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "List active guild threads",
+        file_path,
+    ))
+}
+
+pub async fn get_guild_member(
+    guild_id: GuildId,
+    user_id: UserId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<GuildMember>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_member(guild_id, UserIdOrMe::UserId(user_id)),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get guild member", file_path))
+}
+
+pub async fn list_guild_members(
+    guild_id: GuildId,
+    after: Option<UserId>,
+    limit: Option<u16>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<GuildMember>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_members(guild_id),
+        authorization(token),
+        queries([
+            ("after", after.map(|id| id.to_string())),
+            ("limit", limit.map(|limit| limit.to_string())),
+        ]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "List guild members", file_path).unwrap_or_default())
+}
+
+pub async fn search_guild_members(
+    guild_id: GuildId,
+    query: &str,
+    limit: Option<u16>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<GuildMember>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_members_search(guild_id),
+        authorization(token),
+        queries([
+            ("query", Some(query.to_string())),
+            ("limit", limit.map(|limit| limit.to_string())),
+        ]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Search guild members", file_path).unwrap_or_default())
+}
+
+/// Modifies `user_id`, or the current member when it's `None` (which can only
+/// change `nick`)
+pub async fn modify_guild_member(
+    guild_id: GuildId,
+    user_id: Option<UserId>,
+    member: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<GuildMember>> {
+    let user_id = user_id.map_or(UserIdOrMe::Me, UserIdOrMe::UserId);
+    let response = request(
+        Method::PATCH,
+        Routes::guild_member(guild_id, user_id),
+        authorization(token),
+        None,
+        RequestBody::Json(member),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Modify guild member", file_path))
+}
+
+/// Kicks a member from the guild
+pub async fn remove_guild_member(
+    guild_id: GuildId,
+    user_id: UserId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::guild_member(guild_id, UserIdOrMe::UserId(user_id)),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Remove guild member", file_path))
+}
+
+pub async fn add_guild_member_role(
+    guild_id: GuildId,
+    user_id: UserId,
+    role_id: RoleId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::PUT,
+        Routes::guild_member_role(guild_id, user_id, role_id),
+        authorization(token),
+        None,
+        RequestBody::Json(&json!({})),
+    )
+    .await?;
+
+    Ok(expect_success(response, "Add guild member role", file_path))
+}
+
+pub async fn remove_guild_member_role(
+    guild_id: GuildId,
+    user_id: UserId,
+    role_id: RoleId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::guild_member_role(guild_id, user_id, role_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(
+        response,
+        "Remove guild member role",
+        file_path,
+    ))
+}
+
+pub async fn get_guild_bans(
+    guild_id: GuildId,
+    before: Option<UserId>,
+    after: Option<UserId>,
+    limit: Option<u16>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<Ban>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_bans(guild_id),
+        authorization(token),
+        queries([
+            ("before", before.map(|id| id.to_string())),
+            ("after", after.map(|id| id.to_string())),
+            ("limit", limit.map(|limit| limit.to_string())),
+        ]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get guild bans", file_path).unwrap_or_default())
+}
+
+pub async fn get_guild_ban(
+    guild_id: GuildId,
+    user_id: UserId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Ban>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_ban(guild_id, user_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get guild ban", file_path))
+}
+
+/// Bans a user, deleting their messages from the last
+/// `delete_message_seconds` (up to 7 days)
+pub async fn create_guild_ban(
+    guild_id: GuildId,
+    user_id: UserId,
+    delete_message_seconds: Option<u32>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let ban = match delete_message_seconds {
+        Some(delete_message_seconds) => {
+            json!({ "delete_message_seconds": delete_message_seconds })
+        }
+        None => json!({}),
+    };
+    let response = request(
+        Method::PUT,
+        Routes::guild_ban(guild_id, user_id),
+        authorization(token),
+        None,
+        RequestBody::Json(&ban),
+    )
+    .await?;
+
+    Ok(expect_success(response, "Create guild ban", file_path))
+}
+
+pub async fn remove_guild_ban(
+    guild_id: GuildId,
+    user_id: UserId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::guild_ban(guild_id, user_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Remove guild ban", file_path))
+}
+
+pub async fn get_guild_roles(
+    guild_id: GuildId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<Role>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_roles(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get guild roles", file_path).unwrap_or_default())
+}
+
+pub async fn create_guild_role(
+    guild_id: GuildId,
+    role: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Role>> {
+    let response = request(
+        Method::POST,
+        Routes::guild_roles(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Json(role),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Create guild role", file_path))
+}
+
+pub async fn modify_guild_role(
+    guild_id: GuildId,
+    role_id: RoleId,
+    role: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Role>> {
+    let response = request(
+        Method::PATCH,
+        Routes::guild_role(guild_id, role_id),
+        authorization(token),
+        None,
+        RequestBody::Json(role),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Modify guild role", file_path))
+}
+
+pub async fn delete_guild_role(
+    guild_id: GuildId,
+    role_id: RoleId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::guild_role(guild_id, role_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Delete guild role", file_path))
+}
@@ -1,21 +1,23 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display, time::Duration};
 
 use anyhow::{bail, Result};
-use reqwest::Method;
+use const_format::formatcp;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT},
+    multipart::{Form, Part},
+    Method, RequestBuilder,
+};
 use serde_json::Value as JsonValue;
 use turbopack_binding::{
     turbo::{
         tasks as turbo_tasks,
         tasks::{duration_span, RcStr, Vc},
-        tasks_fetch::{
-            FetchError, FetchErrorKind, FetchResult, HttpResponse, HttpResponseBody,
-            OptionProxyConfig, ProxyConfig,
-        },
+        tasks_fetch::{FetchError, FetchErrorKind, FetchResult, HttpResponse, HttpResponseBody},
     },
     turbopack::core::issue::StyledString,
 };
 
-use super::routes::Route;
+use super::{ratelimit::RATE_LIMITER, routes::Route};
 
 #[turbo_tasks::value(transparent)]
 pub struct OptionHashMap(Option<HashMap<RcStr, RcStr>>);
@@ -23,36 +25,100 @@ pub struct OptionHashMap(Option<HashMap<RcStr, RcStr>>);
 #[turbo_tasks::value(transparent)]
 pub struct OptionQueries(Option<Vec<(RcStr, RcStr)>>);
 
-#[turbo_tasks::value]
+/// A file sent as a `files[n]` part of a multipart/form-data request. Its
+/// description is set by the `attachments` entry of the payload with the same
+/// index.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+pub struct HttpFile {
+    pub filename: RcStr,
+    pub content_type: Option<RcStr>,
+    pub content: Vec<u8>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct HttpFiles(Vec<HttpFile>);
+
+/// Text fields of a multipart/form-data request that doesn't take
+/// `payload_json`
+#[turbo_tasks::value(transparent)]
+pub struct HttpFormFields(Vec<(RcStr, RcStr)>);
+
+/// https://discord.com/developers/docs/reference#user-agent
+pub const DEFAULT_USER_AGENT: &str = formatcp!(
+    "DiscordBot ({}, {})",
+    "https://github.com/0xjujutsu/fujinoki",
+    env!("CARGO_PKG_VERSION")
+);
+
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug, Default)]
+pub struct HttpOptions {
+    /// Overrides [DEFAULT_USER_AGENT]
+    pub user_agent: Option<RcStr>,
+    /// Bot token sent with every request. Requests that set their own
+    /// `Authorization` header, e.g. with a webhook or interaction token,
+    /// override it.
+    pub token: Option<RcStr>,
+    /// Timeout of a whole request in milliseconds
+    pub timeout: Option<u64>,
+    /// Timeout of the connect phase in milliseconds
+    pub connect_timeout: Option<u64>,
+    /// Proxy used for every request, e.g. `http://localhost:8080`
+    pub proxy: Option<RcStr>,
+}
+
+/// Owns the pooled client that every REST request is sent with, so
+/// connections (and their TLS sessions) are kept alive between requests
+#[turbo_tasks::value(serialization = "none", eq = "manual", cell = "new")]
 pub struct Http {
     base_url: RcStr,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    client: reqwest::Client,
 }
 
 #[turbo_tasks::value_impl]
 impl Http {
     #[turbo_tasks::function]
-    pub fn new(base_url: RcStr) -> Vc<Self> {
-        Http { base_url }.cell()
+    pub async fn new(base_url: RcStr, options: Vc<HttpOptions>) -> Result<Vc<Self>> {
+        let options = &*options.await?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_str(options.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))?,
+        );
+        if let Some(token) = &options.token {
+            let mut authorization = HeaderValue::from_str(&format!("Bot {token}"))?;
+            authorization.set_sensitive(true);
+            headers.insert(AUTHORIZATION, authorization);
+        }
+
+        let mut client_builder = reqwest::Client::builder()
+            .default_headers(headers)
+            .pool_idle_timeout(Duration::from_secs(90))
+            .tcp_keepalive(Duration::from_secs(60));
+        if let Some(timeout) = options.timeout {
+            client_builder = client_builder.timeout(Duration::from_millis(timeout));
+        }
+        if let Some(connect_timeout) = options.connect_timeout {
+            client_builder = client_builder.connect_timeout(Duration::from_millis(connect_timeout));
+        }
+        if let Some(proxy) = &options.proxy {
+            client_builder = client_builder.proxy(reqwest::Proxy::all(proxy.as_str())?);
+        }
+
+        Ok(Http {
+            base_url,
+            client: client_builder.build()?,
+        }
+        .cell())
     }
 
     #[turbo_tasks::function]
     async fn make_url(self: Vc<Self>, route: Vc<Route>) -> Result<Vc<RcStr>> {
-        let url = &*self.await?.base_url;
-        let slash = (url.ends_with('/'), route.await?.endpoint.starts_with('/'));
-
         Ok(Vc::cell(
-            match slash {
-                (true, true) => {
-                    format!("{url}{}", &route.await?.endpoint[1..])
-                }
-                (true, false) | (false, true) => {
-                    format!("{url}{}", route.await?.endpoint)
-                }
-                (false, false) => {
-                    format!("{url}/{}", route.await?.endpoint)
-                }
-            }
-            .into(),
+            join_url(&self.await?.base_url, &route.await?.endpoint).into(),
         ))
     }
 
@@ -77,15 +143,18 @@ impl Http {
         queries: Vc<OptionQueries>,
     ) -> Result<Vc<FetchResult>> {
         let url = self.validate_request(Method::GET.to_string().into(), route);
-
-        Ok(without_body(
-            Method::GET.to_string().into(),
-            url,
-            headers,
-            queries,
-            Vc::cell(None),
-            Vc::cell(None),
-        ))
+        let queries = &*queries.await?;
+
+        let result = send(
+            &*self.await?,
+            Method::GET,
+            &url.await?,
+            &*headers.await?,
+            |builder| Ok(with_queries(builder, queries)),
+        )
+        .await?;
+
+        Ok(into_fetch_result(result))
     }
 
     #[turbo_tasks::function(network)]
@@ -96,15 +165,18 @@ impl Http {
         headers: Vc<OptionHashMap>,
     ) -> Result<Vc<FetchResult>> {
         let url = self.validate_request(Method::POST.to_string().into(), route);
-
-        Ok(with_body(
-            Method::POST.to_string().into(),
-            url,
-            json,
-            headers,
-            Vc::cell(None),
-            Vc::cell(None),
-        ))
+        let json = &*json.await?;
+
+        let result = send(
+            &*self.await?,
+            Method::POST,
+            &url.await?,
+            &*headers.await?,
+            |builder| Ok(builder.json(json)),
+        )
+        .await?;
+
+        Ok(into_fetch_result(result))
     }
 
     #[turbo_tasks::function(network)]
@@ -113,36 +185,120 @@ impl Http {
         route: Vc<Route>,
         json: Vc<JsonValue>,
         headers: Vc<OptionHashMap>,
+    ) -> Result<Vc<FetchResult>> {
+        let url = self.validate_request(Method::PUT.to_string().into(), route);
+        let json = &*json.await?;
+
+        let result = send(
+            &*self.await?,
+            Method::PUT,
+            &url.await?,
+            &*headers.await?,
+            |builder| Ok(builder.json(json)),
+        )
+        .await?;
+
+        Ok(into_fetch_result(result))
+    }
+
+    #[turbo_tasks::function(network)]
+    pub async fn patch(
+        self: Vc<Self>,
+        route: Vc<Route>,
+        json: Vc<JsonValue>,
+        headers: Vc<OptionHashMap>,
     ) -> Result<Vc<FetchResult>> {
         let url = self.validate_request(Method::PATCH.to_string().into(), route);
+        let json = &*json.await?;
+
+        let result = send(
+            &*self.await?,
+            Method::PATCH,
+            &url.await?,
+            &*headers.await?,
+            |builder| Ok(builder.json(json)),
+        )
+        .await?;
+
+        Ok(into_fetch_result(result))
+    }
 
-        Ok(with_body(
-            Method::PUT.to_string().into(),
-            url,
-            json,
-            headers,
-            Vc::cell(None),
-            Vc::cell(None),
-        ))
+    /// Sends `json` as the `payload_json` part along with each file as a
+    /// `files[n]` part
+    #[turbo_tasks::function(network)]
+    pub async fn post_multipart(
+        self: Vc<Self>,
+        route: Vc<Route>,
+        json: Vc<JsonValue>,
+        files: Vc<HttpFiles>,
+        headers: Vc<OptionHashMap>,
+    ) -> Result<Vc<FetchResult>> {
+        let url = self.validate_request(Method::POST.to_string().into(), route);
+        let json = &*json.await?;
+        let files = &*files.await?;
+
+        let result = send(
+            &*self.await?,
+            Method::POST,
+            &url.await?,
+            &*headers.await?,
+            |builder| Ok(builder.multipart(multipart_form(json, files)?)),
+        )
+        .await?;
+
+        Ok(into_fetch_result(result))
     }
 
+    /// Sends `json` as the `payload_json` part along with each file as a
+    /// `files[n]` part
     #[turbo_tasks::function(network)]
-    pub async fn patch(
+    pub async fn patch_multipart(
         self: Vc<Self>,
         route: Vc<Route>,
         json: Vc<JsonValue>,
+        files: Vc<HttpFiles>,
         headers: Vc<OptionHashMap>,
     ) -> Result<Vc<FetchResult>> {
         let url = self.validate_request(Method::PATCH.to_string().into(), route);
+        let json = &*json.await?;
+        let files = &*files.await?;
+
+        let result = send(
+            &*self.await?,
+            Method::PATCH,
+            &url.await?,
+            &*headers.await?,
+            |builder| Ok(builder.multipart(multipart_form(json, files)?)),
+        )
+        .await?;
+
+        Ok(into_fetch_result(result))
+    }
 
-        Ok(with_body(
-            Method::PATCH.to_string().into(),
-            url,
-            json,
-            headers,
-            Vc::cell(None),
-            Vc::cell(None),
-        ))
+    /// Sends each of `fields` as a text part along with `file` as the `file`
+    /// part
+    #[turbo_tasks::function(network)]
+    pub async fn post_form(
+        self: Vc<Self>,
+        route: Vc<Route>,
+        fields: Vc<HttpFormFields>,
+        file: Vc<HttpFile>,
+        headers: Vc<OptionHashMap>,
+    ) -> Result<Vc<FetchResult>> {
+        let url = self.validate_request(Method::POST.to_string().into(), route);
+        let fields = &*fields.await?;
+        let file = &*file.await?;
+
+        let result = send(
+            &*self.await?,
+            Method::POST,
+            &url.await?,
+            &*headers.await?,
+            |builder| Ok(builder.multipart(fields_form(fields, file)?)),
+        )
+        .await?;
+
+        Ok(into_fetch_result(result))
     }
 
     #[turbo_tasks::function(network)]
@@ -153,155 +309,216 @@ impl Http {
         queries: Vc<OptionQueries>,
     ) -> Result<Vc<FetchResult>> {
         let url = self.validate_request(Method::DELETE.to_string().into(), route);
-
-        Ok(without_body(
-            Method::DELETE.to_string().into(),
-            url,
-            headers,
-            queries,
-            Vc::cell(None),
-            Vc::cell(None),
-        ))
+        let queries = &*queries.await?;
+
+        let result = send(
+            &*self.await?,
+            Method::DELETE,
+            &url.await?,
+            &*headers.await?,
+            |builder| Ok(with_queries(builder, queries)),
+        )
+        .await?;
+
+        Ok(into_fetch_result(result))
     }
 }
 
-// This is basically just the [turbo_tasks_fetch::fetch] function but with some
-// extra arguments
-#[turbo_tasks::function(network)]
-pub async fn without_body(
-    method: RcStr,
-    url: Vc<RcStr>,
-    headers: Vc<OptionHashMap>,
-    queries: Vc<OptionQueries>,
-    user_agent: Vc<Option<RcStr>>,
-    proxy_option: Vc<OptionProxyConfig>,
-) -> Result<Vc<FetchResult>> {
-    let url = &*url.await?;
-    let headers = &*headers.await?;
-    let queries = &*queries.await?;
-    let user_agent = &*user_agent.await?;
-    let proxy_option = &*proxy_option.await?;
-    let guard = Box::new(duration_span!(
-        "HTTP Request without body",
-        method = display(method.to_string()),
-        url = display(url.to_string())
-    ));
-
-    let client_builder = reqwest::Client::builder();
-    let client_builder = match proxy_option {
-        Some(ProxyConfig::Http(proxy)) => client_builder.proxy(reqwest::Proxy::http(proxy)?),
-        Some(ProxyConfig::Https(proxy)) => client_builder.proxy(reqwest::Proxy::https(proxy)?),
-        _ => client_builder,
-    };
+/// The body of a request sent with [Http::request]
+#[derive(Clone, Copy, Debug)]
+pub enum RequestBody<'a> {
+    Empty,
+    Json(&'a JsonValue),
+    /// Sends the JSON as the `payload_json` part along with each file as a
+    /// `files[n]` part
+    Multipart(&'a JsonValue, &'a [HttpFile]),
+    /// Sends each field as a text part along with the file as the `file` part
+    Form(&'a [(RcStr, RcStr)], &'a HttpFile),
+}
 
-    let client = client_builder.build()?;
+impl<'a> RequestBody<'a> {
+    /// A JSON body, or a multipart body when there are files to upload
+    pub fn with_files(json: &'a JsonValue, files: &'a [HttpFile]) -> Self {
+        match files.is_empty() {
+            true => RequestBody::Json(json),
+            false => RequestBody::Multipart(json, files),
+        }
+    }
 
-    let mut builder = match method.as_str() {
-        "GET" => client.get(url.as_str()),
-        "DELETE" => client.delete(url.as_str()),
-        _ => bail!("Invalid method"),
-    };
-    if let Some(user_agent) = user_agent {
-        builder = builder.header("User-Agent", user_agent.as_str());
+    fn apply(self, builder: RequestBuilder) -> reqwest::Result<RequestBuilder> {
+        Ok(match self {
+            RequestBody::Empty => builder,
+            RequestBody::Json(json) => builder.json(json),
+            RequestBody::Multipart(json, files) => builder.multipart(multipart_form(json, files)?),
+            RequestBody::Form(fields, file) => builder.multipart(fields_form(fields, file)?),
+        })
     }
+}
 
-    if let Some(headers) = headers {
-        for (key, value) in headers {
-            builder = builder.header(key.as_str(), value.as_str());
-        }
-    };
+/// A successful response to a request sent with [Http::request]
+#[derive(Debug)]
+pub struct RestResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
 
-    if let Some(queries) = queries {
-        for query in queries.iter() {
-            builder = builder.query(query);
-        }
-    }
+/// A request that failed to be sent or that responded with an error status
+#[derive(Debug)]
+pub struct RestError {
+    pub url: RcStr,
+    error: reqwest::Error,
+}
 
-    let response = builder.send().await.and_then(|r| r.error_for_status());
+impl RestError {
+    pub fn status(&self) -> Option<u16> {
+        self.error.status().map(|status| status.as_u16())
+    }
+}
 
-    drop(guard);
+impl Display for RestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
 
-    match response {
-        Ok(response) => {
-            let status = response.status().as_u16();
-            let body = response.bytes().await?.to_vec();
+pub type RestResult = Result<RestResponse, RestError>;
 
-            Ok(Vc::cell(Ok(HttpResponse {
-                status,
-                body: HttpResponseBody::cell(HttpResponseBody(body)),
-            }
-            .cell())))
+impl Http {
+    /// Sends a request every time it's called. The functions of [Http] cache
+    /// their response like any other turbo function, so anything with side
+    /// effects, or that reads something that can change, is sent with this.
+    pub async fn request(
+        &self,
+        method: Method,
+        route: Vc<Route>,
+        headers: &Option<HashMap<RcStr, RcStr>>,
+        queries: &Option<Vec<(RcStr, RcStr)>>,
+        body: RequestBody<'_>,
+    ) -> Result<RestResult> {
+        let route = route.await?;
+        if !route
+            .methods
+            .iter()
+            .any(|allowed| allowed == method.as_str())
+        {
+            bail!("{} cannot make a {method} request", route.endpoint);
         }
-        Err(err) => Ok(Vc::cell(Err(from_reqwest_error(&err, url).cell()))),
+        let url = join_url(&self.base_url, &route.endpoint);
+
+        send(self, method, &url, headers, |builder| {
+            body.apply(with_queries(builder, queries))
+        })
+        .await
     }
 }
 
-#[turbo_tasks::function(network)]
-pub async fn with_body(
-    method: RcStr,
-    url: Vc<RcStr>,
-    json: Vc<JsonValue>,
-    headers: Vc<OptionHashMap>,
-    user_agent: Vc<Option<RcStr>>,
-    proxy_option: Vc<OptionProxyConfig>,
-) -> Result<Vc<FetchResult>> {
-    let url = &*url.await?;
-    let headers = &*headers.await?;
-    let user_agent = &*user_agent.await?;
-    let proxy_option = &*proxy_option.await?;
+/// Sends a request with the pooled client of `http`, waiting on its rate limit
+/// bucket. `body` adds the body to the request, it's called once per attempt
+/// because multipart bodies can't be cloned.
+async fn send(
+    http: &Http,
+    method: Method,
+    url: &str,
+    headers: &Option<HashMap<RcStr, RcStr>>,
+    body: impl Fn(RequestBuilder) -> reqwest::Result<RequestBuilder>,
+) -> Result<RestResult> {
     let guard = Box::new(duration_span!(
-        "HTTP Request with body",
+        "HTTP Request",
         method = display(method.to_string()),
         url = display(url.to_string())
     ));
 
-    let client_builder = reqwest::Client::builder();
-    let client_builder = match proxy_option {
-        Some(ProxyConfig::Http(proxy)) => client_builder.proxy(reqwest::Proxy::http(proxy)?),
-        Some(ProxyConfig::Https(proxy)) => client_builder.proxy(reqwest::Proxy::https(proxy)?),
-        _ => client_builder,
-    };
-
-    let client = client_builder.build()?;
-
-    let mut builder = match method.as_str() {
-        "POST" => client.post(url.as_str()),
-        "PATCH" => client.patch(url.as_str()),
-        "PUT" => client.put(url.as_str()),
-        _ => bail!("Invalid method"),
-    };
-    if let Some(user_agent) = user_agent {
-        builder = builder.header("User-Agent", user_agent.as_str());
-    }
-
-    if let Some(headers) = headers {
-        for (key, value) in headers {
-            builder = builder.header(key.as_str(), value.as_str());
-        }
-    };
+    let response = RATE_LIMITER
+        .send(method.as_str(), url, || {
+            let mut builder = http.client.request(method.clone(), url);
+            if let Some(headers) = headers {
+                for (key, value) in headers {
+                    builder = builder.header(key.as_str(), value.as_str());
+                }
+            };
 
-    let response = builder
-        .json(&*json.await?)
-        .send()
+            body(builder)
+        })
         .await
         .and_then(|r| r.error_for_status());
 
     drop(guard);
 
-    match response {
+    Ok(match response {
         Ok(response) => {
             let status = response.status().as_u16();
             let body = response.bytes().await?.to_vec();
 
-            Ok(Vc::cell(Ok(HttpResponse {
-                status,
-                body: HttpResponseBody::cell(HttpResponseBody(body)),
-            }
-            .cell())))
+            Ok(RestResponse { status, body })
         }
-        Err(err) => Ok(Vc::cell(Err(
-            from_reqwest_error(&err.without_url(), url).cell()
-        ))),
+        Err(error) => Err(RestError {
+            url: url.into(),
+            error: error.without_url(),
+        }),
+    })
+}
+
+fn into_fetch_result(result: RestResult) -> Vc<FetchResult> {
+    Vc::cell(match result {
+        Ok(response) => Ok(HttpResponse {
+            status: response.status,
+            body: HttpResponseBody::cell(HttpResponseBody(response.body)),
+        }
+        .cell()),
+        Err(error) => Err(from_reqwest_error(&error.error, &error.url).cell()),
+    })
+}
+
+fn join_url(base_url: &str, endpoint: &str) -> String {
+    match (base_url.ends_with('/'), endpoint.starts_with('/')) {
+        (true, true) => format!("{base_url}{}", &endpoint[1..]),
+        (true, false) | (false, true) => format!("{base_url}{endpoint}"),
+        (false, false) => format!("{base_url}/{endpoint}"),
+    }
+}
+
+fn with_queries(
+    mut builder: RequestBuilder,
+    queries: &Option<Vec<(RcStr, RcStr)>>,
+) -> RequestBuilder {
+    if let Some(queries) = queries {
+        for query in queries.iter() {
+            builder = builder.query(query);
+        }
+    }
+
+    builder
+}
+
+/// `json` as the `payload_json` part along with each file as a `files[n]` part
+fn multipart_form(json: &JsonValue, files: &[HttpFile]) -> reqwest::Result<Form> {
+    let mut form = Form::new().part(
+        "payload_json",
+        Part::text(json.to_string()).mime_str("application/json")?,
+    );
+    for (index, file) in files.iter().enumerate() {
+        form = form.part(format!("files[{index}]"), file_part(file)?);
+    }
+
+    Ok(form)
+}
+
+/// Each of `fields` as a text part along with `file` as the `file` part
+fn fields_form(fields: &[(RcStr, RcStr)], file: &HttpFile) -> reqwest::Result<Form> {
+    let mut form = Form::new();
+    for (name, value) in fields.iter() {
+        form = form.text(name.to_string(), value.to_string());
+    }
+
+    Ok(form.part("file", file_part(file)?))
+}
+
+fn file_part(file: &HttpFile) -> reqwest::Result<Part> {
+    let part = Part::bytes(file.content.clone()).file_name(file.filename.to_string());
+
+    match &file.content_type {
+        Some(content_type) => part.mime_str(content_type),
+        None => Ok(part),
     }
 }
 
@@ -8,7 +8,11 @@ use turbopack_binding::{
     turbopack::core::issue::{IssueExt, IssueSeverity, StyledString},
 };
 
-use super::{http::fetch_error_to_string, routes::MessageIdOrOriginal, HTTP};
+use super::{
+    http::{fetch_error_to_string, HttpFiles},
+    routes::MessageIdOrOriginal,
+    HTTP,
+};
 use crate::{
     channel::{
         embed::{Embed, EmbedField},
@@ -29,6 +33,7 @@ pub async fn create_interaction_response(
     interaction_id: Vc<InteractionId>,
     interaction_token: Vc<RcStr>,
     interaction_response: Vc<InteractionResponse>,
+    files: Vc<HttpFiles>,
     file_path: Option<Vc<FileSystemPath>>,
 ) -> Result<Vc<()>> {
     let issue_title = "Create interaction response".to_string().into();
@@ -49,16 +54,24 @@ pub async fn create_interaction_response(
         return Ok(Vc::cell(()));
     }
 
-    let response = *HTTP
-        .post(
-            Routes::interaction_callback(
-                interaction_id.await?.clone_value().into(),
-                interaction_token.await?.clone_value().to_string().into(),
-            ),
-            Vc::cell(request_body.unwrap()),
-            Vc::cell(None),
-        )
-        .await?;
+    let route = Routes::interaction_callback(
+        interaction_id.await?.clone_value().into(),
+        interaction_token.await?.clone_value().to_string().into(),
+    );
+    let response = if files.await?.is_empty() {
+        *HTTP
+            .post(route, Vc::cell(request_body.unwrap()), Vc::cell(None))
+            .await?
+    } else {
+        *HTTP
+            .post_multipart(
+                route,
+                Vc::cell(request_body.unwrap()),
+                files,
+                Vc::cell(None),
+            )
+            .await?
+    };
 
     match response {
         Ok(response) => {
@@ -1,18 +1,44 @@
 // TODO(kijv) make this into discord-rest
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
 use const_format::formatcp;
 use lazy_static::lazy_static;
 use turbopack_binding::turbo::tasks::Vc;
 
+use self::http::HttpOptions;
 use crate::VERSION;
 
 pub mod application;
+pub mod auto_moderation;
+pub mod channel;
+pub mod emoji;
 pub mod gateway;
+pub mod guild;
 pub mod http;
 pub mod interactions;
+pub mod ratelimit;
+mod request;
 pub mod routes;
+pub mod scheduled_event;
+pub mod sticker;
+pub mod webhook;
+
+static HTTP_OPTIONS: OnceLock<HttpOptions> = OnceLock::new();
 
 lazy_static! {
-    pub static ref HTTP: Vc<http::Http> = http::Http::new(get_api_url().into());
+    pub static ref HTTP: Vc<http::Http> = http::Http::new(
+        get_api_url().into(),
+        HTTP_OPTIONS.get().cloned().unwrap_or_default().cell()
+    );
+}
+
+/// Configures the client shared by every REST request, this has to be called
+/// before the first request is made.
+pub fn configure(options: HttpOptions) -> Result<()> {
+    HTTP_OPTIONS
+        .set(options)
+        .map_err(|_| anyhow!("the REST client has already been configured"))
 }
 
 pub const fn get_api_url() -> &'static str {
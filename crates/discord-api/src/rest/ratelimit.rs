@@ -0,0 +1,383 @@
+//! Per-route rate limit tracking for the Discord REST API.
+//!
+//! Requests that share a bucket are queued and sent one at a time. Once a
+//! bucket is exhausted, the next request sleeps until `X-RateLimit-Reset-After`
+//! has elapsed. A 429 response is retried after its `retry_after`, and a
+//! global 429 pauses every bucket.
+//!
+//! See <https://discord.com/developers/docs/topics/rate-limits>
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use lazy_static::lazy_static;
+use reqwest::{header::HeaderMap, RequestBuilder, Response, StatusCode};
+use serde::Deserialize;
+use tokio::time::{sleep_until, Instant};
+use tracing::{info_span, Instrument};
+
+/// How many times a request is retried after receiving a 429
+const MAX_RETRIES: u32 = 5;
+
+/// Expired buckets are only pruned once there are at least this many
+const MIN_PRUNE_SIZE: usize = 256;
+
+lazy_static! {
+    pub static ref RATE_LIMITER: RateLimiter = RateLimiter::default();
+}
+
+#[derive(Debug, Default)]
+struct Bucket {
+    remaining: Option<u64>,
+    reset_at: Option<Instant>,
+}
+
+impl Bucket {
+    /// When the next request can be sent, if the bucket is exhausted
+    fn wait_until(&self) -> Option<Instant> {
+        match (self.remaining, self.reset_at) {
+            (Some(0), Some(reset_at)) if reset_at > Instant::now() => Some(reset_at),
+            _ => None,
+        }
+    }
+}
+
+/// Every webhook and interaction token is a major parameter of its own, so
+/// buckets that have expired are pruned as new ones are added
+#[derive(Debug, Default)]
+struct Buckets {
+    buckets: HashMap<String, Arc<tokio::sync::Mutex<Bucket>>>,
+    /// The size at which expired buckets are pruned next
+    prune_at: usize,
+}
+
+impl Buckets {
+    fn get_or_insert(&mut self, key: String) -> Arc<tokio::sync::Mutex<Bucket>> {
+        if let Some(bucket) = self.buckets.get(&key) {
+            return bucket.clone();
+        }
+
+        if self.buckets.len() >= self.prune_at.max(MIN_PRUNE_SIZE) {
+            self.prune();
+            self.prune_at = self.buckets.len() * 2;
+        }
+
+        self.buckets.entry(key).or_default().clone()
+    }
+
+    /// Removes the buckets that no request is waiting on and whose reset has
+    /// passed, these start over with a full bucket anyway
+    fn prune(&mut self) {
+        let now = Instant::now();
+
+        self.buckets.retain(|_, bucket| {
+            // Requests hold a reference while they wait on or use the bucket
+            if Arc::strong_count(bucket) > 1 {
+                return true;
+            }
+
+            match bucket.try_lock() {
+                Ok(bucket) => bucket.reset_at.is_some_and(|reset_at| reset_at > now),
+                Err(_) => true,
+            }
+        });
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RateLimitedBody {
+    retry_after: f64,
+    #[serde(default)]
+    global: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    /// Route key -> bucket hash from `X-RateLimit-Bucket`
+    routes: Mutex<HashMap<String, String>>,
+    /// `{bucket}:{major parameter}` -> bucket state
+    buckets: Mutex<Buckets>,
+    global_reset_at: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// Sends the request created by `request`, waiting on the route's bucket
+    /// before sending and retrying when rate limited.
+    ///
+    /// `request` is called once per attempt because multipart bodies can't be
+    /// cloned.
+    pub async fn send(
+        &self,
+        method: &str,
+        url: &str,
+        request: impl Fn() -> reqwest::Result<RequestBuilder>,
+    ) -> reqwest::Result<Response> {
+        let route = RouteKey::new(method, url);
+        let mut retries = 0;
+
+        loop {
+            let bucket = self.bucket(&route);
+            // Holding the bucket for the whole request keeps requests queued in order
+            let mut bucket = bucket.lock().await;
+
+            self.wait_for_global(&route).await;
+            if let Some(wait_until) = bucket.wait_until() {
+                throttle(&route, wait_until, false).await;
+            }
+
+            let response = request()?.send().await?;
+            self.update(&route, &mut bucket, response.headers());
+            drop(bucket);
+
+            if response.status() != StatusCode::TOO_MANY_REQUESTS || retries >= MAX_RETRIES {
+                return Ok(response);
+            }
+            retries += 1;
+
+            let headers = response.headers().clone();
+            let body = response.bytes().await?;
+            let (retry_after, global) = match serde_json::from_slice::<RateLimitedBody>(&body) {
+                Ok(body) => (body.retry_after, body.global),
+                Err(_) => (
+                    header_f64(&headers, "retry-after").unwrap_or(1.0),
+                    is_global(&headers),
+                ),
+            };
+            let wait_until = seconds_from_now(retry_after)
+                .unwrap_or_else(|| Instant::now() + Duration::from_secs(1));
+
+            if global {
+                *self.global_reset_at.lock().unwrap() = Some(wait_until);
+            } else {
+                throttle(&route, wait_until, false).await;
+            }
+        }
+    }
+
+    fn bucket(&self, route: &RouteKey) -> Arc<tokio::sync::Mutex<Bucket>> {
+        let key = self.bucket_key(route);
+
+        self.buckets.lock().unwrap().get_or_insert(key)
+    }
+
+    fn bucket_key(&self, route: &RouteKey) -> String {
+        // Until Discord tells us which bucket a route belongs to, the route is
+        // its own bucket
+        let routes = self.routes.lock().unwrap();
+        let bucket = routes.get(&route.route).unwrap_or(&route.route);
+
+        format!("{bucket}:{}", route.major)
+    }
+
+    async fn wait_for_global(&self, route: &RouteKey) {
+        let global_reset_at = *self.global_reset_at.lock().unwrap();
+
+        if let Some(reset_at) = global_reset_at.filter(|reset_at| *reset_at > Instant::now()) {
+            throttle(route, reset_at, true).await;
+        }
+    }
+
+    fn update(&self, route: &RouteKey, bucket: &mut Bucket, headers: &HeaderMap) {
+        if let Some(remaining) = header_str(headers, "x-ratelimit-remaining") {
+            bucket.remaining = remaining.parse().ok();
+        }
+        if let Some(reset_after) = header_f64(headers, "x-ratelimit-reset-after") {
+            bucket.reset_at = seconds_from_now(reset_after);
+        }
+
+        if let Some(hash) = header_str(headers, "x-ratelimit-bucket") {
+            let previous = self
+                .routes
+                .lock()
+                .unwrap()
+                .insert(route.route.clone(), hash.to_string());
+
+            // The route now shares its state with every route in the same bucket
+            if previous.as_deref() != Some(hash) {
+                let key = format!("{hash}:{}", route.major);
+                let shared = self.buckets.lock().unwrap().get_or_insert(key);
+
+                if let Ok(mut shared) = shared.try_lock() {
+                    shared.remaining = bucket.remaining;
+                    shared.reset_at = bucket.reset_at;
+                }
+            }
+        }
+
+        if is_global(headers) {
+            if let Some(reset_at) = header_f64(headers, "retry-after").and_then(seconds_from_now) {
+                *self.global_reset_at.lock().unwrap() = Some(reset_at);
+            }
+        }
+    }
+}
+
+async fn throttle(route: &RouteKey, wait_until: Instant, global: bool) {
+    let wait = wait_until.saturating_duration_since(Instant::now());
+
+    sleep_until(wait_until)
+        .instrument(info_span!(
+            "rate limited",
+            route = %route.route,
+            major = %route.major,
+            global,
+            wait_ms = wait.as_millis() as u64
+        ))
+        .await;
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn header_f64(headers: &HeaderMap, name: &str) -> Option<f64> {
+    header_str(headers, name).and_then(|value| value.parse().ok())
+}
+
+/// Negative values are treated as 0, values that are out of range as invalid
+fn seconds_from_now(seconds: f64) -> Option<Instant> {
+    let duration = Duration::try_from_secs_f64(seconds.max(0.0)).ok()?;
+    Instant::now().checked_add(duration)
+}
+
+fn is_global(headers: &HeaderMap) -> bool {
+    header_str(headers, "x-ratelimit-global") == Some("true")
+        || header_str(headers, "x-ratelimit-scope") == Some("global")
+}
+
+/// Identifies the rate limit of a request. Discord buckets routes by their
+/// major parameter (channel, guild, webhook or interaction) while every other
+/// id in the route shares the same limit.
+#[derive(Debug, PartialEq, Eq)]
+struct RouteKey {
+    route: String,
+    major: String,
+}
+
+impl RouteKey {
+    fn new(method: &str, url: &str) -> Self {
+        let path = url::Url::parse(url)
+            .map(|url| url.path().to_string())
+            .unwrap_or_else(|_| url.to_string());
+
+        let mut route = vec![];
+        let mut major = vec![];
+        let mut previous: Option<&str> = None;
+        let mut take_token = false;
+
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            if take_token {
+                // Webhook and interaction tokens are part of the major parameter
+                major.push(segment);
+                route.push(":token");
+                take_token = false;
+            } else if previous.is_some_and(is_major_resource) && is_snowflake(segment) {
+                major.push(segment);
+                route.push(segment);
+                take_token = matches!(previous, Some("webhooks" | "interactions"));
+            } else if is_snowflake(segment) {
+                route.push(":id");
+            } else {
+                route.push(segment);
+            }
+
+            previous = Some(segment);
+        }
+
+        RouteKey {
+            route: format!("{method} /{}", route.join("/")),
+            major: major.join("/"),
+        }
+    }
+}
+
+fn is_major_resource(segment: &str) -> bool {
+    matches!(segment, "channels" | "guilds" | "webhooks" | "interactions")
+}
+
+fn is_snowflake(segment: &str) -> bool {
+    segment.len() >= 15 && segment.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use super::{seconds_from_now, Buckets, RouteKey, MIN_PRUNE_SIZE};
+
+    #[test]
+    fn test_prune_expired_buckets() {
+        let mut buckets = Buckets::default();
+
+        let waiting = buckets.get_or_insert("waiting".to_string());
+        buckets
+            .get_or_insert("exhausted".to_string())
+            .try_lock()
+            .unwrap()
+            .reset_at = Some(Instant::now() + Duration::from_secs(60));
+        buckets
+            .get_or_insert("expired".to_string())
+            .try_lock()
+            .unwrap()
+            .reset_at = Some(Instant::now() - Duration::from_secs(1));
+        for token in 0..MIN_PRUNE_SIZE {
+            buckets.get_or_insert(format!("interaction:{token}"));
+        }
+
+        assert!(buckets.buckets.len() <= MIN_PRUNE_SIZE);
+        assert!(buckets.buckets.contains_key("waiting"));
+        assert!(buckets.buckets.contains_key("exhausted"));
+        assert!(!buckets.buckets.contains_key("expired"));
+        // The bucket that is in use is still shared with the next request
+        assert!(std::sync::Arc::ptr_eq(
+            &waiting,
+            &buckets.get_or_insert("waiting".to_string())
+        ));
+    }
+
+    #[test]
+    fn test_seconds_from_now_out_of_range() {
+        assert!(seconds_from_now(-5.0).is_some());
+        assert!(seconds_from_now(1.5).is_some());
+        assert!(seconds_from_now(1e300).is_none());
+        assert!(seconds_from_now(f64::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_route_key_major_parameters() {
+        let route = RouteKey::new(
+            "DELETE",
+            "https://discord.com/api/v10/channels/175928847299117063/messages/175928847299117064",
+        );
+        assert_eq!(
+            route.route,
+            "DELETE /api/v10/channels/175928847299117063/messages/:id"
+        );
+        assert_eq!(route.major, "175928847299117063");
+
+        let route = RouteKey::new(
+            "PATCH",
+            "https://discord.com/api/v10/webhooks/175928847299117063/aW50ZXJhY3Rpb24/messages/@original",
+        );
+        assert_eq!(
+            route.route,
+            "PATCH /api/v10/webhooks/175928847299117063/:token/messages/@original"
+        );
+        assert_eq!(route.major, "175928847299117063/aW50ZXJhY3Rpb24");
+    }
+
+    #[test]
+    fn test_route_key_without_major_parameter() {
+        let route = RouteKey::new(
+            "PUT",
+            "https://discord.com/api/v10/applications/175928847299117063/commands",
+        );
+        assert_eq!(route.route, "PUT /api/v10/applications/:id/commands");
+        assert_eq!(route.major, "");
+    }
+}
@@ -0,0 +1,121 @@
+//! Helpers shared by the typed REST functions so each of them only has to
+//! describe the request it makes.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use reqwest::{header, Method};
+use serde::de::DeserializeOwned;
+use turbopack_binding::{
+    turbo::{
+        tasks::{RcStr, Vc},
+        tasks_fs::FileSystemPath,
+    },
+    turbopack::core::issue::{IssueExt, IssueSeverity, StyledString},
+};
+
+use super::{
+    http::{RequestBody, RestResult},
+    routes::Route,
+    HTTP,
+};
+use crate::issue::DiscordApiIssue;
+
+pub(crate) fn authorization(token: &str) -> Option<HashMap<RcStr, RcStr>> {
+    Some(HashMap::from_iter(vec![(
+        header::AUTHORIZATION.to_string().into(),
+        format!("Bot {token}").into(),
+    )]))
+}
+
+/// For endpoints that act on behalf of a user, with an OAuth2 access token
+pub(crate) fn bearer_authorization(access_token: &str) -> Option<HashMap<RcStr, RcStr>> {
+    Some(HashMap::from_iter(vec![(
+        header::AUTHORIZATION.to_string().into(),
+        format!("Bearer {access_token}").into(),
+    )]))
+}
+
+/// Turns `(key, value)` pairs into query parameters, skipping unset values
+pub(crate) fn queries<const N: usize>(
+    queries: [(&str, Option<String>); N],
+) -> Option<Vec<(RcStr, RcStr)>> {
+    let queries = queries
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key.into(), value.into())))
+        .collect::<Vec<_>>();
+
+    (!queries.is_empty()).then_some(queries)
+}
+
+/// Sends a request with the shared client. Unlike the functions of
+/// [super::http::Http] it isn't cached, so it's sent every time it's called.
+pub(crate) async fn request(
+    method: Method,
+    route: Vc<Route>,
+    headers: Option<HashMap<RcStr, RcStr>>,
+    queries: Option<Vec<(RcStr, RcStr)>>,
+    body: RequestBody<'_>,
+) -> Result<RestResult> {
+    (*HTTP)
+        .await?
+        .request(method, route, &headers, &queries, body)
+        .await
+}
+
+/// Deserializes the body of a successful response into `T`, emitting a
+/// [DiscordApiIssue] when the request failed or the body can't be
+/// deserialized.
+pub(crate) fn parse_response<T: DeserializeOwned>(
+    result: RestResult,
+    issue_title: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Option<T> {
+    match result {
+        Ok(response) => match serde_json::from_slice::<T>(&response.body) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                emit_issue(
+                    issue_title,
+                    format!("Failed to transform response into JSON: {err}"),
+                    file_path,
+                );
+
+                None
+            }
+        },
+        Err(err) => {
+            emit_issue(issue_title, format!("Received error: {err}"), file_path);
+
+            None
+        }
+    }
+}
+
+/// For requests that respond with `204 No Content`, returns whether the
+/// request succeeded and emits a [DiscordApiIssue] when it didn't.
+pub(crate) fn expect_success(
+    result: RestResult,
+    issue_title: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> bool {
+    match result {
+        Ok(_) => true,
+        Err(err) => {
+            emit_issue(issue_title, format!("Received error: {err}"), file_path);
+
+            false
+        }
+    }
+}
+
+fn emit_issue(issue_title: &str, message: String, file_path: Option<Vc<FileSystemPath>>) {
+    DiscordApiIssue {
+        severity: IssueSeverity::Error.cell(),
+        file_path,
+        title: Some(RcStr::from(issue_title)),
+        message: StyledString::Text(message.into()).cell(),
+    }
+    .cell()
+    .emit();
+}
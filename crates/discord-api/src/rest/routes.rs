@@ -250,6 +250,19 @@ impl Routes {
             .cell()
     }
 
+    /// Route for:
+    /// - GET    `/channels/{channel.id}/messages/{message.id}`
+    /// - PATCH  `/channels/{channel.id}/messages/{message.id}`
+    /// - DELETE `/channels/{channel.id}/messages/{message.id}`
+    #[turbo_tasks::function]
+    pub fn channel_message(channel_id: ChannelId, message_id: MessageId) -> Vc<Route> {
+        Route::new(format!("/channels/{channel_id}/messages/{message_id}"))
+            .get()
+            .patch()
+            .delete()
+            .cell()
+    }
+
     /// Route for:
     /// - POST `/channels/{channel.id}/messages/{message.id}/crosspost`
     #[turbo_tasks::function]
@@ -453,7 +466,7 @@ impl Routes {
     /// - DELETE `/guilds/{guild.id}`
     #[turbo_tasks::function]
     pub fn guild(guild_id: GuildId) -> Vc<Route> {
-        Route::new(format!("/guilds/${guild_id}"))
+        Route::new(format!("/guilds/{guild_id}"))
             .get()
             .patch()
             .delete()
@@ -788,7 +801,7 @@ impl Routes {
         Route::new(format!(
             "/channels/{thread_id}/thread-members{}",
             match user_id {
-                Some(user_id_or_me) => user_id_or_me.to_string(),
+                Some(user_id_or_me) => format!("/{user_id_or_me}"),
                 None => "".to_string(),
             }
         ))
@@ -1155,7 +1168,7 @@ impl Routes {
     pub fn application_command_permissions(
         application_id: ApplicationId,
         guild_id: GuildId,
-        command_id: GenericId,
+        command_id: CommandId,
     ) -> Vc<Route> {
         Route::new(format!(
             "/applications/{application_id}/guilds/{guild_id}/commands/{command_id}/permissions"
@@ -1188,7 +1201,11 @@ impl Routes {
     /// - DELETE `/stage-instances/{channel.id}`
     #[turbo_tasks::function]
     pub fn stage_instance(channel_id: ChannelId) -> Vc<Route> {
-        Route::new(format!("/stage-instances/{channel_id}")).cell()
+        Route::new(format!("/stage-instances/{channel_id}"))
+            .get()
+            .patch()
+            .delete()
+            .cell()
     }
 
     /// Route for:
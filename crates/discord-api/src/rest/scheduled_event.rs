@@ -0,0 +1,145 @@
+use anyhow::Result;
+use reqwest::Method;
+use serde_json::Value as JsonValue;
+use turbopack_binding::turbo::{tasks::Vc, tasks_fs::FileSystemPath};
+
+use super::{
+    http::RequestBody,
+    request::{authorization, expect_success, parse_response, queries, request},
+};
+use crate::{
+    id::{GenericId, GuildId, ScheduledEventId},
+    scheduled_event::{GuildScheduledEvent, GuildScheduledEventUser},
+    Routes,
+};
+
+pub async fn list_guild_scheduled_events(
+    guild_id: GuildId,
+    with_user_count: bool,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<GuildScheduledEvent>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_scheduled_events(guild_id),
+        authorization(token),
+        queries([("with_user_count", Some(with_user_count.to_string()))]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "List guild scheduled events", file_path).unwrap_or_default())
+}
+
+pub async fn get_guild_scheduled_event(
+    guild_id: GuildId,
+    event_id: ScheduledEventId,
+    with_user_count: bool,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<GuildScheduledEvent>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_scheduled_event(guild_id, GenericId(event_id.0)),
+        authorization(token),
+        queries([("with_user_count", Some(with_user_count.to_string()))]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "Get guild scheduled event",
+        file_path,
+    ))
+}
+
+pub async fn create_guild_scheduled_event(
+    guild_id: GuildId,
+    event: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<GuildScheduledEvent>> {
+    let response = request(
+        Method::POST,
+        Routes::guild_scheduled_events(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Json(event),
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "Create guild scheduled event",
+        file_path,
+    ))
+}
+
+pub async fn modify_guild_scheduled_event(
+    guild_id: GuildId,
+    event_id: ScheduledEventId,
+    event: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<GuildScheduledEvent>> {
+    let response = request(
+        Method::PATCH,
+        Routes::guild_scheduled_event(guild_id, GenericId(event_id.0)),
+        authorization(token),
+        None,
+        RequestBody::Json(event),
+    )
+    .await?;
+
+    Ok(parse_response(
+        response,
+        "Modify guild scheduled event",
+        file_path,
+    ))
+}
+
+pub async fn delete_guild_scheduled_event(
+    guild_id: GuildId,
+    event_id: ScheduledEventId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::guild_scheduled_event(guild_id, GenericId(event_id.0)),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(
+        response,
+        "Delete guild scheduled event",
+        file_path,
+    ))
+}
+
+pub async fn get_guild_scheduled_event_users(
+    guild_id: GuildId,
+    event_id: ScheduledEventId,
+    limit: Option<u8>,
+    with_member: bool,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<GuildScheduledEventUser>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_scheduled_event_users(guild_id, GenericId(event_id.0)),
+        authorization(token),
+        queries([
+            ("limit", limit.map(|limit| limit.to_string())),
+            ("with_member", Some(with_member.to_string())),
+        ]),
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get guild scheduled event users", file_path).unwrap_or_default())
+}
@@ -0,0 +1,135 @@
+use anyhow::Result;
+use reqwest::Method;
+use serde_json::Value as JsonValue;
+use turbopack_binding::turbo::{
+    tasks::{RcStr, Vc},
+    tasks_fs::FileSystemPath,
+};
+
+use super::{
+    http::{HttpFile, RequestBody},
+    request::{authorization, expect_success, parse_response, request},
+};
+use crate::{
+    id::{GuildId, StickerId},
+    sticker::Sticker,
+    Routes,
+};
+
+pub async fn get_sticker(
+    sticker_id: StickerId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Sticker>> {
+    let response = request(
+        Method::GET,
+        Routes::sticker(sticker_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get sticker", file_path))
+}
+
+pub async fn list_guild_stickers(
+    guild_id: GuildId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<Sticker>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_stickers(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "List guild stickers", file_path).unwrap_or_default())
+}
+
+pub async fn get_guild_sticker(
+    guild_id: GuildId,
+    sticker_id: StickerId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Sticker>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_sticker(guild_id, sticker_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get guild sticker", file_path))
+}
+
+/// `file` is a PNG, APNG, GIF or Lottie JSON file of at most 512 KiB, the
+/// fields are sent as form fields rather than `payload_json`
+#[allow(clippy::too_many_arguments)]
+pub async fn create_guild_sticker(
+    guild_id: GuildId,
+    name: RcStr,
+    description: RcStr,
+    tags: RcStr,
+    file: &HttpFile,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Sticker>> {
+    let fields = [
+        ("name".into(), name),
+        ("description".into(), description),
+        ("tags".into(), tags),
+    ];
+    let response = request(
+        Method::POST,
+        Routes::guild_stickers(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Form(&fields, file),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Create guild sticker", file_path))
+}
+
+pub async fn modify_guild_sticker(
+    guild_id: GuildId,
+    sticker_id: StickerId,
+    sticker: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Sticker>> {
+    let response = request(
+        Method::PATCH,
+        Routes::guild_sticker(guild_id, sticker_id),
+        authorization(token),
+        None,
+        RequestBody::Json(sticker),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Modify guild sticker", file_path))
+}
+
+pub async fn delete_guild_sticker(
+    guild_id: GuildId,
+    sticker_id: StickerId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::guild_sticker(guild_id, sticker_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Delete guild sticker", file_path))
+}
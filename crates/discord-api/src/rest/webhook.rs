@@ -0,0 +1,232 @@
+use anyhow::Result;
+use reqwest::Method;
+use serde_json::Value as JsonValue;
+use turbopack_binding::turbo::{tasks::Vc, tasks_fs::FileSystemPath};
+
+use super::{
+    http::{HttpFile, RequestBody},
+    request::{authorization, expect_success, parse_response, request},
+    routes::{MessageIdOrOriginal, WebhookOrApplicationId},
+};
+use crate::{
+    channel::message::Message,
+    id::{ChannelId, GuildId, MessageId, WebhookId},
+    webhook::Webhook,
+    Routes,
+};
+
+pub async fn create_webhook(
+    channel_id: ChannelId,
+    webhook: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Webhook>> {
+    let response = request(
+        Method::POST,
+        Routes::channel_webhooks(channel_id),
+        authorization(token),
+        None,
+        RequestBody::Json(webhook),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Create webhook", file_path))
+}
+
+pub async fn get_channel_webhooks(
+    channel_id: ChannelId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<Webhook>> {
+    let response = request(
+        Method::GET,
+        Routes::channel_webhooks(channel_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get channel webhooks", file_path).unwrap_or_default())
+}
+
+pub async fn get_guild_webhooks(
+    guild_id: GuildId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Vec<Webhook>> {
+    let response = request(
+        Method::GET,
+        Routes::guild_webhooks(guild_id),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get guild webhooks", file_path).unwrap_or_default())
+}
+
+/// Gets a webhook with the bot's token, or with `webhook_token` when given
+pub async fn get_webhook(
+    webhook_id: WebhookId,
+    webhook_token: Option<&str>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Webhook>> {
+    let response = request(
+        Method::GET,
+        Routes::webhook(
+            WebhookOrApplicationId::WebhookId(webhook_id),
+            webhook_token.map(Into::into),
+        ),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get webhook", file_path))
+}
+
+pub async fn modify_webhook(
+    webhook_id: WebhookId,
+    webhook_token: Option<&str>,
+    webhook: &JsonValue,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Webhook>> {
+    let response = request(
+        Method::PATCH,
+        Routes::webhook(
+            WebhookOrApplicationId::WebhookId(webhook_id),
+            webhook_token.map(Into::into),
+        ),
+        authorization(token),
+        None,
+        RequestBody::Json(webhook),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Modify webhook", file_path))
+}
+
+pub async fn delete_webhook(
+    webhook_id: WebhookId,
+    webhook_token: Option<&str>,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::webhook(
+            WebhookOrApplicationId::WebhookId(webhook_id),
+            webhook_token.map(Into::into),
+        ),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(response, "Delete webhook", file_path))
+}
+
+pub async fn execute_webhook(
+    webhook_id: WebhookId,
+    webhook_token: &str,
+    message: &JsonValue,
+    files: &[HttpFile],
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::POST,
+        Routes::webhook(
+            WebhookOrApplicationId::WebhookId(webhook_id),
+            Some(webhook_token.into()),
+        ),
+        authorization(token),
+        None,
+        RequestBody::with_files(message, files),
+    )
+    .await?;
+
+    Ok(expect_success(response, "Execute webhook", file_path))
+}
+
+pub async fn get_webhook_message(
+    webhook_id: WebhookId,
+    webhook_token: &str,
+    message_id: MessageId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Message>> {
+    let response = request(
+        Method::GET,
+        Routes::webhook_message(
+            WebhookOrApplicationId::WebhookId(webhook_id),
+            webhook_token.into(),
+            MessageIdOrOriginal::MessageId(message_id),
+        ),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(parse_response(response, "Get webhook message", file_path))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn edit_webhook_message(
+    webhook_id: WebhookId,
+    webhook_token: &str,
+    message_id: MessageId,
+    message: &JsonValue,
+    files: &[HttpFile],
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<Option<Message>> {
+    let response = request(
+        Method::PATCH,
+        Routes::webhook_message(
+            WebhookOrApplicationId::WebhookId(webhook_id),
+            webhook_token.into(),
+            MessageIdOrOriginal::MessageId(message_id),
+        ),
+        authorization(token),
+        None,
+        RequestBody::with_files(message, files),
+    )
+    .await?;
+
+    Ok(parse_response(response, "Edit webhook message", file_path))
+}
+
+pub async fn delete_webhook_message(
+    webhook_id: WebhookId,
+    webhook_token: &str,
+    message_id: MessageId,
+    token: &str,
+    file_path: Option<Vc<FileSystemPath>>,
+) -> Result<bool> {
+    let response = request(
+        Method::DELETE,
+        Routes::webhook_message(
+            WebhookOrApplicationId::WebhookId(webhook_id),
+            webhook_token.into(),
+            MessageIdOrOriginal::MessageId(message_id),
+        ),
+        authorization(token),
+        None,
+        RequestBody::Empty,
+    )
+    .await?;
+
+    Ok(expect_success(
+        response,
+        "Delete webhook message",
+        file_path,
+    ))
+}
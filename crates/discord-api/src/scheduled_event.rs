@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr};
+
+use crate::{
+    id::{ChannelId, GuildId, ScheduledEventId, UserId},
+    impl_serde_for_integer_enum,
+    user::User,
+};
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GuildScheduledEvent {
+    /// the id of the scheduled event
+    pub id: ScheduledEventId,
+    /// the guild id which the scheduled event belongs to
+    pub guild_id: GuildId,
+    /// the channel id in which the scheduled event will be hosted, or null if
+    /// scheduled entity type is EXTERNAL
+    pub channel_id: Option<ChannelId>,
+    /// the id of the user that created the scheduled event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator_id: Option<UserId>,
+    /// the name of the scheduled event (1-100 characters)
+    pub name: RcStr,
+    /// the description of the scheduled event (1-1000 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<RcStr>,
+    /// the time the scheduled event will start
+    pub scheduled_start_time: RcStr,
+    /// the time the scheduled event will end, required if entity_type is
+    /// EXTERNAL
+    pub scheduled_end_time: Option<RcStr>,
+    /// the privacy level of the scheduled event
+    pub privacy_level: GuildScheduledEventPrivacyLevel,
+    /// the status of the scheduled event
+    pub status: GuildScheduledEventStatus,
+    /// the type of the scheduled event
+    pub entity_type: GuildScheduledEventEntityType,
+    /// the id of an entity associated with a guild scheduled event
+    pub entity_id: Option<RcStr>,
+    /// additional metadata for the guild scheduled event
+    pub entity_metadata: Option<GuildScheduledEventEntityMetadata>,
+    /// the user that created the scheduled event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator: Option<User>,
+    /// the number of users subscribed to the scheduled event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_count: Option<u32>,
+    /// the cover image hash of the scheduled event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<RcStr>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GuildScheduledEventEntityMetadata {
+    /// location of the event (1-100 characters), required for events with
+    /// entity_type EXTERNAL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<RcStr>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GuildScheduledEventPrivacyLevel {
+    /// the scheduled event is only accessible to guild members
+    GuildOnly = 2,
+}
+
+impl_serde_for_integer_enum!(GuildScheduledEventPrivacyLevel, u8, [GuildOnly]);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GuildScheduledEventStatus {
+    Scheduled = 1,
+    Active = 2,
+    Completed = 3,
+    Canceled = 4,
+}
+
+impl_serde_for_integer_enum!(
+    GuildScheduledEventStatus,
+    u8,
+    [Scheduled, Active, Completed, Canceled]
+);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GuildScheduledEventEntityType {
+    StageInstance = 1,
+    Voice = 2,
+    External = 3,
+}
+
+impl_serde_for_integer_enum!(
+    GuildScheduledEventEntityType,
+    u8,
+    [StageInstance, Voice, External]
+);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GuildScheduledEventUser {
+    /// the scheduled event id which the user subscribed to
+    pub guild_scheduled_event_id: ScheduledEventId,
+    /// user which subscribed to an event
+    pub user: User,
+    /// guild member data for this user for the guild which this event belongs
+    /// to, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<serde_json::Value>,
+}
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr};
+
+use crate::{
+    id::{GuildId, StickerId, StickerPackId},
+    impl_serde_for_integer_enum,
+    user::User,
+};
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Sticker {
+    /// id of the sticker
+    pub id: StickerId,
+    /// for standard stickers, id of the pack the sticker is from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_id: Option<StickerPackId>,
+    /// name of the sticker
+    pub name: RcStr,
+    /// description of the sticker
+    pub description: Option<RcStr>,
+    /// autocomplete/suggestion tags for the sticker (max 200 characters)
+    pub tags: RcStr,
+    /// type of sticker
+    pub r#type: StickerType,
+    /// type of sticker format
+    pub format_type: StickerFormatType,
+    /// whether this guild sticker can be used, may be false due to loss of
+    /// Server Boosts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available: Option<bool>,
+    /// id of the guild that owns this sticker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<GuildId>,
+    /// the user that uploaded the guild sticker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<User>,
+    /// the standard sticker's sort order within its pack
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_value: Option<u32>,
+}
+
+/// The smallest amount of data required to render a sticker, sent with
+/// messages
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StickerItem {
+    /// id of the sticker
+    pub id: StickerId,
+    /// name of the sticker
+    pub name: RcStr,
+    /// type of sticker format
+    pub format_type: StickerFormatType,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StickerType {
+    /// an official sticker in a pack
+    Standard = 1,
+    /// a sticker uploaded to a guild for the guild's members
+    Guild = 2,
+}
+
+impl_serde_for_integer_enum!(StickerType, u8, [Standard, Guild]);
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StickerFormatType {
+    Png = 1,
+    Apng = 2,
+    Lottie = 3,
+    Gif = 4,
+}
+
+impl_serde_for_integer_enum!(StickerFormatType, u8, [Png, Apng, Lottie, Gif]);
+
+#[cfg(test)]
+mod tests {
+    use super::{Sticker, StickerFormatType, StickerType};
+    use crate::utils::assert_round_trip;
+
+    #[test]
+    fn test_sticker_round_trip() {
+        let stickers = assert_round_trip::<Vec<Sticker>>(include_str!("../fixtures/stickers.json"));
+
+        assert_eq!(stickers[0].r#type, StickerType::Standard);
+        assert_eq!(stickers[0].format_type, StickerFormatType::Lottie);
+        assert_eq!(stickers[1].r#type, StickerType::Guild);
+        assert!(stickers[1].user.is_some());
+    }
+}
@@ -14,3 +14,112 @@ macro_rules! impl_deserialize_from_bits {
         }
     };
 }
+
+#[macro_export]
+macro_rules! impl_serialize_to_bits {
+    ($name:ident) => {
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                self.bits().serialize(serializer)
+            }
+        }
+    };
+}
+
+/// Serializes a fieldless enum as its discriminant, the enum has to be `Copy`
+#[macro_export]
+macro_rules! impl_serde_for_integer_enum {
+    ($name:ident, $type:ty, [$($variant:ident),* $(,)?]) => {
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                (*self as $type).serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let value = <$type>::deserialize(deserializer)?;
+                $(
+                    if value == $name::$variant as $type {
+                        return Ok($name::$variant);
+                    }
+                )*
+
+                Err(serde::de::Error::custom(format!(
+                    "unknown {} {value}",
+                    stringify!($name)
+                )))
+            }
+        }
+    };
+    // Values that aren't listed are kept in the `$unknown` variant instead of
+    // failing to deserialize, as Discord adds new ones without a new API version
+    ($name:ident, $type:ty, [$($variant:ident = $value:literal),* $(,)?], $unknown:ident) => {
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                let value: $type = match self {
+                    $($name::$variant => $value,)*
+                    $name::$unknown(value) => *value,
+                };
+                value.serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                Ok(match <$type>::deserialize(deserializer)? {
+                    $($value => $name::$variant,)*
+                    value => $name::$unknown(value),
+                })
+            }
+        }
+    };
+}
+
+/// Deserializes a payload from `fixtures/` into `T` and checks that serializing
+/// it gives back the same payload. Missing fields and `null` are treated the
+/// same, as Discord isn't consistent about which of the two it sends.
+#[cfg(test)]
+pub(crate) fn assert_round_trip<T>(fixture: &str) -> T
+where
+    T: serde::de::DeserializeOwned + serde::Serialize,
+{
+    fn strip_nulls(value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => serde_json::Value::Object(
+                map.into_iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(key, value)| (key, strip_nulls(value)))
+                    .collect(),
+            ),
+            serde_json::Value::Array(values) => {
+                serde_json::Value::Array(values.into_iter().map(strip_nulls).collect())
+            }
+            value => value,
+        }
+    }
+
+    let payload = serde_json::from_str::<serde_json::Value>(fixture).unwrap();
+    let value = serde_json::from_value::<T>(payload.clone()).unwrap();
+    assert_eq!(
+        strip_nulls(serde_json::to_value(&value).unwrap()),
+        strip_nulls(payload)
+    );
+
+    value
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr};
+
+use crate::{
+    guild::GuildMember,
+    id::{ChannelId, GuildId, UserId},
+};
+
+/// A user's voice connection status
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VoiceState {
+    /// the guild id this voice state is for, missing from the voice states of
+    /// the GUILD_CREATE event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<GuildId>,
+    /// the channel id this user is connected to, null when they disconnected
+    pub channel_id: Option<ChannelId>,
+    /// the user id this voice state is for
+    pub user_id: UserId,
+    /// the guild member this voice state is for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<GuildMember>,
+    /// the session id for this voice state
+    pub session_id: RcStr,
+    /// whether this user is deafened by the server
+    pub deaf: bool,
+    /// whether this user is muted by the server
+    pub mute: bool,
+    /// whether this user is locally deafened
+    pub self_deaf: bool,
+    /// whether this user is locally muted
+    pub self_mute: bool,
+    /// whether this user is streaming using "Go Live"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub self_stream: Option<bool>,
+    /// whether this user's camera is enabled
+    pub self_video: bool,
+    /// whether this user's permission to speak is denied
+    pub suppress: bool,
+    /// the time at which the user requested to speak
+    pub request_to_speak_timestamp: Option<RcStr>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VoiceState;
+    use crate::{id::ChannelId, utils::assert_round_trip};
+
+    #[test]
+    fn test_voice_state_round_trip() {
+        let voice_state =
+            assert_round_trip::<VoiceState>(include_str!("../fixtures/voice_state.json"));
+
+        assert_eq!(voice_state.channel_id, Some(ChannelId(157733188964188161)));
+        assert!(voice_state.self_mute);
+        assert!(voice_state.member.is_some());
+    }
+}
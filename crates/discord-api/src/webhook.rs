@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use turbopack_binding::turbo::tasks::{self as turbo_tasks, RcStr};
+
+use crate::{
+    id::{ApplicationId, ChannelId, GuildId, WebhookId},
+    impl_serde_for_integer_enum,
+    user::User,
+};
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Webhook {
+    /// the id of the webhook
+    pub id: WebhookId,
+    /// the type of the webhook
+    pub r#type: WebhookType,
+    /// the guild id this webhook is for, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<GuildId>,
+    /// the channel id this webhook is for, if any
+    pub channel_id: Option<ChannelId>,
+    /// the user this webhook was created by (not returned when getting a
+    /// webhook with its token)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<User>,
+    /// the default name of the webhook
+    pub name: Option<RcStr>,
+    /// the default user avatar hash of the webhook
+    pub avatar: Option<RcStr>,
+    /// the secure token of the webhook (returned for Incoming Webhooks)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<RcStr>,
+    /// the bot/OAuth2 application that created this webhook
+    pub application_id: Option<ApplicationId>,
+    /// the guild of the channel that this webhook is following (returned for
+    /// Channel Follower Webhooks)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_guild: Option<JsonValue>,
+    /// the channel that this webhook is following (returned for Channel
+    /// Follower Webhooks)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_channel: Option<JsonValue>,
+    /// the url used for executing the webhook (returned by the webhooks OAuth2
+    /// flow)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<RcStr>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WebhookType {
+    /// Incoming Webhooks can post messages to channels with a generated token
+    Incoming = 1,
+    /// Channel Follower Webhooks are internal webhooks used with Channel
+    /// Following to post new messages into channels
+    ChannelFollower = 2,
+    /// Application webhooks are webhooks used with Interactions
+    Application = 3,
+}
+
+impl_serde_for_integer_enum!(WebhookType, u8, [Incoming, ChannelFollower, Application]);
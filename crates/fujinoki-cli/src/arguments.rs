@@ -82,6 +82,7 @@ impl Arguments {
                 Command::Upgrade(args) => args.common.dir.as_deref(),
                 Command::Dev(args) => args.common.dir.as_deref(),
                 Command::Build(args) => args.common.dir.as_deref(),
+                Command::Sync(args) => args.common.dir.as_deref(),
                 _ => None,
             }
         } else {
@@ -110,6 +111,8 @@ pub enum Command {
     Upgrade(UpgradeArguments),
     Dev(DevArguments),
     Build(BuildArguments),
+    /// Register the application commands with Discord
+    Sync(SyncArguments),
 }
 
 #[derive(Debug, Args)]
@@ -185,3 +188,20 @@ pub struct BuildArguments {
     #[clap(long)]
     pub no_minify: bool,
 }
+
+#[derive(Debug, Args)]
+pub struct SyncArguments {
+    #[clap(flatten)]
+    pub common: CommonArguments,
+    #[clap(flatten)]
+    pub turbo: TurboArguments,
+
+    /// Register the commands to the `devGuildIds` of the config instead of
+    /// globally
+    #[clap(long)]
+    pub dev: bool,
+
+    /// Print the changes without registering them
+    #[clap(long)]
+    pub dry_run: bool,
+}
@@ -10,16 +10,21 @@ use fujinoki_cli_utils::issue::{ConsoleUi, LogOptions};
 use fujinoki_core::{
     config::FujinokiConfig,
     structures::{
-        commands::{directory_tree_to_commands_metadata, find_commands_dir},
-        events::{directory_tree_to_events_metadata, find_events_dir},
+        commands::{
+            directory_tree_to_commands_metadata, find_commands_dir, find_message_commands_dir,
+            CommandsMetadata,
+        },
+        events::{directory_tree_to_events_metadata, find_events_dir, EventsMetadata},
         get_directory_tree,
+        tasks::{directory_tree_to_tasks_metadata, find_tasks_dir, TasksMetadata},
     },
 };
+use fujinoki_websocket::source::{ContentSourceData, EntryMap, Executor};
 use turbopack_binding::{
     turbo::{
         tasks::{
             self as turbo_tasks, RcStr, ReadConsistency, TransientInstance, TryJoinIterExt,
-            TurboTasks, Value, Vc,
+            TurboTasks, Value, ValueToString, Vc,
         },
         tasks_fs::FileSystem,
         tasks_memory::MemoryBackend,
@@ -53,9 +58,12 @@ use turbopack_binding::{
 use crate::{
     arguments::BuildArguments,
     contexts::{get_asset_context, get_compile_time_info, NodeEnv},
+    dev::source::get_project_path,
+    typegen::write_types,
     util::{
-        commands_metadata_to_entry_requests, events_metadata_to_entry_requests, normalize_dirs,
-        output_fs, project_fs, EntryRequest, EntryRequests, NormalizedDirs,
+        commands_metadata_to_entry_requests, events_metadata_to_entry_requests,
+        middleware_by_entry, middleware_entry, normalize_dirs, output_fs, project_fs,
+        tasks_metadata_to_entry_requests, EntryRequest, EntryRequests, NormalizedDirs,
     },
 };
 
@@ -119,7 +127,7 @@ impl FujinokiBuildBuilder {
         let task = self.turbo_tasks.spawn_once_task::<(), _>(async move {
             let build_result = build_internal(
                 self.project_dir.clone().into(),
-                self.root_dir.into(),
+                self.root_dir.clone().into(),
                 EntryRequests(
                     self.entry_requests
                         .iter()
@@ -136,7 +144,7 @@ impl FujinokiBuildBuilder {
 
             let issue_reporter: Vc<Box<dyn IssueReporter>> =
                 Vc::upcast(ConsoleUi::new(TransientInstance::new(LogOptions {
-                    project_dir: PathBuf::from(self.project_dir),
+                    project_dir: PathBuf::from(self.project_dir.clone()),
                     current_dir: current_dir().unwrap(),
                     show_all: self.show_all,
                     log_detail: self.log_detail,
@@ -152,6 +160,39 @@ impl FujinokiBuildBuilder {
             )
             .await?;
 
+            let project_path =
+                get_project_path(self.root_dir.into(), self.project_dir.clone().into());
+            let config = FujinokiConfig::from_json(
+                project_path.join("fujinoki.config.json".into()),
+                Some(NodeEnv::Production.to_string().into()),
+            );
+            handle_issues(
+                config,
+                issue_reporter,
+                IssueSeverity::Fatal.cell(),
+                None,
+                Some("get config"),
+            )
+            .await?;
+
+            discord_api::rest::configure(config.http_options().await?.clone_value())?;
+
+            // The types are generated from the commands and events of this build
+            let types = write_types(
+                self.project_dir.into(),
+                build_result,
+                config,
+                issue_reporter,
+            );
+            handle_issues(
+                types,
+                issue_reporter,
+                IssueSeverity::Fatal.cell(),
+                None,
+                Some("generate types"),
+            )
+            .await?;
+
             Ok(Default::default())
         });
 
@@ -169,7 +210,7 @@ async fn build_internal(
     root_dir: RcStr,
     entry_requests: Vc<EntryRequests>,
     minify_type: MinifyType,
-) -> Result<Vc<()>> {
+) -> Result<Vc<ContentSourceData>> {
     let env = node_build_environment();
     let output_fs = output_fs(project_dir.clone());
     let project_fs = project_fs(root_dir.clone(), Default::default(), false);
@@ -198,10 +239,15 @@ async fn build_internal(
         .map(|r| r.clone())
         .collect::<Vec<_>>();
 
+    let mut events_metadata = EventsMetadata::default().cell();
+    let mut commands_metadata = CommandsMetadata::default().cell();
+    let mut message_commands_metadata = CommandsMetadata::default().cell();
+    let mut tasks_metadata = TasksMetadata::default().cell();
+
     let events_dir = find_events_dir(project_path);
     if let Some(events_dir) = &*events_dir.await? {
         let directory_tree = get_directory_tree(*events_dir, config.file_extensions());
-        let events_metadata = directory_tree_to_events_metadata(*events_dir, directory_tree);
+        events_metadata = directory_tree_to_events_metadata(*events_dir, directory_tree);
         let new_entry_requests = events_metadata_to_entry_requests(events_metadata).await?;
         for entry in new_entry_requests.iter() {
             entry_requests.push(*entry);
@@ -211,13 +257,35 @@ async fn build_internal(
     let commands_dir = find_commands_dir(project_path);
     if let Some(commands_dir) = &*commands_dir.await? {
         let directory_tree = get_directory_tree(*commands_dir, config.file_extensions());
-        let commands_metadata = directory_tree_to_commands_metadata(*commands_dir, directory_tree);
+        commands_metadata = directory_tree_to_commands_metadata(*commands_dir, directory_tree);
         let new_entry_requests = commands_metadata_to_entry_requests(commands_metadata).await?;
         for entry in new_entry_requests.iter() {
             entry_requests.push(*entry);
         }
     }
 
+    let message_commands_dir = find_message_commands_dir(project_path);
+    if let Some(message_commands_dir) = &*message_commands_dir.await? {
+        let directory_tree = get_directory_tree(*message_commands_dir, config.file_extensions());
+        message_commands_metadata =
+            directory_tree_to_commands_metadata(*message_commands_dir, directory_tree);
+        let new_entry_requests =
+            commands_metadata_to_entry_requests(message_commands_metadata).await?;
+        for entry in new_entry_requests.iter() {
+            entry_requests.push(*entry);
+        }
+    }
+
+    let tasks_dir = find_tasks_dir(project_path);
+    if let Some(tasks_dir) = &*tasks_dir.await? {
+        let directory_tree = get_directory_tree(*tasks_dir, config.file_extensions());
+        tasks_metadata = directory_tree_to_tasks_metadata(*tasks_dir, directory_tree);
+        let new_entry_requests = tasks_metadata_to_entry_requests(tasks_metadata).await?;
+        for entry in new_entry_requests.iter() {
+            entry_requests.push(*entry);
+        }
+    }
+
     let chunking_context = Vc::upcast(
         NodeJsChunkingContext::builder(
             project_path,
@@ -233,8 +301,8 @@ async fn build_internal(
     );
 
     let compile_time_info = get_compile_time_info(node_env);
-    let execution_context =
-        ExecutionContext::new(project_path, chunking_context, load_env(project_path));
+    let process_env = load_env(project_path);
+    let execution_context = ExecutionContext::new(project_path, chunking_context, process_env);
     let asset_context = get_asset_context(project_path, execution_context, compile_time_info);
 
     let entry_requests = (*entry_requests
@@ -283,9 +351,36 @@ async fn build_internal(
         .try_join()
         .await?;
 
+    // Handlers are bundled with the `_middleware` files above them
+    let middleware = middleware_by_entry(
+        events_metadata,
+        &[commands_metadata, message_commands_metadata],
+    )
+    .await?;
+    let entries = entries
+        .into_iter()
+        .map(|entry| {
+            let middleware = &middleware;
+            async move {
+                let path = entry.ident().path().to_string().await?.clone_value();
+                let entry = match middleware.get(&path) {
+                    Some(middleware) => middleware_entry(entry, middleware.clone(), asset_context),
+                    None => entry,
+                };
+                Ok((path, entry))
+            }
+        })
+        .try_join()
+        .await?;
+
+    let mut mapped_entries = EntryMap::empty();
+    for (path, entry) in &entries {
+        mapped_entries.insert_entry(path.clone(), *entry);
+    }
+
     let entry_chunk_groups = entries
         .into_iter()
-        .map(|entry_module| async move {
+        .map(|(_, entry_module)| async move {
             Ok(
                 if let Some(ecmascript) =
                     Vc::try_resolve_downcast_type::<EcmascriptModuleAsset>(entry_module).await?
@@ -354,11 +449,33 @@ async fn build_internal(
         .try_join()
         .await?;
 
-    Ok(Default::default())
+    // The commands and events of the build, which the types are generated from
+    Ok(ContentSourceData {
+        project_path,
+        executor: Executor::new(
+            project_path,
+            process_env,
+            asset_context,
+            chunking_context,
+            None,
+        ),
+        entries: mapped_entries.cell(),
+        events: events_metadata,
+        commands: commands_metadata,
+        events_dir,
+        commands_dir,
+        message_commands: message_commands_metadata,
+        message_commands_dir,
+        tasks: tasks_metadata,
+        tasks_dir,
+    }
+    .cell())
 }
 
 pub async fn build(args: &BuildArguments) -> Result<()> {
     register();
+    // The types are generated by the command updater of `fujinoki dev`
+    crate::dev::register();
 
     let NormalizedDirs {
         project_dir,
@@ -371,7 +488,7 @@ pub async fn build(args: &BuildArguments) -> Result<()> {
             .map_or(usize::MAX, |l| l * 1024 * 1024),
     ));
 
-    let builder = FujinokiBuildBuilder::new(tt.clone(), project_dir.clone(), root_dir)
+    let builder = FujinokiBuildBuilder::new(tt, project_dir, root_dir)
         .log_detail(args.turbo.log_detail)
         .log_level(
             args.turbo
@@ -12,11 +12,17 @@ use console::style;
 use fujinoki_cli_utils::issue::{ConsoleUi, LogOptions};
 use fujinoki_core::config::FujinokiConfig;
 use fujinoki_dev_server::DevServer;
+use fujinoki_websocket::{
+    discord::commands::update::{CommandsUpdate, DiscordApplicationCommandsUpdater},
+    scheduler::SchedulerEvent,
+    source::{ContentSource, ContentSourceData},
+};
 use owo_colors::OwoColorize;
 use turbopack_binding::{
     turbo::{
         malloc::TurboMalloc,
         tasks::{
+            self as turbo_tasks,
             util::{FormatBytes, FormatDuration},
             TransientInstance, TurboTasks, UpdateInfo, Vc,
         },
@@ -32,6 +38,7 @@ use crate::{
     arguments::DevArguments,
     contexts::NodeEnv,
     dev::source::{get_project_path, source},
+    typegen::write_types,
     util::{normalize_dirs, EntryRequest, NormalizedDirs},
 };
 
@@ -135,31 +142,144 @@ impl FujinokiDevServerBuilder {
 
                 // TODO validate the config
 
+                discord_api::rest::configure(config.http_options().await?.clone_value())?;
+
                 Ok(config)
             })
             .await?;
 
         let gateway = DevServer::connect(None, discord_api::VERSION).await?;
 
+        let types_project_dir = project_dir.clone();
         let source = move || {
             source(
                 root_dir.into(),
                 project_dir.into(),
                 entry_requests.clone(),
                 config,
+                NodeEnv::Development.cell(),
             )
         };
 
+        // Keeps `.fujinoki/types.d.ts` up to date with the handlers
+        let types_source = source.clone();
+        let get_issue_reporter = issue_reporter_arc.clone();
+        tasks.spawn_root_task(move || {
+            let source = types_source.clone();
+            let project_dir = types_project_dir.clone();
+            let issue_reporter = get_issue_reporter();
+            async move {
+                let types = write_types(project_dir.into(), source(), config, issue_reporter);
+                handle_issues(
+                    types,
+                    issue_reporter,
+                    IssueSeverity::Fatal.cell(),
+                    None,
+                    Some("generate types"),
+                )
+                .await?;
+
+                Ok(types)
+            }
+        });
+
+        // Registers the commands with Discord, this is re-executed whenever the
+        // commands change and only the commands whose files changed are
+        // evaluated again
+        let commands_source = source.clone();
+        let get_issue_reporter = issue_reporter_arc.clone();
+        tasks.spawn_root_task(move || {
+            let source = commands_source.clone();
+            let issue_reporter = get_issue_reporter();
+            async move {
+                let update = update_commands(source(), config, issue_reporter);
+                handle_issues(
+                    update,
+                    issue_reporter,
+                    IssueSeverity::Fatal.cell(),
+                    None,
+                    Some("update application commands"),
+                )
+                .await?;
+
+                let diffs = update.await?;
+                let changed = diffs
+                    .iter()
+                    .flatten()
+                    .filter(|scope| !scope.diff.is_empty());
+                for scope in changed {
+                    print!(
+                        "{event_type} - updated application commands ({scope})\n{diff}",
+                        event_type = "event".purple(),
+                        scope = scope.scope,
+                        diff = scope.diff,
+                    );
+                }
+
+                Ok(update)
+            }
+        });
+
         Ok(gateway.serve(
             tasks.clone(),
             source,
             issue_reporter_arc,
             self.exit_handler,
             config,
+            Arc::new(|event: SchedulerEvent| match event {
+                SchedulerEvent::Failed { .. } => {
+                    eprintln!("{event_type} - {event}", event_type = "error".red())
+                }
+                _ => println!("{event_type} - {event}", event_type = "event".purple()),
+            }),
         ))
     }
 }
 
+/// Registers the commands of `commands/` to the `devGuildIds` of the config,
+/// or globally without them
+#[turbo_tasks::function]
+async fn update_commands(
+    source: Vc<ContentSourceData>,
+    config: Vc<FujinokiConfig>,
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+) -> Result<Vc<CommandsUpdate>> {
+    if source.get_commands_dir().await?.is_none() {
+        return Ok(Vc::cell(Some(vec![])));
+    }
+
+    let application = discord_api::rest::application::get_current_application(
+        config.client().token().await?.clone_value(),
+        None,
+    );
+    handle_issues(
+        application,
+        issue_reporter,
+        IssueSeverity::Fatal.cell(),
+        None,
+        Some("get current application"),
+    )
+    .await?;
+    let Some(application) = *application.await? else {
+        return Ok(Vc::cell(None));
+    };
+
+    let updater = DiscordApplicationCommandsUpdater::new(
+        config,
+        source,
+        issue_reporter,
+        application.await?.id,
+        true,
+        false,
+    );
+
+    Ok(updater.update_application_commands(
+        source.get_commands(),
+        source.get_entries(),
+        Vc::cell(Some(source.get_commands_dir().routes_changed(config))),
+    ))
+}
+
 pub fn register() {
     fujinoki_cli_utils::register();
     fujinoki_dev_server::register();
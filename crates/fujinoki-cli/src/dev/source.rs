@@ -4,9 +4,13 @@ use anyhow::{anyhow, Result};
 use fujinoki_core::{
     config::FujinokiConfig,
     structures::{
-        commands::{directory_tree_to_commands_metadata, find_commands_dir, CommandsMetadata},
+        commands::{
+            directory_tree_to_commands_metadata, find_commands_dir, find_message_commands_dir,
+            CommandsMetadata,
+        },
         events::{directory_tree_to_events_metadata, find_events_dir, EventsMetadata},
         get_directory_tree,
+        tasks::{directory_tree_to_tasks_metadata, find_tasks_dir, TasksMetadata},
     },
 };
 use fujinoki_websocket::source::{ContentSourceData, EntryMap, Executor};
@@ -41,8 +45,9 @@ use turbopack_binding::{
 use crate::{
     contexts::{get_asset_context, get_compile_time_info, NodeEnv},
     util::{
-        commands_metadata_to_entry_requests, events_metadata_to_entry_requests, output_fs,
-        project_fs, EntryRequest,
+        commands_metadata_to_entry_requests, events_metadata_to_entry_requests,
+        middleware_by_entry, middleware_entry, output_fs, project_fs,
+        tasks_metadata_to_entry_requests, EntryRequest,
     },
 };
 
@@ -74,6 +79,7 @@ pub async fn source(
     project_dir: RcStr,
     entry_requests: TransientInstance<Vec<EntryRequest>>,
     config: Vc<FujinokiConfig>,
+    node_env: Vc<NodeEnv>,
 ) -> Result<Vc<ContentSourceData>> {
     let output_fs = output_fs(project_dir.clone());
     let build_output_root = output_fs.root().join(".turbopack/build".into());
@@ -82,7 +88,6 @@ pub async fn source(
 
     let process_env: Vc<Box<dyn ProcessEnv>> = load_env(project_path);
     let build_env = node_build_environment();
-    let node_env = NodeEnv::Development.cell();
 
     let build_chunking_context = NodeJsChunkingContext::builder(
         project_path,
@@ -106,6 +111,8 @@ pub async fn source(
     let mut entry_requests = entry_requests.iter().map(|r| r.clone()).collect::<Vec<_>>();
     let mut events_metadata = EventsMetadata::default().cell();
     let mut commands_metadata = CommandsMetadata::default().cell();
+    let mut message_commands_metadata = CommandsMetadata::default().cell();
+    let mut tasks_metadata = TasksMetadata::default().cell();
 
     let events_dir = find_events_dir(project_path);
     if let Some(events_dir) = *events_dir.await? {
@@ -129,6 +136,31 @@ pub async fn source(
         }
     }
 
+    let message_commands_dir = find_message_commands_dir(project_path);
+    if let Some(message_commands_dir) = *message_commands_dir.await? {
+        let directory_tree =
+            get_directory_tree(message_commands_dir.clone(), config.file_extensions());
+        message_commands_metadata =
+            directory_tree_to_commands_metadata(message_commands_dir, directory_tree);
+        let new_entry_requests =
+            commands_metadata_to_entry_requests(message_commands_metadata).await?;
+        for entry in new_entry_requests.iter() {
+            let entry = entry.clone().await?;
+            entry_requests.push(entry.clone_value());
+        }
+    }
+
+    let tasks_dir = find_tasks_dir(project_path);
+    if let Some(tasks_dir) = *tasks_dir.await? {
+        let directory_tree = get_directory_tree(tasks_dir.clone(), config.file_extensions());
+        tasks_metadata = directory_tree_to_tasks_metadata(tasks_dir, directory_tree);
+        let new_entry_requests = tasks_metadata_to_entry_requests(tasks_metadata).await?;
+        for entry in new_entry_requests.iter() {
+            let entry = entry.clone().await?;
+            entry_requests.push(entry.clone_value());
+        }
+    }
+
     let entry_requests: Vec<Vc<Request>> = entry_requests
         .iter()
         .map(|r| match r {
@@ -187,10 +219,22 @@ pub async fn source(
         .try_join()
         .await?;
 
+    // Handlers are bundled with the `_middleware` files above them
+    let middleware = middleware_by_entry(
+        events_metadata,
+        &[commands_metadata, message_commands_metadata],
+    )
+    .await?;
+
     let mut mapped_entries = EntryMap::empty();
 
     for entry in entries.clone() {
-        mapped_entries.insert_entry(entry.ident().path().to_string().await?.clone_value(), entry);
+        let path = entry.ident().path().to_string().await?.clone_value();
+        let entry = match middleware.get(&path) {
+            Some(middleware) => middleware_entry(entry, middleware.clone(), asset_context),
+            None => entry,
+        };
+        mapped_entries.insert_entry(path, entry);
     }
 
     Ok((ContentSourceData {
@@ -208,6 +252,10 @@ pub async fn source(
         commands: commands_metadata,
         events_dir,
         commands_dir,
+        message_commands: message_commands_metadata,
+        message_commands_dir,
+        tasks: tasks_metadata,
+        tasks_dir,
     })
     .cell())
 }
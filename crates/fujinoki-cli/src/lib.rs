@@ -12,7 +12,9 @@ pub(crate) mod contexts;
 pub mod dev;
 pub(crate) mod embed_js;
 pub mod panic_handler;
+pub mod sync;
 pub mod tracing_presets;
+pub mod typegen;
 pub mod upgrade;
 pub(crate) mod util;
 
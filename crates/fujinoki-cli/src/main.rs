@@ -9,7 +9,7 @@ use fujinoki_cli::{
     arguments::{Arguments, Command},
     build, dev,
     panic_handler::panic_handler,
-    register,
+    register, sync,
     tracing_presets::{
         TRACING_DISCORD_TARGETS, TRACING_FUJINOKI_TARGETS, TRACING_OVERVIEW_TARGETS,
         TRACING_TURBOPACK_TARGETS, TRACING_TURBO_TASKS_TARGETS,
@@ -124,6 +124,7 @@ async fn main_inner(args: Arguments) -> Result<()> {
             Command::Upgrade(args) => upgrade::install_latest_build(args).await?,
             Command::Dev(args) => dev::start_server(args, exit_handler).await?,
             Command::Build(args) => build::build(args).await?,
+            Command::Sync(args) => sync::sync(args).await?,
         };
     }
 
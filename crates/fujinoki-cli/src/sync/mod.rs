@@ -0,0 +1,146 @@
+use std::{env::current_dir, path::PathBuf};
+
+use anyhow::{bail, Result};
+use console::style;
+use fujinoki_cli_utils::issue::{ConsoleUi, LogOptions};
+use fujinoki_core::config::FujinokiConfig;
+use fujinoki_websocket::{
+    discord::commands::update::{DiscordApplicationCommandsUpdater, ScopeCommandsDiff},
+    source::ContentSource,
+};
+use turbopack_binding::{
+    turbo::{
+        tasks::{TransientInstance, TurboTasks, Vc},
+        tasks_memory::MemoryBackend,
+    },
+    turbopack::core::issue::{handle_issues, IssueReporter, IssueSeverity},
+};
+
+use crate::{
+    arguments::SyncArguments,
+    contexts::NodeEnv,
+    dev::{
+        register,
+        source::{get_project_path, source},
+    },
+    util::{normalize_dirs, NormalizedDirs},
+};
+
+pub async fn sync(args: &SyncArguments) -> Result<()> {
+    register();
+
+    let NormalizedDirs {
+        project_dir,
+        root_dir,
+    } = normalize_dirs(&args.common.dir, &args.common.root)?;
+
+    let tt = TurboTasks::new(MemoryBackend::new(
+        args.turbo
+            .memory_limit
+            .map_or(usize::MAX, |l| l * 1024 * 1024),
+    ));
+
+    let log_args = TransientInstance::new(LogOptions {
+        current_dir: current_dir().unwrap(),
+        project_dir: PathBuf::from(project_dir.clone()),
+        show_all: args.turbo.show_all,
+        log_detail: args.turbo.log_detail,
+        log_level: args
+            .turbo
+            .log_level
+            .map_or_else(|| IssueSeverity::Warning, |l| l.0),
+    });
+    let dev = args.dev;
+    let dry_run = args.dry_run;
+    // Commands are synced as they are deployed unless `--dev` is passed
+    let node_env = if dev {
+        NodeEnv::Development
+    } else {
+        NodeEnv::Production
+    };
+
+    let diff = tt
+        .run_once::<Option<Vec<ScopeCommandsDiff>>>(async move {
+            let issue_reporter: Vc<Box<dyn IssueReporter>> = Vc::upcast(ConsoleUi::new(log_args));
+
+            let project_path =
+                get_project_path(root_dir.clone().into(), project_dir.clone().into());
+            let config = FujinokiConfig::from_json(
+                project_path.join("fujinoki.config.json".into()),
+                Some(node_env.to_string().into()),
+            );
+            handle_issues(
+                config,
+                issue_reporter,
+                IssueSeverity::Fatal.cell(),
+                None,
+                Some("get config"),
+            )
+            .await?;
+
+            discord_api::rest::configure(config.http_options().await?.clone_value())?;
+
+            let application = discord_api::rest::application::get_current_application(
+                config.client().token().await?.clone_value(),
+                None,
+            );
+            handle_issues(
+                application,
+                issue_reporter,
+                IssueSeverity::Fatal.cell(),
+                None,
+                Some("get current application"),
+            )
+            .await?;
+            let Some(application) = *application.await? else {
+                return Ok(None);
+            };
+
+            let source = source(
+                root_dir.into(),
+                project_dir.into(),
+                TransientInstance::new(vec![]),
+                config,
+                node_env.cell(),
+            );
+            let updater = DiscordApplicationCommandsUpdater::new(
+                config,
+                source,
+                issue_reporter,
+                application.await?.id,
+                dev,
+                dry_run,
+            );
+            let diff = updater.update_application_commands(
+                source.get_commands(),
+                source.get_entries(),
+                Vc::cell(None),
+            );
+            handle_issues(
+                diff,
+                issue_reporter,
+                IssueSeverity::Fatal.cell(),
+                None,
+                Some("update application commands"),
+            )
+            .await?;
+
+            Ok(diff.await?.clone_value())
+        })
+        .await?;
+
+    let Some(diffs) = diff else {
+        bail!("Unable to sync application commands");
+    };
+
+    if dry_run {
+        println!("{} - application commands diff", style("info").cyan());
+    } else {
+        println!("{} - synced application commands", style("ready").green());
+    }
+    for scope in diffs {
+        print!("{}\n{}", scope.scope, scope.diff);
+    }
+
+    Ok(())
+}
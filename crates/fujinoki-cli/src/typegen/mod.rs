@@ -0,0 +1,569 @@
+//! Generates `.fujinoki/types.d.ts`, which types the arguments of the handlers
+//! in `events/` and `commands/`, e.g.
+//!
+//! ```ts
+//! export default (({ options }) => {
+//!   options.user.member;
+//! }) satisfies Fujinoki.CommandHandler<"ban">;
+//! ```
+
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use discord_api::{
+    application::command::{
+        ApplicationCommand, ApplicationCommandOption, ApplicationCommandOptionChoiceValue,
+        ApplicationCommandOptionType,
+    },
+    id::ApplicationId,
+};
+use fujinoki_core::{config::FujinokiConfig, structures::events::LifecycleEvent};
+use fujinoki_websocket::{
+    discord::commands::update::DiscordApplicationCommandsUpdater,
+    source::{ContentSource, ContentSourceData},
+};
+use turbopack_binding::{
+    turbo::{
+        tasks as turbo_tasks,
+        tasks::{RcStr, ValueToString, Vc},
+        tasks_fs::{File, FileContent, FileSystem},
+    },
+    turbopack::core::issue::IssueReporter,
+};
+
+use crate::util::output_fs;
+
+pub const TYPES_PATH: &str = ".fujinoki/types.d.ts";
+
+/// The types that don't depend on the project
+const PRELUDE: &str = r#"    type Snowflake = string;
+
+    interface User {
+      id: Snowflake;
+      username: string;
+      discriminator: string;
+      global_name: string | null;
+      avatar: string | null;
+      bot?: boolean;
+      [key: string]: unknown;
+    }
+
+    interface Member {
+      user?: User;
+      nick?: string | null;
+      roles: Snowflake[];
+      joined_at: string;
+      [key: string]: unknown;
+    }
+
+    /** A user option, with the member of the user when it was used in a guild */
+    interface ResolvedUser {
+      user: User;
+      member: Member | null;
+    }
+
+    interface Role {
+      id: Snowflake;
+      name: string;
+      color: number;
+      position: number;
+      permissions: string;
+      [key: string]: unknown;
+    }
+
+    interface Channel {
+      id: Snowflake;
+      type: number;
+      guild_id?: Snowflake;
+      name?: string | null;
+      parent_id?: Snowflake | null;
+      [key: string]: unknown;
+    }
+
+    interface Attachment {
+      id: Snowflake;
+      filename: string;
+      size: number;
+      url: string;
+      proxy_url: string;
+      content_type?: string;
+      [key: string]: unknown;
+    }
+
+    interface Guild {
+      id: Snowflake;
+      name: string;
+      owner_id: Snowflake;
+      [key: string]: unknown;
+    }
+
+    interface Message {
+      id: Snowflake;
+      channel_id: Snowflake;
+      guild_id?: Snowflake;
+      author: User;
+      member?: Member;
+      content: string;
+      timestamp: string;
+      attachments: Attachment[];
+      [key: string]: unknown;
+    }
+
+    interface Interaction {
+      id: Snowflake;
+      application_id: Snowflake;
+      type: number;
+      token: string;
+      guild_id?: Snowflake;
+      channel_id?: Snowflake;
+      member?: Member;
+      user?: User;
+      locale?: string;
+      guild_locale?: string;
+      [key: string]: unknown;
+    }
+
+    /** The data of READY, along with the cached entities of the event when the cache is enabled */
+    interface Client {
+      user: User;
+      application: { id: Snowflake; flags: number };
+      [key: string]: unknown;
+    }
+
+    type EventContext<Name extends keyof Events> = Name extends "READY"
+      ? { client: Client }
+      : Name extends "INTERACTION_CREATE"
+        ? { interaction: Events[Name]; client: Client }
+        : { data: Events[Name]; client: Client };
+
+    type EventHandler<Name extends keyof Events> = (
+      context: EventContext<Name>,
+    ) => unknown;
+
+    type CommandContext<Name extends keyof Commands> = Commands[Name] & {
+      interaction: Interaction;
+      client: Client;
+      locale: string;
+      guildLocale: string | null;
+    };
+
+    type CommandHandler<Name extends keyof Commands> = (
+      context: CommandContext<Name>,
+    ) => unknown;
+
+    interface MessageCommandContext {
+      message: Message;
+      client: Client;
+      args: string[];
+      prefix: string;
+      command: string;
+    }
+
+    interface TaskContext {
+      client: Client;
+      task: string;
+    }
+"#;
+
+/// Writes the types of the events and commands of `source` to
+/// `.fujinoki/types.d.ts`, the command definitions are evaluated to type
+/// their options
+#[turbo_tasks::function]
+pub async fn write_types(
+    project_dir: RcStr,
+    source: Vc<ContentSourceData>,
+    config: Vc<FujinokiConfig>,
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+) -> Result<Vc<()>> {
+    let events = source
+        .get_events()
+        .await?
+        .iter()
+        .map(|event| event.name.clone())
+        .collect::<BTreeSet<_>>();
+
+    // Only `data` is evaluated, which doesn't depend on the application
+    let updater = DiscordApplicationCommandsUpdater::new(
+        config,
+        source,
+        issue_reporter,
+        ApplicationId::default(),
+        false,
+        true,
+    );
+    let entries = source.get_entries();
+    let invalidation = Vc::cell(Some(source.get_commands_dir().routes_changed(config)));
+
+    let mut commands = vec![];
+    for command in source.get_commands().await?.iter() {
+        let entry = *entries.get_entry(command.file_path.to_string()).await?;
+        let definition =
+            updater.application_command(command.clone().cell(), Vc::cell(entry), invalidation);
+        let definition = match *definition.await? {
+            Some(definition) => {
+                serde_json::from_value::<ApplicationCommand>(definition.await?.command.clone()).ok()
+            }
+            // Invalid commands are reported as issues, and are typed loosely
+            None => None,
+        };
+        commands.push((command.name.clone(), definition));
+    }
+
+    output_fs(project_dir)
+        .root()
+        .join(TYPES_PATH.into())
+        .write(FileContent::Content(File::from(render_types(&events, &commands))).cell())
+        .await?;
+
+    Ok(Default::default())
+}
+
+fn render_types(
+    events: &BTreeSet<RcStr>,
+    commands: &[(RcStr, Option<ApplicationCommand>)],
+) -> String {
+    let mut types = String::new();
+    types.push_str("// Generated by `fujinoki dev` and `fujinoki build`, do not edit this file\n");
+    types.push_str("/* eslint-disable */\n\n");
+    types.push_str("declare global {\n  namespace Fujinoki {\n");
+    types.push_str(PRELUDE);
+
+    types.push_str("\n    interface Events {\n");
+    for event in events {
+        types.push_str(&format!("      {}: {};\n", event, event_data_type(event)));
+    }
+    types.push_str("    }\n");
+
+    types.push_str("\n    interface Commands {\n");
+    for (name, definition) in commands {
+        let variants = match definition
+            .as_ref()
+            .and_then(|command| command.options.as_deref())
+        {
+            Some(options) => {
+                let mut variants = vec![];
+                option_variants(options, &mut vec![], &mut variants);
+                variants
+            }
+            // Context menu commands and commands without options
+            None if definition.is_some() => vec!["{ subcommand: null; options: {} }".to_string()],
+            None => {
+                vec!["{ subcommand: string | null; options: Record<string, unknown> }".to_string()]
+            }
+        };
+
+        types.push_str(&format!(
+            "      {}:\n        | {};\n",
+            property_name(name),
+            variants.join("\n        | ")
+        ));
+    }
+    types.push_str("    }\n  }\n}\n\nexport {};\n");
+
+    types
+}
+
+/// Each subcommand has its own set of options, so every subcommand becomes a
+/// variant that is discriminated by `subcommand`
+fn option_variants(
+    options: &[ApplicationCommandOption],
+    path: &mut Vec<String>,
+    variants: &mut Vec<String>,
+) {
+    let subcommands = options
+        .iter()
+        .filter(|option| is_subcommand(option.r#type))
+        .collect::<Vec<_>>();
+    if !subcommands.is_empty() {
+        for subcommand in subcommands {
+            path.push(subcommand.name.clone());
+            option_variants(
+                subcommand.options.as_deref().unwrap_or_default(),
+                path,
+                variants,
+            );
+            path.pop();
+        }
+        return;
+    }
+
+    let subcommand = match path.is_empty() {
+        true => "null".to_string(),
+        false => string_literal(&path.join(" ")),
+    };
+    let options = match options.is_empty() {
+        true => "{}".to_string(),
+        false => {
+            let options = options
+                .iter()
+                .map(|option| format!("{}: {}", property_name(&option.name), option_type(option)))
+                .collect::<Vec<_>>()
+                .join("; ");
+            format!("{{ {options} }}")
+        }
+    };
+    variants.push(format!(
+        "{{ subcommand: {subcommand}; options: {options} }}"
+    ));
+}
+
+/// The type of an option as it is passed to the handler, options that aren't
+/// required are `null` when they were left out
+fn option_type(option: &ApplicationCommandOption) -> String {
+    let value = if !option.choices.is_empty() {
+        option
+            .choices
+            .iter()
+            .map(|choice| match &choice.value {
+                ApplicationCommandOptionChoiceValue::String(value) => string_literal(value),
+                ApplicationCommandOptionChoiceValue::Integer(value) => value.to_string(),
+                ApplicationCommandOptionChoiceValue::Double(value) => value.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    } else {
+        match option.r#type {
+            ApplicationCommandOptionType::String => "string",
+            ApplicationCommandOptionType::Integer | ApplicationCommandOptionType::Number => {
+                "number"
+            }
+            ApplicationCommandOptionType::Boolean => "boolean",
+            ApplicationCommandOptionType::User => "ResolvedUser",
+            ApplicationCommandOptionType::Channel => "Channel",
+            ApplicationCommandOptionType::Role => "Role",
+            ApplicationCommandOptionType::Mentionable => "ResolvedUser | Role",
+            ApplicationCommandOptionType::Attachment => "Attachment",
+            ApplicationCommandOptionType::SubCommand
+            | ApplicationCommandOptionType::SubCommandGroup => "never",
+        }
+        .to_string()
+    };
+
+    match option.required {
+        Some(true) => value,
+        _ => format!("{value} | null"),
+    }
+}
+
+/// The type of `data` of an event, events that aren't modelled are loosely
+/// typed
+fn event_data_type(name: &str) -> &'static str {
+    if let Some(event) = LifecycleEvent::ALL
+        .into_iter()
+        .find(|event| event.name() == name)
+    {
+        return match event {
+            LifecycleEvent::ShardReady => "{ shard: [id: number, count: number] | null }",
+            LifecycleEvent::Resumed => "null",
+            LifecycleEvent::Reconnecting => "{ reason: string; code?: number }",
+            LifecycleEvent::Disconnect => "{ code: number | null; reason: string | null }",
+            LifecycleEvent::Heartbeat => "{ latency: number }",
+            LifecycleEvent::Error => "{ message: string }",
+        };
+    }
+
+    match name {
+        "READY" => "Client",
+        "RESUMED" => "null",
+        "GUILD_CREATE" | "GUILD_UPDATE" => "Guild",
+        "GUILD_DELETE" => "{ id: Snowflake; unavailable?: boolean }",
+        "GUILD_MEMBER_ADD" | "GUILD_MEMBER_UPDATE" => "Member & { guild_id: Snowflake }",
+        "GUILD_MEMBER_REMOVE" => "{ guild_id: Snowflake; user: User }",
+        "GUILD_ROLE_CREATE" | "GUILD_ROLE_UPDATE" => "{ guild_id: Snowflake; role: Role }",
+        "GUILD_ROLE_DELETE" => "{ guild_id: Snowflake; role_id: Snowflake }",
+        "CHANNEL_CREATE" | "CHANNEL_UPDATE" | "CHANNEL_DELETE" | "THREAD_CREATE"
+        | "THREAD_UPDATE" | "THREAD_DELETE" => "Channel",
+        "MESSAGE_CREATE" | "MESSAGE_UPDATE" => "Message",
+        "MESSAGE_DELETE" => "{ id: Snowflake; channel_id: Snowflake; guild_id?: Snowflake }",
+        "MESSAGE_REACTION_ADD" | "MESSAGE_REACTION_REMOVE" => {
+            "{ user_id: Snowflake; channel_id: Snowflake; message_id: Snowflake; guild_id?: \
+             Snowflake; emoji: { id: Snowflake | null; name: string | null }; [key: string]: \
+             unknown }"
+        }
+        "INTERACTION_CREATE" => "Interaction",
+        _ => "Record<string, unknown>",
+    }
+}
+
+fn is_subcommand(r#type: ApplicationCommandOptionType) -> bool {
+    matches!(
+        r#type,
+        ApplicationCommandOptionType::SubCommand | ApplicationCommandOptionType::SubCommandGroup
+    )
+}
+
+/// Names of commands and options can contain `-`, which have to be quoted
+fn property_name(name: &str) -> String {
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|char| char.is_ascii_alphabetic() || char == '_' || char == '$')
+        && name
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '_' || char == '$');
+    match is_identifier {
+        true => name.to_string(),
+        false => string_literal(name),
+    }
+}
+
+fn string_literal(value: &str) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use discord_api::application::command::{ApplicationCommand, ApplicationCommandOption};
+    use serde_json::json;
+
+    use super::{option_type, option_variants, property_name, render_types};
+
+    fn option(value: serde_json::Value) -> ApplicationCommandOption {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn variants(options: serde_json::Value) -> Vec<String> {
+        let options: Vec<ApplicationCommandOption> = serde_json::from_value(options).unwrap();
+        let mut variants = vec![];
+        option_variants(&options, &mut vec![], &mut variants);
+        variants
+    }
+
+    #[test]
+    fn test_option_variants() {
+        assert_eq!(
+            variants(json!([
+                { "type": 6, "name": "user", "description": "", "required": true },
+                { "type": 3, "name": "reason", "description": "" },
+            ])),
+            vec!["{ subcommand: null; options: { user: ResolvedUser; reason: string | null } }"]
+        );
+        assert_eq!(
+            variants(json!([])),
+            vec!["{ subcommand: null; options: {} }"]
+        );
+    }
+
+    #[test]
+    fn test_subcommand_variants() {
+        assert_eq!(
+            variants(json!([
+                {
+                    "type": 2,
+                    "name": "role",
+                    "description": "",
+                    "options": [
+                        {
+                            "type": 1,
+                            "name": "add",
+                            "description": "",
+                            "options": [
+                                { "type": 8, "name": "role", "description": "", "required": true },
+                            ],
+                        },
+                        { "type": 1, "name": "clear", "description": "" },
+                    ],
+                },
+                { "type": 1, "name": "reset", "description": "" },
+            ])),
+            vec![
+                "{ subcommand: \"role add\"; options: { role: Role } }",
+                "{ subcommand: \"role clear\"; options: {} }",
+                "{ subcommand: \"reset\"; options: {} }",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_choices() {
+        assert_eq!(
+            option_type(&option(json!({
+                "type": 3,
+                "name": "color",
+                "description": "",
+                "required": true,
+                "choices": [
+                    { "name": "Red", "value": "red" },
+                    { "name": "Blue", "value": "blue" },
+                ],
+            }))),
+            "\"red\" | \"blue\""
+        );
+        assert_eq!(
+            option_type(&option(json!({
+                "type": 4,
+                "name": "days",
+                "description": "",
+                "choices": [
+                    { "name": "One", "value": 1 },
+                    { "name": "Seven", "value": 7 },
+                ],
+            }))),
+            "1 | 7 | null"
+        );
+    }
+
+    #[test]
+    fn test_optional_options() {
+        assert_eq!(
+            option_type(&option(
+                json!({ "type": 5, "name": "silent", "description": "" })
+            )),
+            "boolean | null"
+        );
+        assert_eq!(
+            option_type(&option(json!({
+                "type": 5,
+                "name": "silent",
+                "description": "",
+                "required": false,
+            }))),
+            "boolean | null"
+        );
+        assert_eq!(
+            option_type(&option(json!({
+                "type": 10,
+                "name": "amount",
+                "description": "",
+                "required": true,
+            }))),
+            "number"
+        );
+    }
+
+    #[test]
+    fn test_property_name() {
+        assert_eq!(property_name("ban"), "ban");
+        assert_eq!(property_name("$_user2"), "$_user2");
+        assert_eq!(property_name("ban-user"), "\"ban-user\"");
+        assert_eq!(property_name("2fa"), "\"2fa\"");
+    }
+
+    #[test]
+    fn test_render_types() {
+        let command: ApplicationCommand = serde_json::from_value(json!({
+            "name": "ban-user",
+            "options": [
+                { "type": 6, "name": "target-user", "description": "", "required": true },
+            ],
+        }))
+        .unwrap();
+        let types = render_types(
+            &BTreeSet::from(["MESSAGE_CREATE".into()]),
+            &[("ban-user".into(), Some(command)), ("ping".into(), None)],
+        );
+
+        assert!(types.contains("      MESSAGE_CREATE: Message;\n"));
+        assert!(types.contains(
+            "      \"ban-user\":\n        | { subcommand: null; options: { \"target-user\": \
+             ResolvedUser } };\n"
+        ));
+        // Commands that couldn't be evaluated are typed loosely
+        assert!(types.contains(
+            "      ping:\n        | { subcommand: string | null; options: Record<string, unknown> \
+             };\n"
+        ));
+    }
+}
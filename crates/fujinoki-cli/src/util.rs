@@ -1,21 +1,26 @@
-use std::{env::current_dir, path::PathBuf};
+use std::{collections::HashMap, env::current_dir, fmt::Write, path::PathBuf};
 
 use anyhow::{Context, Result};
 use dunce::canonicalize;
-use fujinoki_core::structures::{commands::CommandsMetadata, events::EventsMetadata};
+use fujinoki_core::structures::{
+    commands::CommandsMetadata, events::EventsMetadata, tasks::TasksMetadata,
+};
 use serde_json::Value as JsonValue;
 use turbopack_binding::{
     turbo::{
         tasks as turbo_tasks,
-        tasks::{RcStr, Value, Vc},
-        tasks_fs::{DiskFileSystem, FileContent, FileSystem, FileSystemPath},
+        tasks::{RcStr, Value, ValueToString, Vc},
+        tasks_fs::{DiskFileSystem, File, FileContent, FileSystem, FileSystemPath},
     },
     turbopack::{
         core::{
+            asset::AssetContent,
             context::{AssetContext, ProcessResult},
             file_source::FileSource,
-            reference_type::{EntryReferenceSubType, ReferenceType},
+            module::Module,
+            reference_type::{EntryReferenceSubType, InnerAssets, ReferenceType},
             resolve::node::node_cjs_resolve_options,
+            virtual_source::VirtualSource,
             PROJECT_FILESYSTEM_NAME,
         },
         ecmascript::typescript::resolve::{read_from_tsconfigs, read_tsconfigs, tsconfig},
@@ -123,6 +128,135 @@ pub async fn commands_metadata_to_entry_requests(
     Ok(entry_requests.cell())
 }
 
+#[turbo_tasks::function]
+pub async fn tasks_metadata_to_entry_requests(
+    tasks_metadata: Vc<TasksMetadata>,
+) -> Result<Vc<EntryRequests>> {
+    let mut entry_requests: EntryRequests = EntryRequests { 0: vec![] };
+
+    for metadata in tasks_metadata.await? {
+        entry_requests
+            .0
+            .push(EntryRequest::Relative(metadata.file_path.realpath().await?.to_string()).cell());
+    }
+
+    Ok(entry_requests.cell())
+}
+
+/// The `_middleware` chain of every command and event, keyed by the path of
+/// its file
+pub async fn middleware_by_entry(
+    events_metadata: Vc<EventsMetadata>,
+    commands_metadata: &[Vc<CommandsMetadata>],
+) -> Result<HashMap<RcStr, Vec<Vc<FileSystemPath>>>> {
+    let mut handlers: Vec<_> = events_metadata
+        .await?
+        .iter()
+        .map(|metadata| (metadata.file_path, metadata.middleware.clone()))
+        .collect();
+    for commands_metadata in commands_metadata {
+        handlers.extend(
+            commands_metadata
+                .await?
+                .iter()
+                .map(|metadata| (metadata.file_path, metadata.middleware.clone())),
+        );
+    }
+
+    let mut middleware = HashMap::new();
+    for (file_path, chain) in handlers {
+        if !chain.is_empty() {
+            let path = file_path.to_string().await?.clone_value();
+            middleware.insert(path, chain);
+        }
+    }
+
+    Ok(middleware)
+}
+
+/// Bundles the `_middleware` files with the entry of a handler. Each middleware
+/// is called with the context of the handler and a `next` function: calling
+/// `next(extra?)` continues with the context merged with `extra`, returning
+/// without calling it short-circuits with the returned value as the response.
+/// Named exports, like the `data` of a command, are those of the handler.
+#[turbo_tasks::function]
+pub async fn middleware_entry(
+    entry: Vc<Box<dyn Module>>,
+    middleware: Vec<Vc<FileSystemPath>>,
+    asset_context: Vc<Box<dyn AssetContext>>,
+) -> Result<Vc<Box<dyn Module>>> {
+    if middleware.is_empty() {
+        return Ok(entry);
+    }
+
+    let code = middleware_code(middleware.len())?;
+    let mut inner_assets = vec![("HANDLER".into(), entry)];
+    for (index, path) in middleware.into_iter().enumerate() {
+        let module = process_path_to_asset(path, asset_context).module();
+        inner_assets.push((format!("MIDDLEWARE_{index}").into(), module));
+    }
+
+    // Kept next to the handler, so it is bundled into the same place
+    let handler_path = entry.ident().path();
+    let file_stem = handler_path
+        .await?
+        .file_stem()
+        .unwrap_or("index")
+        .to_string();
+    let path = handler_path
+        .parent()
+        .join(format!("{file_stem}.middleware.js").into());
+
+    Ok(asset_context
+        .process(
+            Vc::upcast(VirtualSource::new(
+                path,
+                AssetContent::file(File::from(code).into()),
+            )),
+            Value::new(ReferenceType::Internal(Vc::<InnerAssets>::cell(
+                inner_assets.into_iter().collect(),
+            ))),
+        )
+        .module())
+}
+
+/// The module that runs the chain, the handler is imported from `HANDLER` and
+/// the middleware from `MIDDLEWARE_<index>`, outermost first
+fn middleware_code(count: usize) -> Result<String> {
+    let mut imports = String::new();
+    let mut chain = vec![];
+    for index in 0..count {
+        writeln!(
+            imports,
+            "import middleware{index} from 'MIDDLEWARE_{index}';"
+        )?;
+        chain.push(format!("middleware{index}"));
+    }
+
+    Ok(format!(
+        r#"{imports}import handler from 'HANDLER';
+
+export * from 'HANDLER';
+
+const chain = [{chain}];
+
+function run(index, context) {{
+  if (index === chain.length) {{
+    return handler(context);
+  }}
+  return chain[index](context, (extra) =>
+    run(index + 1, extra ? {{ ...context, ...extra }} : context),
+  );
+}}
+
+export default function (context) {{
+  return run(0, context);
+}}
+"#,
+        chain = chain.join(", ")
+    ))
+}
+
 #[turbo_tasks::function]
 fn process_path_to_asset(
     path: Vc<FileSystemPath>,
@@ -179,3 +313,113 @@ pub async fn get_existing_tsconfigs(
 
     Ok(Vc::cell(None))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process::Command};
+
+    use serde_json::{json, Value as JsonValue};
+
+    use super::middleware_code;
+
+    /// Runs the chain with Node, using the middleware and handler in `files`
+    /// instead of the bundled modules. `script` is run after the chain is
+    /// imported as `run`, and prints its result as JSON.
+    fn run_chain(files: &[(&str, &str)], script: &str) -> JsonValue {
+        let dir = tempfile::tempdir().unwrap();
+        let mut code = middleware_code(files.len() - 1)
+            .unwrap()
+            .replace("'HANDLER'", "'./handler.mjs'");
+        for index in 0..files.len() - 1 {
+            code = code.replace(
+                &format!("'MIDDLEWARE_{index}'"),
+                &format!("'./middleware{index}.mjs'"),
+            );
+        }
+
+        for (name, content) in files {
+            fs::write(dir.path().join(name), content).unwrap();
+        }
+        fs::write(dir.path().join("chain.mjs"), code).unwrap();
+        fs::write(
+            dir.path().join("main.mjs"),
+            format!("import run from './chain.mjs';\n{script}"),
+        )
+        .unwrap();
+
+        let output = Command::new("node")
+            .arg(dir.path().join("main.mjs"))
+            .output()
+            .expect("node should be installed");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice(&output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_middleware_code_imports_in_order() {
+        let code = middleware_code(2).unwrap();
+        assert!(code.starts_with(
+            "import middleware0 from 'MIDDLEWARE_0';\nimport middleware1 from \
+             'MIDDLEWARE_1';\nimport handler from 'HANDLER';"
+        ));
+        assert!(code.contains("const chain = [middleware0, middleware1];"));
+        // Named exports are those of the handler
+        assert!(code.contains("export * from 'HANDLER';"));
+    }
+
+    #[test]
+    fn test_middleware_chain() {
+        let files = [
+            (
+                "middleware0.mjs",
+                "export default (context, next) => {
+                   context.calls.push('outer');
+                   return context.stop ? 'stopped' : next({ outer: true });
+                 };",
+            ),
+            (
+                "middleware1.mjs",
+                "export default (context, next) => {
+                   context.calls.push('inner');
+                   return next();
+                 };",
+            ),
+            (
+                "handler.mjs",
+                "export const data = 'data';
+                 export default (context) => {
+                   context.calls.push('handler');
+                   return { outer: context.outer ?? false };
+                 };",
+            ),
+        ];
+
+        let result = run_chain(
+            &files,
+            "const calls = [];
+             const response = await run({ calls });
+             const stoppedCalls = [];
+             const stopped = await run({ calls: stoppedCalls, stop: true });
+             const { data } = await import('./chain.mjs');
+             console.log(JSON.stringify({ calls, response, stoppedCalls, stopped, data }));",
+        );
+
+        assert_eq!(
+            result,
+            json!({
+                // The outermost middleware runs first
+                "calls": ["outer", "inner", "handler"],
+                // `next(extra)` merges `extra` into the context
+                "response": { "outer": true },
+                // Returning without calling `next` skips the rest of the chain
+                "stoppedCalls": ["outer"],
+                "stopped": "stopped",
+                "data": "data",
+            })
+        );
+    }
+}
@@ -2,6 +2,7 @@ use std::sync::{Arc, OnceLock};
 
 // TODO use biome_deserialize
 use anyhow::{Context, Ok, Result};
+use discord_api::{gateway::GatewayIntents, id::GuildId, rest::http::HttpOptions};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -40,6 +41,136 @@ pub struct ClientOptions {
     intents: Option<u32>,
 }
 
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestOptions {
+    /// Proxy used for every REST request, e.g. `http://localhost:8080`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<RcStr>,
+    /// Timeout of a whole REST request in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Timeout of the connect phase of a REST request in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<RcStr>,
+}
+
+/// Which entities are kept in memory from the gateway events, the cache is
+/// disabled unless this is set
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guilds: Option<CachePolicyOrBool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<CachePolicyOrBool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<CachePolicyOrBool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<CachePolicyOrBool>,
+    /// Where the cached entities are shared with other shards and processes,
+    /// defaults to the memory of this process
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<CacheBackendOptions>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CacheBackendOptions {
+    Memory,
+    /// Any server that speaks the Redis protocol
+    Redis {
+        /// e.g. `redis://:password@localhost:6379/0`
+        url: RcStr,
+        /// Prefix of the keys the entities are stored in, defaults to
+        /// `fujinoki`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        prefix: Option<RcStr>,
+    },
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CachePolicyOrBool {
+    /// Whether the entity is cached, without a limit
+    Bool(bool),
+    Policy(CachePolicy),
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachePolicy {
+    /// Whether the entity is cached, defaults to `true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Maximum number of entities that are kept, the least recently updated
+    /// ones are evicted first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<usize>,
+    /// Maximum number of bytes the entities take, measured by the size of
+    /// their JSON. The least recently updated ones are evicted first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_memory: Option<usize>,
+}
+
+impl CachePolicyOrBool {
+    /// The policy of an entity, entities are cached without limits by default
+    pub fn resolve(policy: Option<&Self>) -> CachePolicy {
+        match policy {
+            None => CachePolicy::default(),
+            Some(CachePolicyOrBool::Bool(enabled)) => CachePolicy {
+                enabled: Some(*enabled),
+                ..Default::default()
+            },
+            Some(CachePolicyOrBool::Policy(policy)) => policy.clone(),
+        }
+    }
+}
+
+/// Commands invoked by messages like `!ping`, the commands are the files of
+/// `message-commands/` and those of `commands/` with a `prefix` export
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefixCommandsOptions {
+    /// e.g. `"!"` or `["!", "?"]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<PrefixOrPrefixes>,
+    /// Whether mentioning the bot can be used as the prefix, e.g. `@bot ping`,
+    /// defaults to `true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mention: Option<bool>,
+}
+
+#[turbo_tasks::value(shared, serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PrefixOrPrefixes {
+    Prefix(RcStr),
+    Prefixes(Vec<RcStr>),
+}
+
+impl PrefixCommandsOptions {
+    pub fn prefixes(&self) -> Vec<RcStr> {
+        match &self.prefix {
+            Some(PrefixOrPrefixes::Prefix(prefix)) => vec![prefix.clone()],
+            Some(PrefixOrPrefixes::Prefixes(prefixes)) => prefixes.clone(),
+            None => vec![],
+        }
+    }
+
+    pub fn mention(&self) -> bool {
+        self.mention.unwrap_or(true)
+    }
+}
+
 #[turbo_tasks::value]
 pub struct OptionalClientOptions {
     pub(crate) inner: Option<Vc<ClientOptions>>,
@@ -96,13 +227,28 @@ impl OptionalClientOptions {
     }
 }
 
+#[turbo_tasks::value(transparent)]
+pub struct OptionPrefixCommandsOptions(Option<PrefixCommandsOptions>);
+
 #[turbo_tasks::value(serialization = "custom", eq = "manual")]
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FujinokiConfig {
     pub client: Option<ClientOptions>,
+    pub rest: Option<RestOptions>,
     pub env: Option<IndexMap<String, JsonValue>>,
     pub file_extensions: Option<Vec<RcStr>>,
+    /// Guilds that commands are registered to during development instead of
+    /// globally, guild commands are updated instantly
+    pub dev_guild_ids: Option<Vec<GuildId>>,
+    /// Opt-in cache of the guilds, channels, roles and members received from
+    /// the gateway, which handlers can read from `client`
+    pub cache: Option<CacheOptions>,
+    /// Opt-in message commands, which require the `MESSAGE_CONTENT` intent
+    pub prefix_commands: Option<PrefixCommandsOptions>,
+    /// Sent when a command is on cooldown, `{remaining}` is replaced with the
+    /// seconds left
+    pub cooldown_message: Option<RcStr>,
     #[serde(skip)]
     pub(crate) node_env: RcStr,
 }
@@ -170,6 +316,67 @@ impl FujinokiConfig {
         Ok(options.cell())
     }
 
+    #[turbo_tasks::function]
+    pub async fn http_options(self: Vc<Self>) -> Result<Vc<HttpOptions>> {
+        let this = self.await?;
+        let rest = this.rest.clone().unwrap_or_default();
+        // A missing token is only reported by the commands that need one
+        let token = this
+            .client
+            .as_ref()
+            .and_then(|client| match client.token.as_ref()? {
+                TokenOptionsOrString::String(token) => Some(token.clone()),
+                TokenOptionsOrString::Options(map) => map
+                    .get(this.node_env.as_str())
+                    .map(|token| token.as_str().into()),
+            });
+
+        Ok(HttpOptions {
+            user_agent: rest.user_agent,
+            token,
+            timeout: rest.timeout,
+            connect_timeout: rest.connect_timeout,
+            proxy: rest.proxy,
+        }
+        .cell())
+    }
+
+    /// The prefix commands options, emits an issue when the intents don't allow
+    /// reading the content of messages
+    #[turbo_tasks::function]
+    pub async fn prefix_commands(self: Vc<Self>) -> Result<Vc<OptionPrefixCommandsOptions>> {
+        let Some(options) = self.await?.prefix_commands.clone() else {
+            return Ok(Vc::cell(None));
+        };
+
+        let intents = GatewayIntents::from_bits_truncate(*self.client().intents().await?);
+        let mut missing = vec![];
+        if !intents.contains(GatewayIntents::MESSAGE_CONTENT) {
+            missing.push("MESSAGE_CONTENT");
+        }
+        if !intents.intersects(GatewayIntents::GUILD_MESSAGES | GatewayIntents::DIRECT_MESSAGES) {
+            missing.push("GUILD_MESSAGES or DIRECT_MESSAGES");
+        }
+        if let (false, Some(path)) = (missing.is_empty(), CONFIG_FILE.get()) {
+            ConfigIssue {
+                path: **path,
+                description: StyledString::Text(
+                    format!(
+                        "`prefixCommands` requires the {} intent in `client.intents`, without it \
+                         the content of messages is empty",
+                        missing.join(" and ")
+                    )
+                    .into(),
+                )
+                .cell(),
+            }
+            .cell()
+            .emit();
+        }
+
+        Ok(Vc::cell(Some(options)))
+    }
+
     #[turbo_tasks::function]
     pub async fn env(self: Vc<Self>) -> Result<Vc<EnvMap>> {
         // The value expected for env is Record<String, String>, but config itself
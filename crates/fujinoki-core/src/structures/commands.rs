@@ -11,7 +11,7 @@ use turbopack_binding::{
     turbopack::core::issue::{IssueExt, IssueSeverity, StyledString},
 };
 
-use super::{get_directory_tree, issue::DirectoryTreeIssue, DirectoryTree};
+use super::{get_directory_tree, issue::DirectoryTreeIssue, middleware_chain, DirectoryTree};
 use crate::config::FujinokiConfig;
 
 #[turbo_tasks::value(shared)]
@@ -19,6 +19,8 @@ use crate::config::FujinokiConfig;
 pub struct CommandMetadata {
     pub name: RcStr,
     pub file_path: Vc<FileSystemPath>,
+    /// The `_middleware` files that run before the command, outermost first
+    pub middleware: Vec<Vc<FileSystemPath>>,
 }
 
 #[turbo_tasks::value(shared, transparent)]
@@ -49,8 +51,21 @@ impl OptionCommandsDir {
 /// Finds and returns the [DirectoryTree] of the _ directory if existing.
 #[turbo_tasks::function]
 pub async fn find_commands_dir(project_path: Vc<FileSystemPath>) -> Result<Vc<OptionCommandsDir>> {
-    let app = project_path.join("commands".to_string().into());
-    let src_app = project_path.join("src/commands".to_string().into());
+    find_dir(project_path, "commands").await
+}
+
+/// Finds the `message-commands` directory, whose commands are only invoked by
+/// messages with a prefix
+#[turbo_tasks::function]
+pub async fn find_message_commands_dir(
+    project_path: Vc<FileSystemPath>,
+) -> Result<Vc<OptionCommandsDir>> {
+    find_dir(project_path, "message-commands").await
+}
+
+async fn find_dir(project_path: Vc<FileSystemPath>, name: &str) -> Result<Vc<OptionCommandsDir>> {
+    let app = project_path.join(name.to_string().into());
+    let src_app = project_path.join(format!("src/{name}").into());
     let dir = if *app.get_type().await? == FileSystemEntryType::Directory {
         app
     } else if *src_app.get_type().await? == FileSystemEntryType::Directory {
@@ -89,6 +104,7 @@ async fn directory_tree_to_commands_metadata_internal(
     let DirectoryTree {
         subdirectories,
         components,
+        middleware,
     } = &*directory_tree.await?;
 
     for (name, subdirectory) in subdirectories {
@@ -100,13 +116,15 @@ async fn directory_tree_to_commands_metadata_internal(
                         commands.0.push(CommandMetadata {
                             name: name.clone(),
                             file_path: metadata.file_path,
+                            middleware: middleware_chain(*middleware, &metadata.middleware),
                         });
                     }
                     _ => {
                         if is_group_route(&name) {
                             commands.0.push(CommandMetadata {
                                 name: metadata.name.clone(),
-                                ..*metadata
+                                file_path: metadata.file_path,
+                                middleware: middleware_chain(*middleware, &metadata.middleware),
                             });
                         }
                     }
@@ -120,6 +138,7 @@ async fn directory_tree_to_commands_metadata_internal(
             commands.0.push(CommandMetadata {
                 name: name.clone(),
                 file_path: file.clone(),
+                middleware: middleware_chain(*middleware, &[]),
             });
         } else {
             DirectoryTreeIssue {
@@ -1,6 +1,5 @@
-use std::collections::BTreeMap;
-
 use anyhow::Result;
+use discord_api::gateway::event::DISPATCH_EVENT_NAMES;
 use tracing::Instrument;
 use turbopack_binding::{
     turbo::{
@@ -11,14 +10,63 @@ use turbopack_binding::{
     turbopack::core::issue::{IssueExt, IssueSeverity, StyledString},
 };
 
-use super::{get_directory_tree, issue::DirectoryTreeIssue, DirectoryTree};
+use super::{
+    get_directory_tree, issue::DirectoryTreeIssue, middleware_chain, DirectoryTree, ONCE_MODIFIER,
+};
 use crate::config::FujinokiConfig;
 
+/// Events emitted by fujinoki itself rather than Discord, they are handled in
+/// `events/` like any other event, e.g. `events/fujinoki_disconnect.ts`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    /// The shard received READY, with `{ shard }`
+    ShardReady,
+    /// A dropped session was resumed
+    Resumed,
+    /// The connection is being re-established, with `{ reason }`
+    Reconnecting,
+    /// The connection was closed for good, with `{ code, reason }`
+    Disconnect,
+    /// The gateway acknowledged a heartbeat, with `{ latency }` in milliseconds
+    Heartbeat,
+    /// The connection to the gateway failed, with `{ message }`
+    Error,
+}
+
+impl LifecycleEvent {
+    pub const ALL: [LifecycleEvent; 6] = [
+        LifecycleEvent::ShardReady,
+        LifecycleEvent::Resumed,
+        LifecycleEvent::Reconnecting,
+        LifecycleEvent::Disconnect,
+        LifecycleEvent::Heartbeat,
+        LifecycleEvent::Error,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LifecycleEvent::ShardReady => "FUJINOKI_SHARD_READY",
+            LifecycleEvent::Resumed => "FUJINOKI_RESUMED",
+            LifecycleEvent::Reconnecting => "FUJINOKI_RECONNECTING",
+            LifecycleEvent::Disconnect => "FUJINOKI_DISCONNECT",
+            LifecycleEvent::Heartbeat => "FUJINOKI_HEARTBEAT",
+            LifecycleEvent::Error => "FUJINOKI_ERROR",
+        }
+    }
+}
+
+/// A handler of an event, an event can have many handlers, e.g.
+/// `events/message_create/logging.ts` and `events/message_create/automod.ts`
 #[turbo_tasks::value]
 #[derive(Clone, Debug)]
 pub struct EventMetadata {
     pub name: RcStr,
     pub file_path: Vc<FileSystemPath>,
+    /// The `_middleware` files that run before the handler, outermost first
+    pub middleware: Vec<Vc<FileSystemPath>>,
+    /// Whether the handler only runs on the first occurrence of the event, e.g.
+    /// `events/ready.once.ts`
+    pub once: bool,
 }
 
 #[turbo_tasks::value(transparent)]
@@ -73,9 +121,26 @@ pub async fn directory_tree_to_events_metadata(
         let dir = dir.to_string().await?.to_string();
         tracing::info_span!("events directory tree into data", name = dir)
     };
-    directory_tree_to_events_metadata_internal(dir, directory_tree)
+    let events = directory_tree_to_events_metadata_internal(dir, directory_tree)
         .instrument(span)
-        .await
+        .await?;
+
+    // Names are only complete at the root, e.g. `message/create.ts`
+    for event in events.await?.iter() {
+        if !is_event_name(&event.name) {
+            unknown_event_issue(dir, &event.name, event.file_path).await?;
+        }
+    }
+
+    Ok(events)
+}
+
+#[turbo_tasks::function]
+async fn directory_tree_to_partial_events_metadata(
+    dir: Vc<FileSystemPath>,
+    directory_tree: Vc<DirectoryTree>,
+) -> Result<Vc<EventsMetadata>> {
+    directory_tree_to_events_metadata_internal(dir, directory_tree).await
 }
 
 async fn directory_tree_to_events_metadata_internal(
@@ -89,16 +154,30 @@ async fn directory_tree_to_events_metadata_internal(
     let DirectoryTree {
         subdirectories,
         components,
+        middleware,
     } = &*directory_tree.await?;
 
     for (name, subdirectory) in subdirectories {
-        let subevents = directory_tree_to_events_metadata(dir.clone(), *subdirectory).await?;
+        let subevents =
+            directory_tree_to_partial_events_metadata(dir.clone(), *subdirectory).await?;
+        // Every file in a directory named after an event is a handler of it
+        let is_event_dir = is_event_name(&name.to_uppercase());
         for event in subevents {
             match event.name.as_str() {
-                "event" => {
+                _ if is_event_dir => {
+                    events.0.push(EventMetadata {
+                        name: name.to_uppercase().into(),
+                        file_path: event.file_path,
+                        middleware: middleware_chain(*middleware, &event.middleware),
+                        once: event.once,
+                    });
+                }
+                "EVENT" => {
                     events.0.push(EventMetadata {
                         name: name.to_string().to_uppercase().into(),
                         file_path: event.file_path,
+                        middleware: middleware_chain(*middleware, &event.middleware),
+                        once: event.once,
                     });
                 }
                 _ => {
@@ -109,6 +188,8 @@ async fn directory_tree_to_events_metadata_internal(
                     events.0.push(EventMetadata {
                         name: name.into(),
                         file_path: event.file_path,
+                        middleware: middleware_chain(*middleware, &event.middleware),
+                        once: event.once,
                     });
                 }
             }
@@ -116,30 +197,18 @@ async fn directory_tree_to_events_metadata_internal(
     }
 
     for (name, file_path) in &components.await?.0 {
+        let (name, once) = match name.strip_suffix(ONCE_MODIFIER) {
+            Some(name) => (name, true),
+            None => (name.as_str(), false),
+        };
         events.0.push(EventMetadata {
-            name: name.to_string().to_uppercase().into(),
+            name: name.to_uppercase().into(),
             file_path: *file_path,
+            middleware: middleware_chain(*middleware, &[]),
+            once,
         });
     }
 
-    let mut seen_conflicts: BTreeMap<RcStr, EventMetadata> = Default::default();
-    for (name, event) in events.0.iter().enumerate() {
-        for (other_name, other_event) in events.0.iter().enumerate() {
-            if name != other_name
-                && event.name == other_event.name
-                && !seen_conflicts.contains_key(&event.name)
-            {
-                conflict_issue(
-                    dir.clone(),
-                    event.clone().name.into(),
-                    &event.file_path.realpath().await?.to_string(),
-                    &other_event.file_path.realpath().await?.to_string(),
-                );
-                seen_conflicts.insert(event.name.clone(), event.clone());
-            }
-        }
-    }
-
     Ok(events.cell())
 }
 
@@ -147,15 +216,61 @@ fn is_group_route(name: &str) -> bool {
     name.starts_with('(') && name.ends_with(')')
 }
 
-fn conflict_issue(dir: Vc<FileSystemPath>, e: String, value_a: &String, value_b: &String) {
+fn is_event_name(name: &str) -> bool {
+    known_event_names().any(|known| known == name)
+}
+
+fn known_event_names() -> impl Iterator<Item = &'static str> {
+    DISPATCH_EVENT_NAMES
+        .iter()
+        .copied()
+        .chain(LifecycleEvent::ALL.map(LifecycleEvent::name))
+}
+
+/// A typo in the name of an event would otherwise never fire
+async fn unknown_event_issue(
+    dir: Vc<FileSystemPath>,
+    name: &str,
+    file_path: Vc<FileSystemPath>,
+) -> Result<()> {
+    let suggestion = known_event_names()
+        .map(|known| (edit_distance(name, known), known))
+        .filter(|(distance, _)| *distance <= (name.len() / 4).max(2))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| format!(", did you mean {known}?"))
+        .unwrap_or_default();
+
     DirectoryTreeIssue {
         dir,
         message: StyledString::Text(
-            format!("Conflicting events for {}: {value_a} and {value_b}", e,).into(),
+            format!(
+                "Unknown event {name} at {}{suggestion}",
+                file_path.realpath().await?.to_string()
+            )
+            .into(),
         )
         .cell(),
-        severity: IssueSeverity::Fatal.cell(),
+        severity: IssueSeverity::Warning.cell(),
     }
     .cell()
     .emit();
+
+    Ok(())
+}
+
+/// The Levenshtein distance between two names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
 }
@@ -11,6 +11,15 @@ use turbopack_binding::turbo::{
 pub mod commands;
 pub mod events;
 pub mod issue;
+pub mod tasks;
+
+/// The stem of the files whose default export runs before every handler in its
+/// directory and the directories beneath it
+pub const MIDDLEWARE_FILE: &str = "_middleware";
+
+/// Marks a handler that only runs on the first occurrence of its event, e.g.
+/// `ready.once.ts`
+pub const ONCE_MODIFIER: &str = ".once";
 
 /// A final route in the `x` directory.
 #[turbo_tasks::value(shared)]
@@ -34,6 +43,8 @@ pub struct DirectoryTree {
     /// key is e.g. "dashboard", "(dashboard)", "@slot"
     pub subdirectories: BTreeMap<RcStr, Vc<DirectoryTree>>,
     pub components: Vc<Components>,
+    /// The `_middleware` file of this directory
+    pub middleware: Option<Vc<FileSystemPath>>,
 }
 
 #[turbo_tasks::value_impl]
@@ -45,6 +56,7 @@ impl DirectoryTree {
         let DirectoryTree {
             subdirectories,
             components,
+            ..
         } = &*self.await?;
         let mut children = Vec::new();
         children.push(components.routes_changed());
@@ -80,6 +92,7 @@ async fn get_directory_tree_internal(
         return Ok(DirectoryTree {
             subdirectories: Default::default(),
             components: Components::default().cell(),
+            middleware: None,
         }
         .cell());
     };
@@ -87,6 +100,7 @@ async fn get_directory_tree_internal(
 
     let mut subdirectories = BTreeMap::new();
     let mut components = Components::default();
+    let mut middleware = None;
 
     for (basename, entry) in entries {
         match *entry {
@@ -96,9 +110,15 @@ async fn get_directory_tree_internal(
                 if basename.ends_with(".d.ts") {
                     continue;
                 }
-                if let Some((stem, ext)) = basename.split_once('.') {
+                if let Some((stem, ext)) = split_extension(basename) {
                     if file_extensions_value.iter().any(|e| e == ext) {
-                        components.0.insert(stem.to_string().into(), file);
+                        if stem == MIDDLEWARE_FILE {
+                            middleware = Some(file);
+                        } else if !stem.starts_with('_') {
+                            // Like directories, other files starting with an underscore are
+                            // private
+                            components.0.insert(stem.to_string().into(), file);
+                        }
                     }
                 }
             }
@@ -118,6 +138,113 @@ async fn get_directory_tree_internal(
     Ok(DirectoryTree {
         subdirectories,
         components: components.cell(),
+        middleware,
     }
     .cell())
 }
+
+/// Splits a file name into its stem and extension, the `.once` modifier is kept
+/// in the stem, e.g. `ready.once.ts` is `ready.once` and `ts`
+fn split_extension(basename: &str) -> Option<(&str, &str)> {
+    let (stem, ext) = basename.split_once('.')?;
+    match ext
+        .strip_prefix(&ONCE_MODIFIER[1..])
+        .and_then(|ext| ext.strip_prefix('.'))
+    {
+        Some(ext) => Some((&basename[..stem.len() + ONCE_MODIFIER.len()], ext)),
+        None => Some((stem, ext)),
+    }
+}
+
+/// Prepends the middleware of a directory to the chain of a handler beneath
+/// it, so the chain runs from the outermost directory inwards
+pub(crate) fn middleware_chain(
+    middleware: Option<Vc<FileSystemPath>>,
+    inner: &[Vc<FileSystemPath>],
+) -> Vec<Vc<FileSystemPath>> {
+    middleware
+        .into_iter()
+        .chain(inner.iter().copied())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, future::Future, sync::Once};
+
+    use anyhow::Result;
+    use turbopack_binding::turbo::{
+        tasks::{RcStr, TurboTasks, Vc},
+        tasks_fs::{DiskFileSystem, FileSystem},
+        tasks_memory::MemoryBackend,
+    };
+
+    use super::{get_directory_tree, middleware_chain, Components};
+
+    async fn run(test: impl Future<Output = Result<()>> + Send + 'static) {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+
+        let turbo_tasks = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        turbo_tasks.run_once(test).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_middleware_chain() {
+        run(async {
+            let root = DiskFileSystem::new("test".into(), "/".into(), vec![]).root();
+            let [outer, inner, handler] = ["a", "a/b", "a/b/c"].map(|path| root.join(path.into()));
+
+            // The middleware of the directory runs before the ones beneath it
+            assert_eq!(
+                middleware_chain(Some(outer), &[inner, handler]),
+                vec![outer, inner, handler]
+            );
+            assert_eq!(middleware_chain(None, &[inner]), vec![inner]);
+            assert!(middleware_chain(None, &[]).is_empty());
+            Ok(())
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_directory_tree_excludes_private_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in [
+            "_middleware.ts",
+            "ping.ts",
+            "_helpers.ts",
+            "types.d.ts",
+            "_private/hidden.ts",
+            "admin/_middleware.ts",
+            "admin/ban.ts",
+        ] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "export default () => {};").unwrap();
+        }
+        let root: RcStr = dir.path().to_str().unwrap().into();
+
+        run(async move {
+            let root = DiskFileSystem::new("test".into(), root, vec![]).root();
+            let tree = get_directory_tree(root, Vc::cell(vec!["ts".into()])).await?;
+
+            let keys =
+                |components: &Components| components.0.keys().cloned().collect::<Vec<RcStr>>();
+            // `_middleware` is the middleware of the directory, other files starting
+            // with an underscore are private
+            assert_eq!(keys(&*tree.components.await?), vec![RcStr::from("ping")]);
+            assert!(tree.middleware.is_some());
+            assert_eq!(
+                tree.subdirectories.keys().cloned().collect::<Vec<_>>(),
+                vec![RcStr::from("admin")]
+            );
+
+            let admin = tree.subdirectories[&RcStr::from("admin")].await?;
+            assert_eq!(keys(&*admin.components.await?), vec![RcStr::from("ban")]);
+            assert!(admin.middleware.is_some());
+            Ok(())
+        })
+        .await;
+    }
+}
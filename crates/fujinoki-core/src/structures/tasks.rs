@@ -0,0 +1,112 @@
+use anyhow::Result;
+use tracing::Instrument;
+use turbopack_binding::turbo::{
+    tasks as turbo_tasks,
+    tasks::{Completion, RcStr, ValueToString, Vc},
+    tasks_fs::{FileSystemEntryType, FileSystemPath},
+};
+
+use super::{get_directory_tree, DirectoryTree};
+use crate::config::FujinokiConfig;
+
+/// A file of `tasks/`, which exports a `schedule` and a default function that
+/// runs on it
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+pub struct TaskMetadata {
+    /// The path of the file relative to `tasks/` without its extension, e.g.
+    /// `stats/post`
+    pub name: RcStr,
+    pub file_path: Vc<FileSystemPath>,
+}
+
+#[turbo_tasks::value(shared, transparent)]
+#[derive(Clone, Debug, Default)]
+pub struct TasksMetadata(pub Vec<TaskMetadata>);
+
+#[turbo_tasks::value(transparent)]
+#[derive(Default)]
+pub struct OptionTasksDir(Option<Vc<FileSystemPath>>);
+
+#[turbo_tasks::value_impl]
+impl OptionTasksDir {
+    /// Returns a completion that changes when any route in the whole tree
+    /// changes.
+    #[turbo_tasks::function]
+    pub async fn routes_changed(
+        self: Vc<Self>,
+        config: Vc<FujinokiConfig>,
+    ) -> Result<Vc<Completion>> {
+        if let Some(dir) = *self.await? {
+            let directory_tree = get_directory_tree(dir, config.file_extensions());
+            directory_tree.routes_changed().await?;
+        }
+        Ok(Completion::new())
+    }
+}
+
+/// Finds the `tasks` directory, next to `commands` and `events`
+#[turbo_tasks::function]
+pub async fn find_tasks_dir(project_path: Vc<FileSystemPath>) -> Result<Vc<OptionTasksDir>> {
+    let app = project_path.join("tasks".to_string().into());
+    let src_app = project_path.join("src/tasks".to_string().into());
+    let dir = if *app.get_type().await? == FileSystemEntryType::Directory {
+        app
+    } else if *src_app.get_type().await? == FileSystemEntryType::Directory {
+        src_app
+    } else {
+        return Ok(Vc::cell(None));
+    }
+    .resolve()
+    .await?;
+
+    Ok(Vc::cell(Some(dir)))
+}
+
+#[turbo_tasks::function]
+pub async fn directory_tree_to_tasks_metadata(
+    dir: Vc<FileSystemPath>,
+    directory_tree: Vc<DirectoryTree>,
+) -> Result<Vc<TasksMetadata>> {
+    let span = {
+        let dir = dir.to_string().await?.to_string();
+        tracing::info_span!("tasks directory tree into data", name = dir)
+    };
+    directory_tree_to_tasks_metadata_internal(dir, directory_tree)
+        .instrument(span)
+        .await
+}
+
+async fn directory_tree_to_tasks_metadata_internal(
+    dir: Vc<FileSystemPath>,
+    directory_tree: Vc<DirectoryTree>,
+) -> Result<Vc<TasksMetadata>> {
+    directory_tree.routes_changed().await?;
+
+    let mut tasks = TasksMetadata::default();
+
+    let DirectoryTree {
+        subdirectories,
+        components,
+        ..
+    } = &*directory_tree.await?;
+
+    for (name, subdirectory) in subdirectories {
+        let subtasks = directory_tree_to_tasks_metadata(dir, *subdirectory).await?;
+        for task in subtasks {
+            tasks.0.push(TaskMetadata {
+                name: format!("{name}/{}", task.name).into(),
+                file_path: task.file_path,
+            });
+        }
+    }
+
+    for (name, file_path) in &components.await?.0 {
+        tasks.0.push(TaskMetadata {
+            name: name.clone(),
+            file_path: *file_path,
+        });
+    }
+
+    Ok(tasks.cell())
+}
@@ -2,7 +2,9 @@ use std::{future::Future, pin::Pin, sync::Arc};
 
 use anyhow::{Context, Result};
 use fujinoki_core::config::FujinokiConfig;
-use fujinoki_websocket::{connect_to_gateway, SourceProvider, Websocket, WebsocketStream};
+use fujinoki_websocket::{
+    connect_to_gateway, scheduler::SchedulerLog, SourceProvider, Websocket, WebsocketStream,
+};
 use turbopack_binding::{
     turbo::tasks::{self as turbo_tasks, trace::TraceRawVcs, TurboTasksApi, Vc},
     turbopack::{core::issue::IssueReporter, trace_utils::exit::ExitHandler},
@@ -41,8 +43,10 @@ impl DevServerBuilder {
         get_issue_reporter: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync>,
         exit_handler: Option<Arc<ExitHandler>>,
         config: Vc<FujinokiConfig>,
+        scheduler_log: SchedulerLog,
     ) -> DevServer {
-        let websocket = Websocket::new(turbo_tasks.clone(), config, get_issue_reporter);
+        let websocket = Websocket::new(turbo_tasks.clone(), config, get_issue_reporter)
+            .scheduler_log(scheduler_log);
         let server = websocket.serve(source_provider, exit_handler, self.gateway);
 
         DevServer {
@@ -0,0 +1,85 @@
+//! Cooldowns of commands invoked over HTTP, which are shared with the gateway
+
+use anyhow::Result;
+use discord_api::interactions::Interaction;
+use fujinoki_websocket::discord::{
+    cooldown::{enforce_cooldown, CooldownTarget},
+    response::{command_return_to_response, CommandResponse},
+};
+use hyper::{header::CONTENT_TYPE, Body, Response};
+use serde_json::json;
+use turbopack_binding::{
+    turbo::tasks::{RcStr, Vc},
+    turbopack::core::issue::{handle_issues, IssueReporter, IssueSeverity},
+};
+
+use crate::InteractionCommands;
+
+/// The response to an application command that is on cooldown, `None` when
+/// the command can run
+pub(crate) async fn cooldown_response(
+    commands: &InteractionCommands,
+    body: &[u8],
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+) -> Result<Option<Response<Body>>> {
+    let Ok(interaction) = serde_json::from_slice::<Interaction>(body) else {
+        return Ok(None);
+    };
+    // Components and modals don't invoke a command
+    let Some(command_name) = interaction.command_name().map(RcStr::from) else {
+        return Ok(None);
+    };
+    let Some(command) =
+        *(commands.get_command)(interaction.application_id, command_name.clone()).await?
+    else {
+        return Ok(None);
+    };
+    let command = command.await?;
+    let cooldown = match command.definition {
+        Some(definition) => definition.await?.cooldown.clone(),
+        None => None,
+    };
+    let Some(cooldown) = cooldown else {
+        return Ok(None);
+    };
+
+    let message = enforce_cooldown(
+        &*commands.cooldowns,
+        commands.config,
+        &command_name,
+        &cooldown,
+        CooldownTarget::of_interaction(&interaction),
+        command.file_path,
+    )
+    .await?;
+    let response = match message {
+        Some(message) => {
+            command_return_to_response(
+                json!({ "content": message, "ephemeral": true }),
+                command.file_path,
+            )
+            .await?
+        }
+        None => None,
+    };
+
+    // An invalid cooldown is reported as an issue
+    let responded: Vc<bool> = Vc::cell(response.is_some());
+    handle_issues(
+        responded,
+        issue_reporter,
+        IssueSeverity::Fatal.cell(),
+        None,
+        Some("enforce cooldown"),
+    )
+    .await?;
+
+    let Some(CommandResponse { response, .. }) = response else {
+        return Ok(None);
+    };
+    Ok(Some(
+        Response::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_vec(&response)?))?,
+    ))
+}
@@ -5,6 +5,7 @@
 #![feature(str_split_remainder)]
 #![feature(arbitrary_self_types)]
 
+mod cooldown;
 // mod http;
 // pub mod introspect;
 mod invalidation;
@@ -19,6 +20,9 @@ use std::{
 };
 
 use anyhow::{Context, Result};
+use discord_api::id::ApplicationId;
+use fujinoki_core::config::FujinokiConfig;
+use fujinoki_websocket::discord::{cooldown::CooldownStore, dispatch::OptionCommandEntry};
 use hyper::{
     server::{conn::AddrIncoming, Builder},
     service::{make_service_fn, service_fn},
@@ -30,7 +34,7 @@ use tokio::task::JoinHandle;
 use tracing::{event, info_span, Instrument, Level, Span};
 use turbopack_binding::{
     turbo::tasks::{
-        self as turbo_tasks, run_once_with_reason, trace::TraceRawVcs, util::FormatDuration,
+        self as turbo_tasks, run_once_with_reason, trace::TraceRawVcs, util::FormatDuration, RcStr,
         TurboTasksApi, Vc,
     },
     turbopack::core::{error::PrettyPrintError, issue::IssueReporter},
@@ -46,6 +50,15 @@ pub struct InteractionsServerBuilder {
     server: Builder<AddrIncoming>,
 }
 
+/// The commands that interactions invoke, so their cooldowns are enforced like
+/// over the gateway
+pub struct InteractionCommands {
+    pub config: Vc<FujinokiConfig>,
+    /// Shared with the gateway, so a command is on cooldown for both
+    pub cooldowns: Arc<dyn CooldownStore>,
+    pub get_command: Arc<dyn Fn(ApplicationId, RcStr) -> Vc<OptionCommandEntry> + Send + Sync>,
+}
+
 #[derive(TraceRawVcs)]
 pub struct InteractionsServer {
     #[turbo_tasks(trace_ignore)]
@@ -95,6 +108,7 @@ impl InteractionsServerBuilder {
         turbo_tasks: Arc<dyn TurboTasksApi>,
         // TODO source_provider: impl SourceProvider + Sync,
         get_issue_reporter: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync>,
+        commands: Option<Arc<InteractionCommands>>,
     ) -> InteractionsServer {
         let ongoing_side_effects = Arc::new(Mutex::new(VecDeque::<
             Arc<tokio::sync::Mutex<Option<JoinHandle<Result<()>>>>>,
@@ -104,6 +118,7 @@ impl InteractionsServerBuilder {
             // TODO let source_provider = source_provider.clone();
             let get_issue_reporter = get_issue_reporter.clone();
             let ongoing_side_effects = ongoing_side_effects.clone();
+            let commands = commands.clone();
             async move {
                 let handler = move |request: Request<hyper::Body>| {
                     let request_span = info_span!(parent: None, "request", name = ?request.uri());
@@ -111,6 +126,7 @@ impl InteractionsServerBuilder {
                     let tt = tt.clone();
                     let get_issue_reporter = get_issue_reporter.clone();
                     let ongoing_side_effects = ongoing_side_effects.clone();
+                    let commands = commands.clone();
                     // TODO let source_provider = source_provider.clone();
                     let future = async move {
                         event!(parent: Span::current(), Level::DEBUG, "request start");
@@ -150,7 +166,17 @@ impl InteractionsServerBuilder {
                         //     uri: request.uri().clone(),
                         // };
                         run_once_with_reason(tt.clone(), reason, async move {
-                            // let issue_reporter = get_issue_reporter();
+                            let issue_reporter = get_issue_reporter();
+
+                            if let Some(commands) = &commands {
+                                let body = hyper::body::to_bytes(request.into_body()).await?;
+                                if let Some(response) =
+                                    cooldown::cooldown_response(commands, &body, issue_reporter)
+                                        .await?
+                                {
+                                    return Ok(response);
+                                }
+                            }
 
                             // if hyper_tungstenite::is_upgrade_request(&
                             // request) {
@@ -0,0 +1,244 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use anyhow::{Context, Result};
+use discord_api::id::{ChannelId, GuildId, RoleId, UserId};
+use fujinoki_core::config::CacheBackendOptions;
+use futures::future::BoxFuture;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::Mutex;
+
+use super::redis::RedisBackend;
+
+/// The kinds of entities that are cached, each is stored on its own in a
+/// backend
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    Guild,
+    Channel,
+    Role,
+    Member,
+}
+
+impl EntityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityKind::Guild => "guilds",
+            EntityKind::Channel => "channels",
+            EntityKind::Role => "roles",
+            EntityKind::Member => "members",
+        }
+    }
+}
+
+impl fmt::Display for EntityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How an entity is keyed in a backend, roles and members are prefixed by the
+/// id of their guild, e.g. `"<guild id>:<user id>"`
+pub trait CacheKey {
+    fn cache_key(&self) -> String;
+}
+
+impl CacheKey for GuildId {
+    fn cache_key(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl CacheKey for ChannelId {
+    fn cache_key(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl CacheKey for (GuildId, RoleId) {
+    fn cache_key(&self) -> String {
+        format!("{}:{}", self.0, self.1)
+    }
+}
+
+impl CacheKey for (GuildId, UserId) {
+    fn cache_key(&self) -> String {
+        format!("{}:{}", self.0, self.1)
+    }
+}
+
+/// A change of the in-memory cache that is written to the backend
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CacheChange {
+    Set {
+        kind: EntityKind,
+        key: String,
+        value: Vec<u8>,
+    },
+    Delete {
+        kind: EntityKind,
+        key: String,
+    },
+}
+
+/// A store the cached entities are written to, so they can be shared between
+/// shards and processes. Entities are stored as JSON serialized `discord-api`
+/// models.
+pub trait CacheBackend: Send + Sync {
+    fn get<'a>(&'a self, kind: EntityKind, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>>>;
+
+    fn set<'a>(
+        &'a self,
+        kind: EntityKind,
+        key: &'a str,
+        value: Vec<u8>,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    fn delete<'a>(&'a self, kind: EntityKind, key: &'a str) -> BoxFuture<'a, Result<()>>;
+
+    /// Every entity of a kind, in no particular order
+    fn entries(&self, kind: EntityKind) -> BoxFuture<'_, Result<Vec<(String, Vec<u8>)>>>;
+
+    /// The number of entities of a kind
+    fn count(&self, kind: EntityKind) -> BoxFuture<'_, Result<usize>>;
+
+    /// Removes every entity of a kind, including the ones written by other
+    /// processes
+    fn clear(&self, kind: EntityKind) -> BoxFuture<'_, Result<()>>;
+
+    /// Writes the changes in order
+    fn apply(&self, changes: Vec<CacheChange>) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            for change in changes {
+                match change {
+                    CacheChange::Set { kind, key, value } => self.set(kind, &key, value).await?,
+                    CacheChange::Delete { kind, key } => self.delete(kind, &key).await?,
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Reads an entity from the backend
+pub async fn get_entity<T: DeserializeOwned>(
+    backend: &dyn CacheBackend,
+    kind: EntityKind,
+    key: &impl CacheKey,
+) -> Result<Option<T>> {
+    let key = key.cache_key();
+    let Some(value) = backend.get(kind, &key).await? else {
+        return Ok(None);
+    };
+
+    serde_json::from_slice(&value)
+        .map(Some)
+        .with_context(|| format!("Unable to deserialize cached {kind} `{key}`"))
+}
+
+/// Writes an entity to the backend
+pub async fn set_entity<T: Serialize>(
+    backend: &dyn CacheBackend,
+    kind: EntityKind,
+    key: &impl CacheKey,
+    entity: &T,
+) -> Result<()> {
+    let key = key.cache_key();
+    let value = serde_json::to_vec(entity)
+        .with_context(|| format!("Unable to serialize {kind} `{key}`"))?;
+
+    backend.set(kind, &key, value).await
+}
+
+/// Connects to the backend of the config, the in-memory cache is used as is
+/// when no external backend is configured
+pub async fn connect_backend(
+    options: Option<&CacheBackendOptions>,
+) -> Result<Option<Arc<dyn CacheBackend>>> {
+    match options {
+        None | Some(CacheBackendOptions::Memory) => Ok(None),
+        Some(CacheBackendOptions::Redis { url, prefix }) => {
+            let backend = RedisBackend::connect(url, prefix.as_deref()).await?;
+            Ok(Some(Arc::new(backend)))
+        }
+    }
+}
+
+/// A backend that keeps the entities in the memory of this process
+#[derive(Default)]
+pub struct MemoryBackend {
+    entities: Mutex<HashMap<EntityKind, HashMap<String, Vec<u8>>>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CacheBackend for MemoryBackend {
+    fn get<'a>(&'a self, kind: EntityKind, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>>> {
+        Box::pin(async move {
+            let entities = self.entities.lock().await;
+            Ok(entities
+                .get(&kind)
+                .and_then(|entities| entities.get(key))
+                .cloned())
+        })
+    }
+
+    fn set<'a>(
+        &'a self,
+        kind: EntityKind,
+        key: &'a str,
+        value: Vec<u8>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut entities = self.entities.lock().await;
+            entities
+                .entry(kind)
+                .or_default()
+                .insert(key.to_string(), value);
+            Ok(())
+        })
+    }
+
+    fn delete<'a>(&'a self, kind: EntityKind, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut entities = self.entities.lock().await;
+            if let Some(entities) = entities.get_mut(&kind) {
+                entities.remove(key);
+            }
+            Ok(())
+        })
+    }
+
+    fn entries(&self, kind: EntityKind) -> BoxFuture<'_, Result<Vec<(String, Vec<u8>)>>> {
+        Box::pin(async move {
+            let entities = self.entities.lock().await;
+            Ok(entities
+                .get(&kind)
+                .map(|entities| {
+                    entities
+                        .iter()
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect()
+                })
+                .unwrap_or_default())
+        })
+    }
+
+    fn count(&self, kind: EntityKind) -> BoxFuture<'_, Result<usize>> {
+        Box::pin(async move {
+            let entities = self.entities.lock().await;
+            Ok(entities.get(&kind).map_or(0, |entities| entities.len()))
+        })
+    }
+
+    fn clear(&self, kind: EntityKind) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.entities.lock().await.remove(&kind);
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,938 @@
+//! An opt-in cache of the entities received from the gateway, so handlers can
+//! look up guilds, channels, roles and members without calling the REST API.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::Hash,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use discord_api::{
+    channel::Channel,
+    gateway::{event::GuildMemberUpdateEvent, DispatchEvent},
+    guild::{Guild, GuildMember, Role},
+    id::{ChannelId, GuildId, RoleId, UserId},
+};
+use fujinoki_core::config::{CacheOptions, CachePolicyOrBool};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Map, Value as JsonValue};
+
+use self::backend::{get_entity, CacheBackend, CacheChange, CacheKey, EntityKind};
+
+pub mod backend;
+pub mod redis;
+
+/// An entity with the size of its JSON
+struct Entry<V> {
+    value: V,
+    size: usize,
+    /// The position of the entity in `EntityMap::order`
+    updated: u64,
+    guild_id: Option<GuildId>,
+}
+
+/// Entities of one kind, ordered by when they were last updated
+pub struct EntityMap<K, V> {
+    kind: EntityKind,
+    enabled: bool,
+    max_size: Option<usize>,
+    max_memory: Option<usize>,
+    entries: HashMap<K, Entry<V>>,
+    /// The keys by when their entity was last updated, the least recently
+    /// updated one first
+    order: BTreeMap<u64, K>,
+    /// Increases with every update
+    tick: u64,
+    /// The keys of the entities of each guild, see
+    /// [`EntityMap::grouped_by_guild`]
+    guilds: HashMap<GuildId, HashSet<K>>,
+    guild_of: fn(&K, &V) -> Option<GuildId>,
+    /// The size of every entity together
+    memory: usize,
+    /// Only recorded when the entities are written to a backend
+    changes: Option<Vec<CacheChange>>,
+}
+
+impl<K: Hash + Eq + Clone + CacheKey, V: Serialize> EntityMap<K, V> {
+    pub fn new(kind: EntityKind, policy: Option<&CachePolicyOrBool>, record_changes: bool) -> Self {
+        let policy = CachePolicyOrBool::resolve(policy);
+
+        Self {
+            kind,
+            enabled: policy.enabled.unwrap_or(true),
+            max_size: policy.max_size,
+            max_memory: policy.max_memory,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            guilds: HashMap::new(),
+            guild_of: |_, _| None,
+            memory: 0,
+            changes: record_changes.then(Vec::new),
+        }
+    }
+
+    /// Indexes the entities by the guild `guild_of` returns, so the entities
+    /// of a guild can be read and removed without going through every entity
+    pub fn grouped_by_guild(mut self, guild_of: fn(&K, &V) -> Option<GuildId>) -> Self {
+        self.guild_of = guild_of;
+        self
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|entry| &entry.value)
+    }
+
+    /// The guild of the entity must not be changed, it is only indexed when it
+    /// is inserted
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.entries.get_mut(key).map(|entry| &mut entry.value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of bytes the entities take as JSON
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+
+    /// The entities, the least recently updated one first
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.order
+            .values()
+            .map(|key| (key, &self.entries[key].value))
+    }
+
+    /// The keys of the entities of a guild, in no particular order
+    pub fn guild_keys(&self, guild_id: GuildId) -> impl Iterator<Item = &K> {
+        self.guilds.get(&guild_id).into_iter().flatten()
+    }
+
+    /// The entities of a guild, in no particular order
+    pub fn guild_values(&self, guild_id: GuildId) -> impl Iterator<Item = &V> {
+        self.guild_keys(guild_id)
+            .map(move |key| &self.entries[key].value)
+    }
+
+    /// Inserts or replaces the entity, evicting the least recently updated
+    /// entities when a limit is reached. Evicted entities are only removed from
+    /// memory, they are kept in the backend.
+    pub fn insert(&mut self, key: K, value: V) {
+        if !self.enabled || self.max_size == Some(0) {
+            return;
+        }
+
+        // The models always serialize
+        let json = serde_json::to_vec(&value).unwrap_or_default();
+        let size = json.len();
+        if let Some(changes) = &mut self.changes {
+            changes.push(CacheChange::Set {
+                kind: self.kind,
+                key: key.cache_key(),
+                value: json,
+            });
+        }
+
+        if self.max_memory.is_some_and(|max_memory| size > max_memory) {
+            // The entity would evict every other one and still not fit
+            self.take_entry(&key);
+            return;
+        }
+
+        // The entity is now the most recently updated one
+        self.take_entry(&key);
+        let guild_id = (self.guild_of)(&key, &value);
+        if let Some(guild_id) = guild_id {
+            self.guilds.entry(guild_id).or_default().insert(key.clone());
+        }
+        self.tick += 1;
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                size,
+                updated: self.tick,
+                guild_id,
+            },
+        );
+        self.memory += size;
+
+        while self
+            .max_size
+            .is_some_and(|max_size| self.entries.len() > max_size)
+            || self
+                .max_memory
+                .is_some_and(|max_memory| self.memory > max_memory)
+        {
+            let Some((_, key)) = self.order.first_key_value() else {
+                break;
+            };
+            let key = key.clone();
+            self.take_entry(&key);
+        }
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let value = self.take_entry(key)?;
+        self.record_delete(key);
+        Some(value)
+    }
+
+    /// Removes the entities of a guild
+    pub fn remove_guild(&mut self, guild_id: GuildId) {
+        for key in self.guilds.remove(&guild_id).unwrap_or_default() {
+            self.remove(&key);
+        }
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&K, &mut V) -> bool) {
+        let removed: Vec<K> = self
+            .entries
+            .iter_mut()
+            .filter_map(|(key, entry)| (!keep(key, &mut entry.value)).then(|| key.clone()))
+            .collect();
+        for key in removed {
+            self.remove(&key);
+        }
+    }
+
+    /// Removes every entity, the backend only loses the ones of this cache
+    pub fn clear(&mut self) {
+        self.retain(|_, _| false);
+    }
+
+    /// Removes the entity from the map and its indexes
+    fn take_entry(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.memory -= entry.size;
+        self.order.remove(&entry.updated);
+        if let Some(guild_id) = entry.guild_id {
+            if let Some(keys) = self.guilds.get_mut(&guild_id) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.guilds.remove(&guild_id);
+                }
+            }
+        }
+        Some(entry.value)
+    }
+
+    fn record_delete(&mut self, key: &K) {
+        if let Some(changes) = &mut self.changes {
+            changes.push(CacheChange::Delete {
+                kind: self.kind,
+                key: key.cache_key(),
+            });
+        }
+    }
+
+    fn take_changes(&mut self) -> Vec<CacheChange> {
+        self.changes
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+}
+
+/// The guilds, channels (including threads), roles and members the bot has
+/// received. Guilds are stored without their roles, channels and members, those
+/// are kept in their own maps so each can be limited on its own.
+///
+/// With a `backend`, every change is also written to it, see
+/// [`EntityCache::take_changes`].
+pub struct EntityCache {
+    pub guilds: EntityMap<GuildId, Guild>,
+    pub channels: EntityMap<ChannelId, Channel>,
+    pub roles: EntityMap<(GuildId, RoleId), Role>,
+    pub members: EntityMap<(GuildId, UserId), GuildMember>,
+    pub backend: Option<Arc<dyn CacheBackend>>,
+}
+
+impl EntityCache {
+    pub fn new(options: &CacheOptions, backend: Option<Arc<dyn CacheBackend>>) -> Self {
+        let record_changes = backend.is_some();
+
+        Self {
+            guilds: EntityMap::new(EntityKind::Guild, options.guilds.as_ref(), record_changes),
+            channels: EntityMap::new(
+                EntityKind::Channel,
+                options.channels.as_ref(),
+                record_changes,
+            )
+            .grouped_by_guild(|_, channel| channel.guild_id),
+            roles: EntityMap::new(EntityKind::Role, options.roles.as_ref(), record_changes)
+                .grouped_by_guild(|(guild_id, _), _| Some(*guild_id)),
+            members: EntityMap::new(EntityKind::Member, options.members.as_ref(), record_changes)
+                .grouped_by_guild(|(guild_id, _), _| Some(*guild_id)),
+            backend,
+        }
+    }
+
+    /// The changes since they were last taken, which have to be written to the
+    /// backend
+    pub fn take_changes(&mut self) -> Vec<CacheChange> {
+        let mut changes = self.guilds.take_changes();
+        changes.extend(self.channels.take_changes());
+        changes.extend(self.roles.take_changes());
+        changes.extend(self.members.take_changes());
+        changes
+    }
+
+    /// Applies a dispatched event to the cache
+    pub fn update(&mut self, event: &DispatchEvent) {
+        match event {
+            // A new session starts without any of the previous state
+            DispatchEvent::Ready(_) => self.clear(),
+            DispatchEvent::GuildCreate(guild) | DispatchEvent::GuildUpdate(guild) => {
+                self.insert_guild(guild.as_ref().clone())
+            }
+            DispatchEvent::GuildDelete(guild) => {
+                if guild.unavailable {
+                    // The guild is only unavailable during an outage, its entities are sent
+                    // again with GUILD_CREATE
+                    if let Some(cached) = self.guilds.get_mut(&guild.id) {
+                        cached.unavailable = Some(true);
+                    }
+                } else {
+                    self.remove_guild(guild.id);
+                }
+            }
+            DispatchEvent::GuildMemberAdd(event) => {
+                self.insert_member(event.guild_id, event.member.clone())
+            }
+            DispatchEvent::GuildMemberUpdate(event) => self.update_member(event),
+            DispatchEvent::GuildMemberRemove(event) => {
+                self.members.remove(&(event.guild_id, event.user.id));
+            }
+            DispatchEvent::GuildRoleCreate(event) | DispatchEvent::GuildRoleUpdate(event) => {
+                self.roles
+                    .insert((event.guild_id, event.role.id), event.role.clone());
+            }
+            DispatchEvent::GuildRoleDelete(event) => {
+                self.roles.remove(&(event.guild_id, event.role_id));
+            }
+            DispatchEvent::ChannelCreate(channel)
+            | DispatchEvent::ChannelUpdate(channel)
+            | DispatchEvent::ThreadCreate(channel)
+            | DispatchEvent::ThreadUpdate(channel) => {
+                self.channels.insert(channel.id, channel.as_ref().clone());
+            }
+            DispatchEvent::ChannelDelete(channel) | DispatchEvent::ThreadDelete(channel) => {
+                self.channels.remove(&channel.id);
+            }
+            DispatchEvent::MessageCreate(event) => {
+                // The member of a message doesn't include the user, it is the author
+                if let (Some(guild_id), Some(member), Some(author)) =
+                    (event.guild_id, &event.member, &event.message.author)
+                {
+                    let mut member = member.clone();
+                    member.user = Some(author.clone());
+                    self.insert_member(guild_id, member);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.guilds.clear();
+        self.channels.clear();
+        self.roles.clear();
+        self.members.clear();
+    }
+
+    fn insert_guild(&mut self, mut guild: Guild) {
+        let guild_id = guild.id;
+
+        // GUILD_UPDATE sends every role, so roles that are missing were deleted
+        let deleted: Vec<_> = self
+            .roles
+            .guild_keys(guild_id)
+            .filter(|(_, role_id)| !guild.roles.iter().any(|role| role.id == *role_id))
+            .copied()
+            .collect();
+        for key in deleted {
+            self.roles.remove(&key);
+        }
+        for role in std::mem::take(&mut guild.roles) {
+            self.roles.insert((guild_id, role.id), role);
+        }
+
+        let channels = guild.channels.take().into_iter().flatten();
+        let threads = guild.threads.take().into_iter().flatten();
+        for mut channel in channels.chain(threads) {
+            // The channels of GUILD_CREATE don't include the guild id
+            channel.guild_id = Some(guild_id);
+            self.channels.insert(channel.id, channel);
+        }
+
+        for member in guild.members.take().into_iter().flatten() {
+            self.insert_member(guild_id, member);
+        }
+
+        // Only GUILD_CREATE includes these, keep the ones we already have
+        if let Some(cached) = self.guilds.get(&guild_id) {
+            guild.joined_at = guild.joined_at.or_else(|| cached.joined_at.clone());
+            guild.large = guild.large.or(cached.large);
+            guild.member_count = guild.member_count.or(cached.member_count);
+        }
+        guild.voice_states = None;
+        self.guilds.insert(guild_id, guild);
+    }
+
+    fn remove_guild(&mut self, guild_id: GuildId) {
+        self.guilds.remove(&guild_id);
+        self.channels.remove_guild(guild_id);
+        self.roles.remove_guild(guild_id);
+        self.members.remove_guild(guild_id);
+    }
+
+    fn insert_member(&mut self, guild_id: GuildId, member: GuildMember) {
+        let Some(user_id) = member.user.as_ref().map(|user| user.id) else {
+            return;
+        };
+
+        self.members.insert((guild_id, user_id), member);
+    }
+
+    fn update_member(&mut self, event: &GuildMemberUpdateEvent) {
+        let key = (event.guild_id, event.user.id);
+        let Some(mut member) = self.members.remove(&key) else {
+            // Members that aren't cached yet are only added when the event is complete
+            if let Some(joined_at) = &event.joined_at {
+                self.members.insert(
+                    key,
+                    GuildMember {
+                        user: Some(event.user.clone()),
+                        nick: event.nick.clone(),
+                        avatar: event.avatar.clone(),
+                        roles: event.roles.clone(),
+                        joined_at: joined_at.clone(),
+                        premium_since: event.premium_since.clone(),
+                        deaf: event.deaf.unwrap_or_default(),
+                        mute: event.mute.unwrap_or_default(),
+                        flags: event.flags.unwrap_or_default(),
+                        pending: event.pending,
+                        permissions: None,
+                        communication_disabled_until: event.communication_disabled_until.clone(),
+                    },
+                );
+            }
+            return;
+        };
+
+        member.user = Some(event.user.clone());
+        member.nick = event.nick.clone();
+        member.avatar = event.avatar.clone();
+        member.roles = event.roles.clone();
+        member.premium_since = event.premium_since.clone();
+        member.pending = event.pending;
+        member.communication_disabled_until = event.communication_disabled_until.clone();
+        if let Some(joined_at) = &event.joined_at {
+            member.joined_at = joined_at.clone();
+        }
+        if let Some(deaf) = event.deaf {
+            member.deaf = deaf;
+        }
+        if let Some(mute) = event.mute {
+            member.mute = mute;
+        }
+        if let Some(flags) = event.flags {
+            member.flags = flags;
+        }
+        self.members.insert(key, member);
+    }
+
+    pub fn guild(&self, guild_id: GuildId) -> Option<&Guild> {
+        self.guilds.get(&guild_id)
+    }
+
+    pub fn channel(&self, channel_id: ChannelId) -> Option<&Channel> {
+        self.channels.get(&channel_id)
+    }
+
+    pub fn role(&self, guild_id: GuildId, role_id: RoleId) -> Option<&Role> {
+        self.roles.get(&(guild_id, role_id))
+    }
+
+    pub fn member(&self, guild_id: GuildId, user_id: UserId) -> Option<&GuildMember> {
+        self.members.get(&(guild_id, user_id))
+    }
+
+    /// The roles of a guild, which aren't kept on the guild itself
+    pub fn guild_roles(&self, guild_id: GuildId) -> impl Iterator<Item = &Role> {
+        self.roles.guild_values(guild_id)
+    }
+
+    /// The cached entities an event refers to, as they are passed to handlers
+    /// in `client`. Only these are serialized, not the whole cache. They are
+    /// keyed by id, roles and members are grouped by the id of their guild,
+    /// e.g. `client.members[guildId][userId]`.
+    /// Any other entity can be read from a shared backend with the `Cache` of
+    /// `@fujinoki/discord-api`.
+    pub fn to_json(&self, scope: &EntityScope) -> JsonValue {
+        let mut guilds = Map::new();
+        let mut channels = Map::new();
+        let mut roles = Map::new();
+        let mut members = Map::new();
+
+        if let Some(guild_id) = scope.guild_id {
+            if let Some(guild) = self.guild(guild_id) {
+                guilds.insert(guild_id.to_string(), json!(guild));
+            }
+
+            let guild_roles: Map<_, _> = self
+                .guild_roles(guild_id)
+                .map(|role| (role.id.to_string(), json!(role)))
+                .collect();
+            if !guild_roles.is_empty() {
+                roles.insert(guild_id.to_string(), JsonValue::Object(guild_roles));
+            }
+
+            if let Some(user_id) = scope.user_id {
+                if let Some(member) = self.member(guild_id, user_id) {
+                    let mut guild_members = Map::new();
+                    guild_members.insert(user_id.to_string(), json!(member));
+                    members.insert(guild_id.to_string(), JsonValue::Object(guild_members));
+                }
+            }
+        }
+
+        if let Some(channel_id) = scope.channel_id {
+            if let Some(channel) = self.channel(channel_id) {
+                channels.insert(channel_id.to_string(), json!(channel));
+
+                // The parent of a thread, e.g. to read the permissions of the thread
+                if let Some(parent) = channel.parent_id.and_then(|id| self.channel(id)) {
+                    channels.insert(parent.id.to_string(), json!(parent));
+                }
+            }
+        }
+
+        json!({
+            "guilds": guilds,
+            "channels": channels,
+            "roles": roles,
+            "members": members,
+        })
+    }
+}
+
+/// Reads the entities of the scope that aren't in `entities` from the backend,
+/// e.g. those of guilds of other shards or that were evicted from memory.
+/// `entities` is shaped like [`EntityCache::to_json`].
+pub async fn read_through(
+    backend: &dyn CacheBackend,
+    scope: &EntityScope,
+    entities: &mut JsonValue,
+) -> Result<()> {
+    if let Some(guild_id) = scope.guild_id {
+        let id = guild_id.to_string();
+        if entities["guilds"][&id].is_null() {
+            if let Some(guild) = get_entity::<Guild>(backend, EntityKind::Guild, &guild_id).await? {
+                entities["guilds"][&id] = json!(guild);
+            }
+        }
+
+        if let Some(user_id) = scope.user_id {
+            let user = user_id.to_string();
+            if entities["members"][&id][&user].is_null() {
+                let key = (guild_id, user_id);
+                if let Some(member) =
+                    get_entity::<GuildMember>(backend, EntityKind::Member, &key).await?
+                {
+                    // The roles of the member, e.g. to compute its permissions
+                    for role_id in &member.roles {
+                        let role = role_id.to_string();
+                        if entities["roles"][&id][&role].is_null() {
+                            let key = (guild_id, *role_id);
+                            if let Some(role_entity) =
+                                get_entity::<Role>(backend, EntityKind::Role, &key).await?
+                            {
+                                entities["roles"][&id][&role] = json!(role_entity);
+                            }
+                        }
+                    }
+                    entities["members"][&id][&user] = json!(member);
+                }
+            }
+        }
+    }
+
+    if let Some(channel_id) = scope.channel_id {
+        let id = channel_id.to_string();
+        if entities["channels"][&id].is_null() {
+            if let Some(channel) =
+                get_entity::<Channel>(backend, EntityKind::Channel, &channel_id).await?
+            {
+                entities["channels"][&id] = json!(channel);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The guild, channel and user an event happened in, which select the cached
+/// entities that are passed to its handlers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EntityScope {
+    pub guild_id: Option<GuildId>,
+    pub channel_id: Option<ChannelId>,
+    pub user_id: Option<UserId>,
+}
+
+impl EntityScope {
+    pub fn of_event(event: &DispatchEvent) -> Self {
+        match event {
+            DispatchEvent::Ready(_) | DispatchEvent::Resumed => Self::default(),
+            DispatchEvent::GuildCreate(guild) | DispatchEvent::GuildUpdate(guild) => Self {
+                guild_id: Some(guild.id),
+                ..Default::default()
+            },
+            DispatchEvent::GuildDelete(guild) => Self {
+                guild_id: Some(guild.id),
+                ..Default::default()
+            },
+            DispatchEvent::GuildMemberAdd(event) => Self {
+                guild_id: Some(event.guild_id),
+                user_id: event.member.user.as_ref().map(|user| user.id),
+                ..Default::default()
+            },
+            DispatchEvent::GuildMemberUpdate(event) => Self {
+                guild_id: Some(event.guild_id),
+                user_id: Some(event.user.id),
+                ..Default::default()
+            },
+            DispatchEvent::GuildMemberRemove(event) => Self {
+                guild_id: Some(event.guild_id),
+                user_id: Some(event.user.id),
+                ..Default::default()
+            },
+            DispatchEvent::GuildRoleCreate(event) | DispatchEvent::GuildRoleUpdate(event) => Self {
+                guild_id: Some(event.guild_id),
+                ..Default::default()
+            },
+            DispatchEvent::GuildRoleDelete(event) => Self {
+                guild_id: Some(event.guild_id),
+                ..Default::default()
+            },
+            DispatchEvent::ChannelCreate(channel)
+            | DispatchEvent::ChannelUpdate(channel)
+            | DispatchEvent::ChannelDelete(channel)
+            | DispatchEvent::ThreadCreate(channel)
+            | DispatchEvent::ThreadUpdate(channel)
+            | DispatchEvent::ThreadDelete(channel) => Self {
+                guild_id: channel.guild_id,
+                channel_id: Some(channel.id),
+                ..Default::default()
+            },
+            DispatchEvent::MessageCreate(event) | DispatchEvent::MessageUpdate(event) => Self {
+                guild_id: event.guild_id,
+                channel_id: Some(event.message.channel_id),
+                user_id: event.message.author.as_ref().map(|author| author.id),
+            },
+            DispatchEvent::MessageDelete(event) => Self {
+                guild_id: event.guild_id,
+                channel_id: Some(event.channel_id),
+                ..Default::default()
+            },
+            DispatchEvent::MessageReactionAdd(event) => Self {
+                guild_id: event.guild_id,
+                channel_id: Some(event.channel_id),
+                user_id: Some(event.user_id),
+            },
+            DispatchEvent::MessageReactionRemove(event) => Self {
+                guild_id: event.guild_id,
+                channel_id: Some(event.channel_id),
+                user_id: Some(event.user_id),
+            },
+            DispatchEvent::InteractionCreate(interaction) => Self {
+                guild_id: interaction.guild_id,
+                channel_id: interaction.channel_id,
+                user_id: interaction
+                    .member
+                    .as_ref()
+                    .and_then(|member| member.user.as_ref())
+                    .or(interaction.user.as_ref())
+                    .map(|user| user.id),
+            },
+            DispatchEvent::VoiceStateUpdate(state) => Self {
+                guild_id: state.guild_id,
+                channel_id: state.channel_id,
+                user_id: Some(state.user_id),
+            },
+            // The events that aren't modelled yet mostly use the same names for their ids
+            DispatchEvent::Unknown { data, .. } => {
+                fn id<T: DeserializeOwned>(data: &JsonValue, name: &str) -> Option<T> {
+                    serde_json::from_value(data.get(name)?.clone()).ok()
+                }
+
+                Self {
+                    guild_id: id(data, "guild_id"),
+                    channel_id: id(data, "channel_id"),
+                    user_id: id(data, "user_id"),
+                }
+            }
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use discord_api::{
+        gateway::DispatchEvent,
+        id::{ChannelId, GuildId, RoleId, UserId},
+    };
+    use fujinoki_core::config::{CacheOptions, CachePolicy, CachePolicyOrBool};
+    use serde_json::json;
+
+    use super::{
+        backend::{CacheChange, EntityKind},
+        EntityCache, EntityMap, EntityScope,
+    };
+
+    const GUILD_ID: GuildId = GuildId(41771983423143937);
+    const USER_ID: UserId = UserId(80351110224678912);
+
+    fn guild_create() -> DispatchEvent {
+        let guild = serde_json::from_str(include_str!(
+            "../../../discord-api/fixtures/guild_create.json"
+        ))
+        .unwrap();
+
+        DispatchEvent::from_payload("GUILD_CREATE", guild).unwrap()
+    }
+
+    fn entity_map(policy: CachePolicy) -> EntityMap<GuildId, String> {
+        EntityMap::new(
+            EntityKind::Guild,
+            Some(&CachePolicyOrBool::Policy(policy)),
+            true,
+        )
+    }
+
+    #[test]
+    fn test_insert_evicts_least_recently_updated() {
+        let mut map = entity_map(CachePolicy {
+            max_size: Some(2),
+            ..Default::default()
+        });
+
+        map.insert(GuildId(1), "one".into());
+        map.insert(GuildId(2), "two".into());
+        // Updating an entity makes it the most recently updated one
+        map.insert(GuildId(1), "one".into());
+        map.insert(GuildId(3), "three".into());
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&GuildId(2)), None);
+        assert_eq!(
+            map.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![GuildId(1), GuildId(3)]
+        );
+        // The evicted entity is kept in the backend
+        assert!(!map
+            .take_changes()
+            .iter()
+            .any(|change| matches!(change, CacheChange::Delete { .. })));
+    }
+
+    #[test]
+    fn test_insert_evicts_over_max_memory() {
+        // Each value is 6 bytes of JSON, e.g. `"four"`
+        let mut map = entity_map(CachePolicy {
+            max_memory: Some(12),
+            ..Default::default()
+        });
+
+        map.insert(GuildId(1), "four".into());
+        map.insert(GuildId(2), "five".into());
+        assert_eq!(map.memory(), 12);
+
+        map.insert(GuildId(3), "nine".into());
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&GuildId(1)), None);
+        assert_eq!(map.memory(), 12);
+
+        // Entities that don't fit on their own aren't cached
+        map.insert(GuildId(4), "a value that is too large".into());
+        assert_eq!(map.get(&GuildId(4)), None);
+        assert_eq!(map.len(), 2);
+
+        map.remove(&GuildId(2));
+        assert_eq!(map.memory(), 6);
+
+        // Only the entities that were removed are deleted from the backend
+        let deleted: Vec<_> = map
+            .take_changes()
+            .into_iter()
+            .filter_map(|change| match change {
+                CacheChange::Delete { key, .. } => Some(key),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(deleted, vec!["2".to_string()]);
+    }
+
+    #[test]
+    fn test_disabled_entities_are_not_cached() {
+        let mut map = EntityMap::<GuildId, String>::new(
+            EntityKind::Guild,
+            Some(&CachePolicyOrBool::Bool(false)),
+            true,
+        );
+
+        map.insert(GuildId(1), "one".into());
+        assert!(map.is_empty());
+        assert!(map.take_changes().is_empty());
+    }
+
+    #[test]
+    fn test_guild_create() {
+        let mut cache = EntityCache::new(&CacheOptions::default(), None);
+        cache.update(&guild_create());
+
+        let guild = cache.guild(GUILD_ID).unwrap();
+        // The entities of the guild are kept in their own maps
+        assert!(guild.roles.is_empty());
+        assert!(guild.channels.is_none());
+        assert!(guild.members.is_none());
+
+        assert_eq!(cache.guild_roles(GUILD_ID).count(), 1);
+        assert!(cache.role(GUILD_ID, RoleId(41771983423143937)).is_some());
+        assert_eq!(cache.channels.len(), 3);
+        // The channels of GUILD_CREATE don't include the guild id
+        let thread = cache.channel(ChannelId(1016763190513786901)).unwrap();
+        assert_eq!(thread.guild_id, Some(GUILD_ID));
+        assert!(cache.member(GUILD_ID, USER_ID).is_some());
+    }
+
+    #[test]
+    fn test_guild_delete() {
+        let mut cache = EntityCache::new(&CacheOptions::default(), None);
+        cache.update(&guild_create());
+
+        // An outage keeps the entities, they are sent again with GUILD_CREATE
+        let unavailable = DispatchEvent::from_payload(
+            "GUILD_DELETE",
+            json!({ "id": GUILD_ID, "unavailable": true }),
+        )
+        .unwrap();
+        cache.update(&unavailable);
+        assert_eq!(cache.guild(GUILD_ID).unwrap().unavailable, Some(true));
+        assert_eq!(cache.channels.len(), 3);
+
+        let removed =
+            DispatchEvent::from_payload("GUILD_DELETE", json!({ "id": GUILD_ID })).unwrap();
+        cache.update(&removed);
+        assert!(cache.guilds.is_empty());
+        assert!(cache.channels.is_empty());
+        assert!(cache.roles.is_empty());
+        assert!(cache.members.is_empty());
+    }
+
+    #[test]
+    fn test_guild_update_removes_deleted_roles() {
+        let mut cache = EntityCache::new(&CacheOptions::default(), None);
+        cache.update(&guild_create());
+
+        let role = |guild_id: GuildId, id: &str| {
+            DispatchEvent::from_payload(
+                "GUILD_ROLE_CREATE",
+                json!({
+                    "guild_id": guild_id,
+                    "role": {
+                        "id": id,
+                        "name": "role",
+                        "color": 0,
+                        "hoist": false,
+                        "position": 1,
+                        "permissions": "0",
+                        "managed": false,
+                        "mentionable": false,
+                        "flags": 0,
+                    },
+                }),
+            )
+            .unwrap()
+        };
+        cache.update(&role(GUILD_ID, "1"));
+        cache.update(&role(GuildId(2), "2"));
+        assert_eq!(cache.guild_roles(GUILD_ID).count(), 2);
+
+        // The guild is sent again without the role
+        cache.update(&guild_create());
+        assert_eq!(cache.guild_roles(GUILD_ID).count(), 1);
+        assert!(cache.role(GUILD_ID, RoleId(1)).is_none());
+        // The roles of other guilds are kept
+        assert!(cache.role(GuildId(2), RoleId(2)).is_some());
+    }
+
+    #[test]
+    fn test_guild_member_update() {
+        let mut cache = EntityCache::new(&CacheOptions::default(), None);
+        cache.update(&guild_create());
+        let joined_at = cache.member(GUILD_ID, USER_ID).unwrap().joined_at.clone();
+
+        let update = DispatchEvent::from_payload(
+            "GUILD_MEMBER_UPDATE",
+            json!({
+                "guild_id": GUILD_ID,
+                "roles": ["41771983423143937"],
+                "user": { "id": USER_ID, "username": "fujinoki", "discriminator": "0" },
+                "nick": "fuji",
+            }),
+        )
+        .unwrap();
+        cache.update(&update);
+
+        let member = cache.member(GUILD_ID, USER_ID).unwrap();
+        assert_eq!(member.nick.as_deref(), Some("fuji"));
+        assert_eq!(member.roles, vec![RoleId(41771983423143937)]);
+        // Missing fields keep their cached value
+        assert_eq!(member.joined_at, joined_at);
+
+        // Members that aren't cached yet need the whole member
+        let partial = DispatchEvent::from_payload(
+            "GUILD_MEMBER_UPDATE",
+            json!({
+                "guild_id": GUILD_ID,
+                "roles": [],
+                "user": { "id": "1", "username": "other", "discriminator": "0" },
+            }),
+        )
+        .unwrap();
+        cache.update(&partial);
+        assert!(cache.member(GUILD_ID, UserId(1)).is_none());
+    }
+
+    #[test]
+    fn test_to_json_only_includes_the_scope() {
+        let mut cache = EntityCache::new(&CacheOptions::default(), None);
+        cache.update(&guild_create());
+
+        let client = cache.to_json(&EntityScope {
+            guild_id: Some(GUILD_ID),
+            channel_id: Some(ChannelId(1016763190513786901)),
+            user_id: Some(USER_ID),
+        });
+        assert!(client["guilds"][GUILD_ID.to_string()].is_object());
+        assert!(client["members"][GUILD_ID.to_string()][USER_ID.to_string()].is_object());
+        // The thread and its parent
+        assert_eq!(client["channels"].as_object().unwrap().len(), 2);
+
+        let client = cache.to_json(&EntityScope::default());
+        assert_eq!(
+            client,
+            json!({ "guilds": {}, "channels": {}, "roles": {}, "members": {} })
+        );
+    }
+}
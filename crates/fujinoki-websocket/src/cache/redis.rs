@@ -0,0 +1,320 @@
+//! A cache backend for Redis and servers that speak its protocol (RESP), such
+//! as Valkey, KeyDB or Dragonfly. Each kind of entity is stored in a hash named
+//! `<prefix>:<kind>`, keyed by the cache key of the entity.
+
+use anyhow::{bail, Context, Result};
+use futures::future::BoxFuture;
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufStream},
+    net::TcpStream,
+    sync::Mutex,
+};
+use url::Url;
+
+use super::backend::{CacheBackend, CacheChange, EntityKind};
+
+const DEFAULT_PREFIX: &str = "fujinoki";
+const DEFAULT_PORT: u16 = 6379;
+
+pub struct RedisBackend {
+    url: Url,
+    /// Discarded when a command fails, a new one is opened by the next command
+    connection: Mutex<Option<BufStream<TcpStream>>>,
+    prefix: String,
+}
+
+/// A reply of the server
+#[derive(Debug, PartialEq)]
+enum Reply {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<Reply>>),
+}
+
+impl RedisBackend {
+    /// Connects to a server with a url like
+    /// `redis://:password@localhost:6379/0`, the path selects the database
+    pub async fn connect(url: &str, prefix: Option<&str>) -> Result<Self> {
+        let url = Url::parse(url).with_context(|| format!("Invalid Redis url `{url}`"))?;
+        if url.scheme() != "redis" {
+            bail!(
+                "Unsupported Redis url scheme `{}`, expected `redis`",
+                url.scheme()
+            );
+        }
+
+        // Connects right away, so an invalid config is reported on startup
+        let connection = open(&url).await?;
+
+        Ok(Self {
+            url,
+            connection: Mutex::new(Some(connection)),
+            prefix: prefix.unwrap_or(DEFAULT_PREFIX).to_string(),
+        })
+    }
+
+    fn hash_key(&self, kind: EntityKind) -> String {
+        format!("{}:{}", self.prefix, kind)
+    }
+
+    async fn command(&self, args: &[&[u8]]) -> Result<Reply> {
+        let reply = self.send(&encode_command(args), 1).await?.remove(0);
+        match reply {
+            Reply::Error(message) => bail!("Redis replied with an error: {message}"),
+            reply => Ok(reply),
+        }
+    }
+
+    /// Sends the encoded commands and reads a reply for each of them. A
+    /// connection that fails is discarded, as its replies may be out of sync,
+    /// and the commands are sent once more on a new one, e.g. after the server
+    /// restarted.
+    async fn send(&self, commands: &[u8], count: usize) -> Result<Vec<Reply>> {
+        let mut connection = self.connection.lock().await;
+        let mut retry = true;
+
+        loop {
+            let mut stream = match connection.take() {
+                Some(stream) => stream,
+                None => open(&self.url).await?,
+            };
+
+            match exchange(&mut stream, commands, count).await {
+                Ok(replies) => {
+                    *connection = Some(stream);
+                    return Ok(replies);
+                }
+                Err(err) if !retry => return Err(err),
+                Err(_) => retry = false,
+            }
+        }
+    }
+}
+
+impl CacheBackend for RedisBackend {
+    fn get<'a>(&'a self, kind: EntityKind, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>>> {
+        Box::pin(async move {
+            let hash_key = self.hash_key(kind);
+            match self
+                .command(&[b"HGET", hash_key.as_bytes(), key.as_bytes()])
+                .await?
+            {
+                Reply::Bulk(value) => Ok(value),
+                reply => bail!("Unexpected reply to HGET: {reply:?}"),
+            }
+        })
+    }
+
+    fn set<'a>(
+        &'a self,
+        kind: EntityKind,
+        key: &'a str,
+        value: Vec<u8>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let hash_key = self.hash_key(kind);
+            self.command(&[b"HSET", hash_key.as_bytes(), key.as_bytes(), &value])
+                .await?;
+            Ok(())
+        })
+    }
+
+    fn delete<'a>(&'a self, kind: EntityKind, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let hash_key = self.hash_key(kind);
+            self.command(&[b"HDEL", hash_key.as_bytes(), key.as_bytes()])
+                .await?;
+            Ok(())
+        })
+    }
+
+    fn entries(&self, kind: EntityKind) -> BoxFuture<'_, Result<Vec<(String, Vec<u8>)>>> {
+        Box::pin(async move {
+            let hash_key = self.hash_key(kind);
+            let Reply::Array(Some(replies)) =
+                self.command(&[b"HGETALL", hash_key.as_bytes()]).await?
+            else {
+                return Ok(vec![]);
+            };
+
+            // The fields and values are alternating
+            let mut entries = Vec::with_capacity(replies.len() / 2);
+            let mut replies = replies.into_iter();
+            while let (Some(key), Some(value)) = (replies.next(), replies.next()) {
+                let (Reply::Bulk(Some(key)), Reply::Bulk(Some(value))) = (key, value) else {
+                    bail!("Unexpected reply to HGETALL");
+                };
+                entries.push((String::from_utf8(key)?, value));
+            }
+
+            Ok(entries)
+        })
+    }
+
+    fn count(&self, kind: EntityKind) -> BoxFuture<'_, Result<usize>> {
+        Box::pin(async move {
+            let hash_key = self.hash_key(kind);
+            match self.command(&[b"HLEN", hash_key.as_bytes()]).await? {
+                Reply::Integer(length) => Ok(usize::try_from(length)?),
+                reply => bail!("Unexpected reply to HLEN: {reply:?}"),
+            }
+        })
+    }
+
+    fn clear(&self, kind: EntityKind) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let hash_key = self.hash_key(kind);
+            self.command(&[b"DEL", hash_key.as_bytes()]).await?;
+            Ok(())
+        })
+    }
+
+    /// Pipelines the changes, so they only take a single round trip
+    fn apply(&self, changes: Vec<CacheChange>) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            if changes.is_empty() {
+                return Ok(());
+            }
+
+            let mut commands = Vec::new();
+            for change in &changes {
+                match change {
+                    CacheChange::Set { kind, key, value } => commands.extend(encode_command(&[
+                        b"HSET",
+                        self.hash_key(*kind).as_bytes(),
+                        key.as_bytes(),
+                        value,
+                    ])),
+                    CacheChange::Delete { kind, key } => commands.extend(encode_command(&[
+                        b"HDEL",
+                        self.hash_key(*kind).as_bytes(),
+                        key.as_bytes(),
+                    ])),
+                }
+            }
+
+            // Every reply is read, even after an error, to keep the connection in sync
+            let replies = self.send(&commands, changes.len()).await?;
+            let error = replies.into_iter().find_map(|reply| match reply {
+                Reply::Error(message) => Some(message),
+                _ => None,
+            });
+            if let Some(message) = error {
+                bail!("Redis replied with an error: {message}");
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Opens a connection, authenticating and selecting the database of the url
+async fn open(url: &Url) -> Result<BufStream<TcpStream>> {
+    let host = url.host_str().unwrap_or("127.0.0.1");
+    let port = url.port().unwrap_or(DEFAULT_PORT);
+    let stream = TcpStream::connect((host, port))
+        .await
+        .with_context(|| format!("Unable to connect to Redis at {host}:{port}"))?;
+    let mut stream = BufStream::new(stream);
+
+    if let Some(password) = url.password() {
+        let command = if url.username().is_empty() {
+            encode_command(&[b"AUTH", password.as_bytes()])
+        } else {
+            encode_command(&[b"AUTH", url.username().as_bytes(), password.as_bytes()])
+        };
+        let reply = exchange(&mut stream, &command, 1).await?.remove(0);
+        expect_ok("AUTH", reply)?;
+    }
+
+    let database = url.path().trim_start_matches('/');
+    if !database.is_empty() {
+        let command = encode_command(&[b"SELECT", database.as_bytes()]);
+        let reply = exchange(&mut stream, &command, 1).await?.remove(0);
+        expect_ok("SELECT", reply)?;
+    }
+
+    Ok(stream)
+}
+
+/// Writes the encoded commands and reads `count` replies
+async fn exchange(
+    stream: &mut BufStream<TcpStream>,
+    commands: &[u8],
+    count: usize,
+) -> Result<Vec<Reply>> {
+    stream.write_all(commands).await?;
+    stream.flush().await?;
+
+    let mut replies = Vec::with_capacity(count);
+    for _ in 0..count {
+        replies.push(read_reply(&mut *stream).await?);
+    }
+    Ok(replies)
+}
+
+fn expect_ok(command: &str, reply: Reply) -> Result<()> {
+    match reply {
+        Reply::Simple(status) if status == "OK" => Ok(()),
+        reply => bail!("Unexpected reply to {command}: {reply:?}"),
+    }
+}
+
+/// Encodes a command as an array of bulk strings
+fn encode_command(args: &[&[u8]]) -> Vec<u8> {
+    let mut command = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        command.extend(format!("${}\r\n", arg.len()).into_bytes());
+        command.extend_from_slice(arg);
+        command.extend_from_slice(b"\r\n");
+    }
+    command
+}
+
+fn read_reply<R>(reader: &mut R) -> BoxFuture<'_, Result<Reply>>
+where
+    R: AsyncBufRead + Unpin + Send,
+{
+    Box::pin(async move {
+        let mut line = Vec::new();
+        reader.read_until(b'\n', &mut line).await?;
+        if !line.ends_with(b"\r\n") {
+            bail!("Redis closed the connection");
+        }
+        line.truncate(line.len() - 2);
+
+        let Some((&kind, rest)) = line.split_first() else {
+            bail!("Received an empty reply from Redis");
+        };
+        let rest = std::str::from_utf8(rest)?;
+
+        Ok(match kind {
+            b'+' => Reply::Simple(rest.to_string()),
+            b'-' => Reply::Error(rest.to_string()),
+            b':' => Reply::Integer(rest.parse()?),
+            b'$' => {
+                let Ok(length) = usize::try_from(rest.parse::<i64>()?) else {
+                    return Ok(Reply::Bulk(None));
+                };
+                // The value is followed by a line break
+                let mut value = vec![0; length + 2];
+                reader.read_exact(&mut value).await?;
+                value.truncate(length);
+                Reply::Bulk(Some(value))
+            }
+            b'*' => {
+                let Ok(length) = usize::try_from(rest.parse::<i64>()?) else {
+                    return Ok(Reply::Array(None));
+                };
+                let mut replies = Vec::with_capacity(length);
+                for _ in 0..length {
+                    replies.push(read_reply(reader).await?);
+                }
+                Reply::Array(Some(replies))
+            }
+            kind => bail!("Unsupported Redis reply type `{}`", kind as char),
+        })
+    })
+}
@@ -1,5 +1,14 @@
+use std::fmt;
+
 use anyhow::{Context, Result};
-use discord_api::id::ApplicationId;
+use discord_api::{
+    application::{
+        command::ApplicationCommand,
+        sync::{diff_commands, CommandsDiff},
+        validation::validate_application_command,
+    },
+    id::{ApplicationId, GuildId},
+};
 use fujinoki_core::{
     config::FujinokiConfig,
     structures::commands::{CommandMetadata, CommandsMetadata},
@@ -8,14 +17,17 @@ use fujinoki_node::transforms::exports::ExportsContext;
 use serde_json::{json, Value as JsonValue};
 use turbopack_binding::{
     turbo::{
-        tasks::{self as turbo_tasks, Completion, ValueToString, Vc},
+        tasks::{self as turbo_tasks, Completion, RcStr, ValueToString, Vc},
         tasks_bytes::stream::SingleValue,
-        tasks_fs::json::parse_json_with_source_context,
+        tasks_fs::{
+            json::parse_json_with_source_context, File, FileContent, FileJsonContent,
+            FileSystemPath,
+        },
     },
     turbopack::{
         core::{
             file_source::FileSource,
-            issue::{handle_issues, IssueReporter, IssueSeverity},
+            issue::{handle_issues, IssueExt, IssueReporter, IssueSeverity},
             module::Module,
             source::Source,
         },
@@ -23,8 +35,15 @@ use turbopack_binding::{
     },
 };
 
-use super::util::merge_json;
-use crate::source::{ContentSourceData, EntryMap};
+use super::util::{merge_json, merge_locales, resolve_command_names, snake_case_command};
+use crate::{
+    discord::issue::RuntimeIssue,
+    source::{ContentSourceData, EntryMap},
+};
+
+/// The guilds that commands were registered to by the last sync, for both
+/// `development` and `production`
+const SYNCED_GUILDS_PATH: &str = ".fujinoki/synced-guilds.json";
 
 #[turbo_tasks::value]
 #[derive(Clone, Debug)]
@@ -33,92 +52,229 @@ pub struct DiscordApplicationCommandsUpdater {
     resolved_source: Vc<ContentSourceData>,
     issue_reporter: Vc<Box<dyn IssueReporter>>,
     application_id: ApplicationId,
+    /// Register commands to the `devGuildIds` of the config instead of
+    /// globally
+    dev: bool,
+    /// Only compute the changes, without registering them with Discord
+    dry_run: bool,
+}
+
+/// A command as it is sent to Discord, along with where it is registered
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+pub struct CommandDefinition {
+    pub command: JsonValue,
+    /// The guilds from the `guilds` export, the command is registered
+    /// globally without it
+    pub guilds: Option<Vec<GuildId>>,
+    /// The `cooldown` export, which is enforced before the command runs
+    pub cooldown: Option<JsonValue>,
+}
+
+/// The changes to the registered commands of a single scope
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+pub struct ScopeCommandsDiff {
+    /// `global` or `guild <id>`
+    pub scope: RcStr,
+    pub diff: CommandsDiff,
+}
+
+/// `None` when the commands couldn't be synced, either because a command is
+/// invalid or because a request to Discord failed
+#[turbo_tasks::value(transparent)]
+pub struct CommandsUpdate(Option<Vec<ScopeCommandsDiff>>);
+
+/// Commands are registered either globally or to a guild, each with its own
+/// set of commands
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CommandScope {
+    Global,
+    Guild(GuildId),
+}
+
+impl fmt::Display for CommandScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandScope::Global => write!(f, "global"),
+            CommandScope::Guild(guild_id) => write!(f, "guild {}", guild_id.0),
+        }
+    }
 }
 
 #[turbo_tasks::value_impl]
 impl DiscordApplicationCommandsUpdater {
+    #[turbo_tasks::function]
+    pub fn new(
+        config: Vc<FujinokiConfig>,
+        resolved_source: Vc<ContentSourceData>,
+        issue_reporter: Vc<Box<dyn IssueReporter>>,
+        application_id: ApplicationId,
+        dev: bool,
+        dry_run: bool,
+    ) -> Vc<Self> {
+        DiscordApplicationCommandsUpdater {
+            config,
+            resolved_source,
+            issue_reporter,
+            application_id,
+            dev,
+            dry_run,
+        }
+        .cell()
+    }
+
+    /// Compares the local commands with the registered ones and, for every
+    /// scope where anything changed, replaces the registered commands with a
+    /// single bulk overwrite.
+    ///
+    /// Returns the changes that were (or, for a dry run, would be) made.
     #[turbo_tasks::function]
     pub async fn update_application_commands(
         self: Vc<Self>,
         commands: Vc<CommandsMetadata>,
         entries: Vc<EntryMap>,
         additional_invalidation: Vc<Option<Vc<Completion>>>,
-    ) -> Result<Vc<()>> {
+    ) -> Result<Vc<CommandsUpdate>> {
         if let Some(additional_invalidation) = *additional_invalidation.await? {
             additional_invalidation.await?;
         };
 
-        let config = self.await?.config;
-        let resolved_source = self.await?.resolved_source;
-        let issue_reporter = self.await?.issue_reporter;
-        let application_id = self.await?.application_id;
-
-        let existing_application_commands =
-            discord_api::rest::application::commands::get_global_application_commands(
-                application_id,
-                false,
-                config.client().token(),
-                resolved_source.await?.commands_dir.await?.clone_value(),
-            );
-
-        handle_issues(
-            existing_application_commands.clone(),
-            issue_reporter,
-            IssueSeverity::Fatal.cell(),
-            None,
-            Some("get global application commands"),
-        )
-        .await?;
+        let this = self.await?;
+        let token = this.config.client().token().await?.clone_value();
+        let commands_dir = this
+            .resolved_source
+            .await?
+            .commands_dir
+            .await?
+            .clone_value();
 
-        let existing_application_commands = (&*existing_application_commands.await?)
+        let dev_guild_ids = if this.dev {
+            this.config.await?.dev_guild_ids.clone().unwrap_or_default()
+        } else {
+            vec![]
+        };
+        // While developing with test guilds the global commands are left alone, they
+        // belong to the deployed application
+        let default_scopes = if dev_guild_ids.is_empty() {
+            vec![CommandScope::Global]
+        } else {
+            dev_guild_ids
+                .into_iter()
+                .map(CommandScope::Guild)
+                .collect::<Vec<_>>()
+        };
+        let mut scopes = default_scopes
             .iter()
-            .map(|value| *value)
-            .collect::<Vec<_>>();
+            .map(|scope| (*scope, vec![]))
+            .collect::<Vec<(CommandScope, Vec<JsonValue>)>>();
 
         for command_data in commands.await?.iter() {
+            let definition = self.application_command(
+                command_data.clone().cell(),
+                entries.get_entry(command_data.file_path.to_string()),
+                additional_invalidation,
+            );
             handle_issues(
-                self.update_application_command(
-                    command_data.clone().cell(),
-                    entries.get_entry(command_data.file_path.to_string()),
-                    additional_invalidation,
-                ),
-                issue_reporter,
+                definition,
+                this.issue_reporter,
                 IssueSeverity::Fatal.cell(),
                 None,
-                Some("update application command"),
+                Some("evaluate application command"),
             )
             .await?;
+
+            // A bulk overwrite deletes every command that isn't included, so don't sync a
+            // partial set of commands
+            let Some(definition) = *definition.await? else {
+                return Ok(Vc::cell(None));
+            };
+            let definition = definition.await?;
+
+            let targets = match &definition.guilds {
+                Some(guilds) => guilds.iter().copied().map(CommandScope::Guild).collect(),
+                None => default_scopes.clone(),
+            };
+            for target in targets {
+                match scopes.iter_mut().find(|(scope, _)| *scope == target) {
+                    Some((_, commands)) => commands.push(definition.command.clone()),
+                    None => scopes.push((target, vec![definition.command.clone()])),
+                }
+            }
         }
 
-        for application_command in existing_application_commands.iter() {
-            let application_command = application_command.await?;
+        // A guild that no command targets anymore still has the commands of the last
+        // sync, which are removed with an empty bulk overwrite
+        let synced_guilds_path = this
+            .resolved_source
+            .await?
+            .project_path
+            .join(SYNCED_GUILDS_PATH.into());
+        let mode = if this.dev {
+            "development"
+        } else {
+            "production"
+        };
+        let mut synced_guilds = match &*synced_guilds_path.read_json().await? {
+            FileJsonContent::Content(content) if content.is_object() => content.clone(),
+            _ => json!({}),
+        };
+        let previous_guild_ids = synced_guilds
+            .get(mode)
+            .and_then(|guild_ids| serde_json::from_value::<Vec<GuildId>>(guild_ids.clone()).ok())
+            .unwrap_or_default();
+        for guild_id in previous_guild_ids {
+            let scope = CommandScope::Guild(guild_id);
+            if !scopes.iter().any(|(target, _)| *target == scope) {
+                scopes.push((scope, vec![]));
+            }
+        }
 
-            if let None = commands
-                .await?
-                .iter()
-                .find(|v| v.name == application_command.name)
-            {
-                let res =
-                    discord_api::rest::application::command::delete_global_application_command(
-                        application_id,
-                        application_command.id,
-                        config.client().token(),
-                        resolved_source.await?.commands_dir.await?.clone_value(),
-                    );
-                handle_issues(res, issue_reporter, IssueSeverity::Fatal.cell(), None, None).await?;
+        let mut diffs = vec![];
+        let mut synced = true;
+        let mut guild_ids = vec![];
+        for (scope, commands) in scopes {
+            let has_commands = !commands.is_empty();
+            let diff = this
+                .sync_scope(scope, commands, token.clone(), commands_dir)
+                .await?;
+            if let CommandScope::Guild(guild_id) = scope {
+                // Guilds that failed to sync are kept so they're tried again
+                if has_commands || diff.is_none() {
+                    guild_ids.push(guild_id);
+                }
+            }
+            match diff {
+                Some(diff) => diffs.push(ScopeCommandsDiff {
+                    scope: scope.to_string().into(),
+                    diff,
+                }),
+                None => synced = false,
             }
         }
 
-        Ok(Default::default())
+        if !this.dry_run {
+            synced_guilds[mode] = json!(guild_ids);
+            synced_guilds_path
+                .write(
+                    FileContent::Content(File::from(serde_json::to_string_pretty(&synced_guilds)?))
+                        .cell(),
+                )
+                .await?;
+        }
+
+        Ok(Vc::cell(synced.then_some(diffs)))
     }
 
+    /// Evaluates the `data`, `guilds` and `cooldown` exports of a command,
+    /// merged with the translations of its `.locales.json` file
     #[turbo_tasks::function]
-    async fn update_application_command(
+    pub async fn application_command(
         self: Vc<Self>,
         command_data: Vc<CommandMetadata>,
         entry: Vc<Option<Vc<Box<dyn Module>>>>,
         additional_invalidation: Vc<Option<Vc<Completion>>>,
-    ) -> Result<Vc<()>> {
+    ) -> Result<Vc<Option<Vc<CommandDefinition>>>> {
         if let Some(additional_invalidation) = *additional_invalidation.await? {
             additional_invalidation.await?;
         };
@@ -126,30 +282,6 @@ impl DiscordApplicationCommandsUpdater {
         let config = self.await?.config;
         let resolved_source = self.await?.resolved_source;
         let issue_reporter = self.await?.issue_reporter;
-        let application_id = self.await?.application_id;
-
-        // This should be okay because the result should already be cached.
-        let registered_application_commands =
-            discord_api::rest::application::commands::get_global_application_commands(
-                application_id,
-                false,
-                config.client().token(),
-                resolved_source.await?.commands_dir.await?.clone_value(),
-            );
-
-        handle_issues(
-            registered_application_commands.clone(),
-            issue_reporter,
-            IssueSeverity::Fatal.cell(),
-            None,
-            None,
-        )
-        .await?;
-
-        let mut existing_application_commands = vec![];
-        for application_command in registered_application_commands.await?.iter() {
-            existing_application_commands.push(application_command.await?.clone());
-        }
 
         let command_data = command_data.await?;
         let exported: JsonValue = if let Some(entry) = *entry.await? {
@@ -166,7 +298,11 @@ impl DiscordApplicationCommandsUpdater {
             let initial_val = custom_evaluate(ExportsContext {
                 // TODO(kijv) use individual exported values AND the data object which contains
                 // EVERY key (to be merged with individual exports having precedence)
-                args: vec![Vc::cell("data".into())],
+                args: vec![
+                    Vc::cell("data".into()),
+                    Vc::cell("guilds".into()),
+                    Vc::cell("cooldown".into()),
+                ],
                 module_asset: entry,
                 cwd: project_path,
                 env,
@@ -186,53 +322,194 @@ impl DiscordApplicationCommandsUpdater {
                     None,
                 )
                 .await?;
-                return Ok(Default::default());
+                return Ok(Vc::cell(None));
             };
             parse_json_with_source_context(val.to_str()?)
                 .context("Unable to deserialize response")?
         } else {
-            return Ok(Default::default());
+            return Ok(Vc::cell(None));
         };
-        dbg!(exported.clone());
-        let data = exported.get("data").unwrap();
 
-        // edit
-        if let Some(application_command) = existing_application_commands
-            .iter()
-            .find(|v| v.name.eq(&command_data.name.to_string()))
-        {
-            let mut command = json!({
-                "name": command_data.name,
-                // TODO(kijv) make `description`/`data.description` a required export
-                "description": "Hello world!"
-            });
-            merge_json(&mut command, &data);
-
-            let res = discord_api::rest::application::command::edit_global_application_command(
-                application_id,
-                application_command.id,
-                Vc::cell(command.clone()),
-                config.client().token(),
-                Some(command_data.file_path),
-            );
-            handle_issues(res, issue_reporter, IssueSeverity::Fatal.cell(), None, None).await?;
+        let mut command = json!({
+            "name": command_data.name,
+        });
+        if let Some(data) = exported.get("data") {
+            merge_json(&mut command, data);
         }
-        // create
-        else {
-            let mut command = json!({
-                "name": command_data.name,
-            });
-            merge_json(&mut command, &data);
+        snake_case_command(&mut command);
 
-            let res = discord_api::rest::application::command::create_global_application_command(
-                application_id,
-                Vc::cell(command.clone()),
-                config.client().token(),
-                Some(command_data.file_path),
-            );
-            handle_issues(res, issue_reporter, IssueSeverity::Fatal.cell(), None, None).await?;
+        // Translations can also be kept next to the command, e.g.
+        // `commands/ban.locales.json`
+        let file_path = command_data.file_path.await?;
+        if let Some(file_stem) = file_path.file_stem() {
+            let locales_path = command_data
+                .file_path
+                .parent()
+                .join(format!("{file_stem}.locales.json").into());
+            match &*locales_path.read_json().await? {
+                FileJsonContent::Content(locales) => merge_locales(&mut command, locales),
+                FileJsonContent::Unparseable(err) => {
+                    RuntimeIssue {
+                        path: locales_path,
+                        severity: Some(IssueSeverity::Error.cell()),
+                        title: format!("Invalid locales of command `{}`", command_data.name).into(),
+                        description: Some(err.message.to_string().into()),
+                    }
+                    .cell()
+                    .emit();
+
+                    return Ok(Vc::cell(None));
+                }
+                FileJsonContent::NotFound => {}
+            }
+        }
+
+        let mut errors = resolve_command_names(&mut command);
+        if errors.is_empty() {
+            errors = match serde_json::from_value::<ApplicationCommand>(command.clone()) {
+                Ok(application_command) => validate_application_command(&application_command),
+                Err(err) => vec![err.to_string()],
+            };
+        }
+        if !errors.is_empty() {
+            for error in errors {
+                RuntimeIssue {
+                    path: command_data.file_path,
+                    severity: Some(IssueSeverity::Error.cell()),
+                    title: format!("Invalid application command `{}`", command_data.name).into(),
+                    description: Some(error.into()),
+                }
+                .cell()
+                .emit();
+            }
+
+            return Ok(Vc::cell(None));
+        }
+
+        let guilds = match exported.get("guilds") {
+            Some(guilds) if !guilds.is_null() => {
+                match serde_json::from_value::<Vec<GuildId>>(guilds.clone()) {
+                    Ok(guilds) => Some(guilds),
+                    Err(err) => {
+                        RuntimeIssue {
+                            path: command_data.file_path,
+                            severity: Some(IssueSeverity::Error.cell()),
+                            title: format!("Invalid guilds of command `{}`", command_data.name)
+                                .into(),
+                            description: Some(
+                                format!("`guilds` must be an array of guild ids: {err}").into(),
+                            ),
+                        }
+                        .cell()
+                        .emit();
+
+                        return Ok(Vc::cell(None));
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let cooldown = exported
+            .get("cooldown")
+            .filter(|cooldown| !cooldown.is_null())
+            .cloned();
+
+        Ok(Vc::cell(Some(
+            CommandDefinition {
+                command,
+                guilds,
+                cooldown,
+            }
+            .cell(),
+        )))
+    }
+}
+
+impl DiscordApplicationCommandsUpdater {
+    /// Reconciles the registered commands of a single scope, returns `None`
+    /// when the registered commands couldn't be fetched
+    async fn sync_scope(
+        &self,
+        scope: CommandScope,
+        commands: Vec<JsonValue>,
+        token: RcStr,
+        file_path: Option<Vc<FileSystemPath>>,
+    ) -> Result<Option<CommandsDiff>> {
+        // The registered commands are requested on every sync, they can be changed
+        // from anywhere in the meantime
+        let registered_commands = match scope {
+            CommandScope::Global => {
+                discord_api::rest::application::commands::get_global_application_commands_json(
+                    self.application_id,
+                    &token,
+                    file_path,
+                )
+                .await?
+            }
+            CommandScope::Guild(guild_id) => {
+                discord_api::rest::application::commands::get_guild_application_commands_json(
+                    self.application_id,
+                    guild_id,
+                    &token,
+                    file_path,
+                )
+                .await?
+            }
+        };
+        // Failed requests emit an issue in this task, which is reported along with
+        // the cell
+        self.report_issues(
+            Vc::cell(registered_commands.is_some()),
+            "get application commands",
+        )
+        .await?;
+
+        let Some(registered_commands) = registered_commands else {
+            return Ok(None);
+        };
+
+        let diff = diff_commands(&commands, &registered_commands);
+        if diff.is_empty() || self.dry_run {
+            return Ok(Some(diff));
         }
 
-        Ok(Default::default())
+        let commands = JsonValue::Array(commands);
+        let overwritten = match scope {
+            CommandScope::Global => {
+                discord_api::rest::application::commands::bulk_overwrite_global_application_commands(
+                    self.application_id,
+                    &commands,
+                    &token,
+                    file_path,
+                )
+                .await?
+            }
+            CommandScope::Guild(guild_id) => {
+                discord_api::rest::application::commands::bulk_overwrite_guild_application_commands(
+                    self.application_id,
+                    guild_id,
+                    &commands,
+                    &token,
+                    file_path,
+                )
+                .await?
+            }
+        };
+        self.report_issues(Vc::cell(overwritten), "bulk overwrite application commands")
+            .await?;
+
+        Ok(overwritten.then_some(diff))
+    }
+
+    async fn report_issues(&self, source: Vc<bool>, operation: &str) -> Result<()> {
+        handle_issues(
+            source,
+            self.issue_reporter,
+            IssueSeverity::Fatal.cell(),
+            None,
+            Some(operation),
+        )
+        .await
     }
 }
@@ -1,4 +1,7 @@
-use serde_json::Value as JsonValue;
+use discord_api::permissions::Permissions;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::util::snake_case;
 
 pub fn merge_json(base: &mut JsonValue, other: &JsonValue) {
     match (base, other) {
@@ -17,3 +20,189 @@ pub fn merge_json(base: &mut JsonValue, other: &JsonValue) {
         }
     }
 }
+
+/// Converts the camelCase keys of a command definition (`nameLocalizations`,
+/// `minValue`, ...) to the snake_case keys Discord expects.
+///
+/// The keys of localizations are locale codes, so those are left as they are.
+pub fn snake_case_command(command: &mut JsonValue) {
+    match command {
+        JsonValue::Object(map) => {
+            *map = std::mem::take(map)
+                .into_iter()
+                .map(|(key, mut value)| {
+                    let key = snake_case(&key);
+                    if !key.ends_with("_localizations") {
+                        snake_case_command(&mut value);
+                    }
+                    (key, value)
+                })
+                .collect();
+        }
+        JsonValue::Array(values) => values.iter_mut().for_each(snake_case_command),
+        _ => {}
+    }
+}
+
+/// Merges the translations of a `<command>.locales.json` file into a command,
+/// localizations from the command's `data` take precedence.
+///
+/// ```json
+/// {
+///   "name": { "de": "bannen" },
+///   "description": { "de": "Bannt ein Mitglied" },
+///   "options": {
+///     "reason": {
+///       "description": { "de": "Warum" },
+///       "choices": { "Spam": { "de": "Spam" } }
+///     }
+///   }
+/// }
+/// ```
+pub fn merge_locales(command: &mut JsonValue, locales: &JsonValue) {
+    let (Some(command), Some(locales)) = (command.as_object_mut(), locales.as_object()) else {
+        return;
+    };
+
+    for key in ["name", "description"] {
+        if let Some(JsonValue::Object(localizations)) = locales.get(key) {
+            merge_localizations(command, &format!("{key}_localizations"), localizations);
+        }
+    }
+
+    if let (Some(JsonValue::Array(options)), Some(JsonValue::Object(option_locales))) =
+        (command.get_mut("options"), locales.get("options"))
+    {
+        for option in options {
+            let Some(name) = option.get("name").and_then(JsonValue::as_str) else {
+                continue;
+            };
+            if let Some(locales) = option_locales.get(name).cloned() {
+                merge_locales(option, &locales);
+            }
+        }
+    }
+
+    if let (Some(JsonValue::Array(choices)), Some(JsonValue::Object(choice_locales))) =
+        (command.get_mut("choices"), locales.get("choices"))
+    {
+        for choice in choices {
+            let Some(choice) = choice.as_object_mut() else {
+                continue;
+            };
+            let Some(JsonValue::Object(localizations)) = choice
+                .get("name")
+                .and_then(JsonValue::as_str)
+                .and_then(|name| choice_locales.get(name))
+            else {
+                continue;
+            };
+            let localizations = localizations.clone();
+            merge_localizations(choice, "name_localizations", &localizations);
+        }
+    }
+}
+
+fn merge_localizations(
+    target: &mut Map<String, JsonValue>,
+    key: &str,
+    localizations: &Map<String, JsonValue>,
+) {
+    let entry = target
+        .entry(key)
+        .or_insert_with(|| JsonValue::Object(Map::new()));
+    if entry.is_null() {
+        *entry = JsonValue::Object(Map::new());
+    }
+    if let JsonValue::Object(entry) = entry {
+        for (locale, value) in localizations {
+            entry.entry(locale.clone()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
+/// Turns the names that can be used in a command definition into the values
+/// Discord expects:
+/// - `default_member_permissions`: permission names, e.g. `["BAN_MEMBERS"]`,
+///   into a bitfield, an empty list only allows admins
+/// - `contexts`: `GUILD`, `BOT_DM` and `PRIVATE_CHANNEL`
+/// - `integration_types`: `GUILD_INSTALL` and `USER_INSTALL`
+///
+/// Returns a message for every name that isn't known.
+pub fn resolve_command_names(command: &mut JsonValue) -> Vec<String> {
+    let mut errors = vec![];
+    let Some(command) = command.as_object_mut() else {
+        return errors;
+    };
+
+    if let Some(permissions) = command.get_mut("default_member_permissions") {
+        let names = match permissions {
+            JsonValue::Array(names) => Some(names.clone()),
+            JsonValue::String(value) if value.parse::<u64>().is_err() => {
+                Some(vec![JsonValue::String(value.clone())])
+            }
+            JsonValue::Number(value) => {
+                *permissions = JsonValue::String(value.to_string());
+                None
+            }
+            _ => None,
+        };
+
+        if let Some(names) = names {
+            let mut bits = 0;
+            for name in &names {
+                match name.as_str().and_then(Permissions::from_str) {
+                    Some(permission) => bits |= permission.0,
+                    None => errors.push(format!(
+                        "`defaultMemberPermissions` contains an unknown permission {name}"
+                    )),
+                }
+            }
+            *permissions = JsonValue::String(bits.to_string());
+        }
+    }
+
+    resolve_enum_names(
+        command,
+        "contexts",
+        &[("GUILD", 0), ("BOT_DM", 1), ("PRIVATE_CHANNEL", 2)],
+        &mut errors,
+    );
+    resolve_enum_names(
+        command,
+        "integration_types",
+        &[("GUILD_INSTALL", 0), ("USER_INSTALL", 1)],
+        &mut errors,
+    );
+
+    errors
+}
+
+fn resolve_enum_names(
+    command: &mut Map<String, JsonValue>,
+    key: &str,
+    names: &[(&str, u8)],
+    errors: &mut Vec<String>,
+) {
+    let Some(JsonValue::Array(values)) = command.get_mut(key) else {
+        return;
+    };
+
+    for value in values {
+        let Some(name) = value.as_str() else {
+            continue;
+        };
+        match names.iter().find(|(known, _)| *known == name) {
+            Some((_, number)) => *value = (*number).into(),
+            None => errors.push(format!(
+                "`{}` contains an unknown value \"{name}\", expected one of {}",
+                key,
+                names
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Cooldowns of commands, from their `cooldown` export, e.g.
+//! `export const cooldown = { duration: 5, scope: "guild" }`
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Result};
+use discord_api::{
+    id::{ChannelId, GuildId, UserId},
+    interactions::Interaction,
+};
+use fujinoki_core::config::FujinokiConfig;
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use tokio::sync::Mutex;
+use turbopack_binding::{
+    turbo::{tasks::Vc, tasks_fs::FileSystemPath},
+    turbopack::core::issue::{IssueExt, IssueSeverity},
+};
+
+use super::issue::RuntimeIssue;
+
+const DEFAULT_MESSAGE: &str = "You're on cooldown, try again in {remaining}s";
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cooldown {
+    /// In seconds
+    pub duration: f64,
+    #[serde(default)]
+    pub scope: CooldownScope,
+    /// Overrides the `cooldownMessage` of the config
+    pub message: Option<String>,
+}
+
+/// Who shares a cooldown
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CooldownScope {
+    #[default]
+    User,
+    Guild,
+    Channel,
+}
+
+/// Who invoked a command and where
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CooldownTarget {
+    pub user_id: Option<UserId>,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: Option<ChannelId>,
+}
+
+impl CooldownTarget {
+    /// The user who invoked the command of an interaction, and where
+    pub fn of_interaction(interaction: &Interaction) -> Self {
+        let user = interaction
+            .member
+            .as_ref()
+            .and_then(|member| member.user.as_ref())
+            .or(interaction.user.as_ref());
+
+        Self {
+            user_id: user.map(|user| user.id),
+            guild_id: interaction.guild_id,
+            channel_id: interaction.channel_id,
+        }
+    }
+
+    fn key(&self, scope: CooldownScope) -> Option<String> {
+        match scope {
+            CooldownScope::User => self.user_id.map(|id| format!("user:{id}")),
+            // Direct messages don't have a guild, their channel is used instead
+            CooldownScope::Guild => self
+                .guild_id
+                .map(|id| format!("guild:{id}"))
+                .or_else(|| self.key(CooldownScope::Channel)),
+            CooldownScope::Channel => self.channel_id.map(|id| format!("channel:{id}")),
+        }
+    }
+}
+
+/// Where running cooldowns are kept
+pub trait CooldownStore: Send + Sync {
+    /// Starts the cooldown of `key`, unless one is already running, in which
+    /// case the time it has left is returned
+    fn try_start<'a>(
+        &'a self,
+        key: &'a str,
+        duration: Duration,
+    ) -> BoxFuture<'a, Result<Option<Duration>>>;
+}
+
+/// Keeps the cooldowns in the memory of this process
+#[derive(Default)]
+pub struct MemoryCooldownStore {
+    expirations: Mutex<HashMap<String, Instant>>,
+}
+
+impl MemoryCooldownStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CooldownStore for MemoryCooldownStore {
+    fn try_start<'a>(
+        &'a self,
+        key: &'a str,
+        duration: Duration,
+    ) -> BoxFuture<'a, Result<Option<Duration>>> {
+        Box::pin(async move {
+            let now = Instant::now();
+            let mut expirations = self.expirations.lock().await;
+            expirations.retain(|_, expiration| *expiration > now);
+
+            if let Some(expiration) = expirations.get(key) {
+                return Ok(Some(*expiration - now));
+            }
+            let Some(expiration) = now.checked_add(duration) else {
+                bail!("The cooldown of `{key}` is too long");
+            };
+            expirations.insert(key.to_string(), expiration);
+
+            Ok(None)
+        })
+    }
+}
+
+/// Enforces the `cooldown` export of a command, returns the message to respond
+/// with when the command is on cooldown
+pub async fn enforce_cooldown(
+    store: &dyn CooldownStore,
+    config: Vc<FujinokiConfig>,
+    command: &str,
+    cooldown: &JsonValue,
+    target: CooldownTarget,
+    file_path: Vc<FileSystemPath>,
+) -> Result<Option<String>> {
+    let cooldown = match serde_json::from_value::<Cooldown>(cooldown.clone()) {
+        Ok(cooldown) => cooldown,
+        Err(err) => {
+            emit_cooldown_issue(command, &err.to_string(), file_path);
+            return Ok(None);
+        }
+    };
+    let Some(duration) = cooldown_duration(cooldown.duration) else {
+        emit_cooldown_issue(command, "`duration` must be a positive number", file_path);
+        return Ok(None);
+    };
+    let Some(key) = target.key(cooldown.scope) else {
+        return Ok(None);
+    };
+
+    let key = format!("{command}:{key}");
+    let Some(remaining) = store.try_start(&key, duration).await? else {
+        return Ok(None);
+    };
+
+    let template = match cooldown.message {
+        Some(message) => message,
+        None => config
+            .await?
+            .cooldown_message
+            .as_deref()
+            .unwrap_or(DEFAULT_MESSAGE)
+            .to_string(),
+    };
+    let remaining = remaining.as_secs_f64().ceil() as u64;
+
+    Ok(Some(
+        template.replace("{remaining}", &remaining.to_string()),
+    ))
+}
+
+/// The duration of a cooldown in seconds, `None` when it isn't positive or is
+/// too large to be a point in time
+fn cooldown_duration(seconds: f64) -> Option<Duration> {
+    Duration::try_from_secs_f64(seconds)
+        .ok()
+        .filter(|duration| !duration.is_zero() && Instant::now().checked_add(*duration).is_some())
+}
+
+fn emit_cooldown_issue(command: &str, description: &str, file_path: Vc<FileSystemPath>) {
+    RuntimeIssue {
+        path: file_path,
+        severity: Some(IssueSeverity::Error.cell()),
+        title: format!("Invalid cooldown of command `{command}`").into(),
+        description: Some(description.into()),
+    }
+    .cell()
+    .emit();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use discord_api::id::{ChannelId, GuildId, UserId};
+
+    use super::{
+        cooldown_duration, CooldownScope, CooldownStore, CooldownTarget, MemoryCooldownStore,
+    };
+
+    #[test]
+    fn test_cooldown_duration() {
+        assert_eq!(cooldown_duration(1.5), Some(Duration::from_millis(1500)));
+        assert_eq!(cooldown_duration(0.0), None);
+        assert_eq!(cooldown_duration(-1.0), None);
+        assert_eq!(cooldown_duration(f64::NAN), None);
+        assert_eq!(cooldown_duration(f64::INFINITY), None);
+        assert_eq!(cooldown_duration(1e30), None);
+    }
+
+    #[test]
+    fn test_cooldown_keys() {
+        let target = CooldownTarget {
+            user_id: Some(UserId(1)),
+            guild_id: Some(GuildId(2)),
+            channel_id: Some(ChannelId(3)),
+        };
+        assert_eq!(target.key(CooldownScope::User).as_deref(), Some("user:1"));
+        assert_eq!(target.key(CooldownScope::Guild).as_deref(), Some("guild:2"));
+        assert_eq!(
+            target.key(CooldownScope::Channel).as_deref(),
+            Some("channel:3")
+        );
+
+        // Direct messages share the cooldown of their channel
+        let direct_message = CooldownTarget {
+            guild_id: None,
+            ..target
+        };
+        assert_eq!(
+            direct_message.key(CooldownScope::Guild).as_deref(),
+            Some("channel:3")
+        );
+
+        assert_eq!(CooldownTarget::default().key(CooldownScope::User), None);
+        assert_eq!(CooldownTarget::default().key(CooldownScope::Guild), None);
+    }
+
+    #[tokio::test]
+    async fn test_memory_cooldown_store() {
+        let store = MemoryCooldownStore::new();
+        let duration = Duration::from_millis(50);
+
+        assert_eq!(store.try_start("a", duration).await.unwrap(), None);
+        let remaining = store.try_start("a", duration).await.unwrap().unwrap();
+        assert!(remaining <= duration);
+        // Each key has its own cooldown
+        assert_eq!(store.try_start("b", duration).await.unwrap(), None);
+
+        tokio::time::sleep(duration).await;
+        assert_eq!(store.try_start("a", duration).await.unwrap(), None);
+
+        assert!(store.try_start("c", Duration::MAX).await.is_err());
+    }
+}
@@ -1,24 +1,24 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use discord_api::{
-    channel::embed::{
-        Embed, EmbedAuthor, EmbedField, EmbedFooter, EmbedImage, EmbedProvider, EmbedThumbnail,
-        EmbedType, EmbedVideo,
-    },
-    gateway::{OpCodeName, Payload, ReadyEventPayload},
-    id::InteractionId,
+    application::command::ApplicationCommand,
+    gateway::{DispatchEvent, OpCode, OpCodeName, Payload, ReadyEventPayload},
+    id::ApplicationId,
     interactions::{
-        InteractionCallbackData, InteractionCallbackMessagesData, InteractionCallbackType,
-        InteractionResponse,
+        options::{resolve_options, ResolvedOptions},
+        Interaction,
     },
 };
+use fujinoki_core::{config::FujinokiConfig, structures::events::LifecycleEvent};
+use fujinoki_node::transforms::exports::ExportsContext;
+use futures::future::join_all;
 use serde_json::{json, Value as JsonValue};
 use tokio::sync::Mutex;
 use turbopack_binding::{
     turbo::{
         tasks as turbo_tasks,
-        tasks::{run_once_with_reason, RcStr, ValueToString, Vc},
+        tasks::{run_once_with_reason, Completion, RcStr, ValueToString, Vc},
         tasks_bytes::stream::SingleValue,
         tasks_fs::{json::parse_json_with_source_context, FileSystemPath},
     },
@@ -26,15 +26,22 @@ use turbopack_binding::{
         core::{
             file_source::FileSource,
             issue::{handle_issues, IssueExt, IssueReporter, IssueSeverity},
-            module::{Module, OptionModule},
+            module::Module,
             source::Source,
         },
-        node::evaluate::evaluate,
+        node::evaluate::{custom_evaluate, evaluate},
     },
 };
 
-use super::{external::lilybird, issue::RuntimeIssue};
+use super::{
+    commands::update::{CommandDefinition, DiscordApplicationCommandsUpdater},
+    cooldown::{enforce_cooldown, CooldownTarget},
+    issue::RuntimeIssue,
+    prefix::dispatch_prefix_command,
+    response::{command_return_to_response, CommandResponse},
+};
 use crate::{
+    cache::{backend::connect_backend, read_through, EntityCache, EntityScope},
     invalidation::WebsocketMessageSideEffects,
     source::{ContentSource, ContentSourceData},
     util::CamelCaseJson,
@@ -67,21 +74,79 @@ pub async fn dispatch(
     )
     .await?;
 
-    let mut data = json.clone().d.expect("failed to retrieve dispatch data");
-    let data = data.as_object_mut().unwrap();
+    let Some(event_name) = json.t.clone() else {
+        // Only dispatch payloads have an event name
+        return Ok(Default::default());
+    };
+    let data = json.d.clone().unwrap_or_default();
+
+    let event = match DispatchEvent::from_payload(&event_name, data.clone()) {
+        Ok(event) => event,
+        Err(err) => {
+            RuntimeIssue {
+                path: source
+                    .resolve_strongly_consistent()
+                    .await?
+                    .await?
+                    .project_path,
+                severity: Some(IssueSeverity::Error.cell()),
+                title: format!("Unable to deserialize the {event_name} event").into(),
+                description: Some(err.to_string().into()),
+            }
+            .cell()
+            .emit();
+
+            // The handlers don't need the typed event, so they still run with the
+            // payload as it was sent
+            DispatchEvent::Unknown {
+                name: event_name.clone(),
+                data: data.clone(),
+            }
+        }
+    };
+    // The handlers still receive the event as it was sent
+    let data = match data {
+        JsonValue::Object(data) => data,
+        _ => Default::default(),
+    };
 
-    let event_name: RcStr = json.clone().t.unwrap().into();
+    // Handlers see the cache with the event already applied
+    update_cache(&event, source, &*ctx.await?).await?;
+    let scope = EntityScope::of_event(&event);
 
     let side_effects_reason = WebsocketMessageSideEffects {
         opcode: json.op,
         event: json.t.as_ref().map(|t| t.to_string()),
     };
 
-    // TODO make executing user-events a function for reusability and less
-    // redundancy
-    match &*event_name.clone().to_string() {
-        "READY" => {
+    if let DispatchEvent::Resumed = &event {
+        dispatch_lifecycle_event(
+            &*ctx.await?,
+            source,
+            issue_reporter,
+            LifecycleEvent::Resumed,
+            JsonValue::Null,
+        )
+        .await?;
+    }
+
+    if let DispatchEvent::MessageCreate(message) = &event {
+        dispatch_prefix_command(
+            message,
+            &data,
+            &scope,
+            source,
+            issue_reporter,
+            ctx,
+            side_effects_reason.clone(),
+        )
+        .await?;
+    }
+
+    match event {
+        DispatchEvent::Ready(ready) => {
             let ctx = ctx.await?;
+            let shard = ready.shard;
             let resolved_source = source.resolve_strongly_consistent().await?;
 
             let mut clean_client_data = ctx
@@ -98,8 +163,8 @@ pub async fn dispatch(
                 .try_lock()
                 .expect("failed to lock `resume_gateway_url`");
 
-            *session_id = Some(data["session_id"].as_str().unwrap().to_string());
-            *resume_gateway_url = Some(data["resume_gateway_url"].as_str().unwrap().to_string());
+            *session_id = Some(ready.session_id);
+            *resume_gateway_url = Some(ready.resume_gateway_url);
 
             // Removes unnecessary data from the ready event, which will be used when
             // calling the ready event handler
@@ -109,29 +174,6 @@ pub async fn dispatch(
             client_data.remove("session_id");
             client_data.remove("resume_gateway_url");
 
-            // * this should be faster once Turbo engine has persistent caching
-            // if resolved_source.get_commands_dir().await?.is_some() {
-            //     let application_id: ApplicationId = client_data["application"]["id"]
-            //         .as_str()
-            //         .unwrap()
-            //         .parse()
-            //         .unwrap();
-            //     util::update_application_commands(
-            //         application_id,
-            //         resolved_source,
-            //         resolved_source.get_commands(),
-            //         resolved_source.get_entries(),
-            //         ctx.config.clone(),
-            //         issue_reporter.clone(),
-            //         Vc::cell(Some(
-            //             resolved_source
-            //                 .get_commands_dir()
-            //                 .routes_changed(ctx.config),
-            //         )),
-            //     )
-            //     .await?;
-            // }
-
             let client_data = ReadyEventPayload {
                 client: JsonValue::Object(client_data.camel_case_json()),
             };
@@ -139,170 +181,71 @@ pub async fn dispatch(
             *clean_client_data = Some(client_data.client.clone());
             drop(clean_client_data);
 
-            if let Some(entry) = get_event_entry(resolved_source, event_name.clone())
-                .await?
-                .clone_value()
-            {
-                let debug = ctx.debug;
-                let config = ctx.config;
-
-                let join_handle = tokio::spawn(run_once_with_reason(
-                    ctx.turbo_tasks.clone(),
-                    side_effects_reason,
-                    async move {
-                        let project_path = resolved_source.clone().await?.project_path;
-                        let chunking_context = resolved_source
-                            .clone()
-                            .await?
-                            .executor
-                            .await?
-                            .chunking_context;
-                        let asset_context =
-                            resolved_source.clone().await?.executor.await?.asset_context;
-                        let env = resolved_source.clone().await?.executor.await?.env;
-
-                        let evaluated = evaluate(
-                            entry,
-                            project_path,
-                            env,
-                            FileSource::new(project_path).ident(),
-                            asset_context,
-                            Vc::upcast(chunking_context),
-                            None,
-                            vec![Vc::cell(serde_json::to_value(client_data).unwrap())],
-                            resolved_source.get_events_dir().routes_changed(config),
-                            debug,
-                        );
-
-                        handle_issues(
-                            evaluated,
-                            issue_reporter,
-                            IssueSeverity::Fatal.cell(),
-                            None,
-                            Some("evaluate js"),
-                        )
-                        .await?;
-
-                        Ok(())
-                    },
-                ));
-                ctx.ongoing_side_effects
-                    .lock()
-                    .await
-                    .push_back(Arc::new(Mutex::new(Some(join_handle))));
-            };
+            run_event_handlers(
+                &ctx,
+                resolved_source,
+                event_name,
+                serde_json::to_value(client_data)?,
+                issue_reporter,
+                side_effects_reason,
+            )
+            .await?;
+
+            dispatch_lifecycle_event(
+                &ctx,
+                source,
+                issue_reporter,
+                LifecycleEvent::ShardReady,
+                json!({ "shard": shard }),
+            )
+            .await?;
 
             Ok(Default::default())
         }
-        "INTERACTION_CREATE" => {
+        DispatchEvent::InteractionCreate(interaction) => {
             let resolved_source = source.resolve_strongly_consistent().await?;
-            let commands = resolved_source.get_commands().await?;
-            let commands: std::collections::BTreeMap<RcStr, Vc<FileSystemPath>> = commands
-                .iter()
-                .map(|v| (v.name.clone(), v.file_path.clone()))
-                .collect::<BTreeMap<_, _>>();
-
-            let command_name: &RcStr = &data["data"]["name"].as_str().unwrap().to_string().into();
-            let command_data = commands.get(command_name);
-            let entries = resolved_source.clone().get_entries();
-            let command = if let Some(file_path) = command_data {
-                *entries.get_entry(file_path.to_string()).await?
-            } else {
-                None
-            };
 
-            // TODO abstract duplicate code
+            // Components and modals don't invoke a command
+            let command_name = interaction.command_name().map(RcStr::from);
+            let command = match &command_name {
+                Some(command_name) => {
+                    *get_command_entry(
+                        resolved_source,
+                        ctx.await?.config,
+                        issue_reporter,
+                        interaction.application_id,
+                        command_name.clone(),
+                    )
+                    .await?
+                }
+                None => None,
+            };
 
-            let event = get_event_entry(resolved_source, event_name.clone());
             // User-provided event
-            if let Some(event_entry) = *event.await? {
-                let ctx = ctx.await?;
-                let data = data.clone();
-                let clean_client_data = ctx.clean_client_data.clone();
-                let debug = ctx.debug;
-                let config = ctx.config;
-
-                let join_handle = tokio::spawn(run_once_with_reason(
-                    ctx.turbo_tasks.clone(),
-                    side_effects_reason.clone(),
-                    async move {
-                        let project_path = resolved_source.clone().await?.project_path;
-                        let chunking_context = resolved_source
-                            .clone()
-                            .await?
-                            .executor
-                            .await?
-                            .chunking_context;
-                        let asset_context =
-                            resolved_source.clone().await?.executor.await?.asset_context;
-                        let env = resolved_source.clone().await?.executor.await?.env;
-
-                        let clean_client_data = clean_client_data
-                            .try_lock()
-                            .expect("failed to lock `clean_client_data`")
-                            .clone()
-                            .unwrap_or(JsonValue::Object(serde_json::Map::default()));
-
-                        // TODO(kijv) only update this application_command (route_changed for
-                        // invalidation) if resolved_source.await?.commands_dir.await?.
-                        // clone_value().is_some() {     let application_id:
-                        // ApplicationId = clean_client_data["application"]["id"].
-                        // as_str().unwrap().parse().unwrap();
-                        //     util::update_application_commands(
-                        //         application_id,
-                        //         resolved_source,
-                        //         resolved_source.get_commands(),
-                        //         resolved_source.get_entries(),
-                        //         config.clone(),
-                        //         issue_reporter.clone(),
-                        //         Vc::cell(Some(resolved_source.get_commands_dir().
-                        // routes_changed(config))),     ).await?;
-                        // }
-
-                        let initial_val = evaluate(
-                            event_entry,
-                            project_path,
-                            env,
-                            FileSource::new(project_path).ident(),
-                            asset_context,
-                            Vc::upcast(chunking_context),
-                            None,
-                            vec![Vc::cell(json!({
-                                "interaction": data.clone(),
-                                "client": clean_client_data
-                            }))],
-                            resolved_source.get_events_dir().routes_changed(config),
-                            debug,
-                        );
-
-                        let SingleValue::Single(_) = initial_val.await?.try_into_single().await?
-                        else {
-                            // An error happened, which has already been converted into an issue.
-                            return handle_issues(
-                                initial_val,
-                                issue_reporter,
-                                IssueSeverity::Fatal.cell(),
-                                None,
-                                None,
-                            )
-                            .await;
-                        };
-
-                        Ok(())
-                    },
-                ));
-                ctx.ongoing_side_effects
-                    .lock()
-                    .await
-                    .push_back(Arc::new(Mutex::new(Some(join_handle))));
-            }
+            run_event_handlers(
+                &*ctx.await?,
+                resolved_source,
+                event_name,
+                json!({
+                    "interaction": data.clone(),
+                    "client": client_data(&*ctx.await?, &scope, source).await?,
+                }),
+                issue_reporter,
+                side_effects_reason.clone(),
+            )
+            .await?;
 
             // TODO(kijv) allow users to disable this?
             // Our own event handler
-            if let Some(entry) = command {
+            if let Some(command) = command {
+                let CommandEntry {
+                    entry, definition, ..
+                } = *command.await?;
                 let ctx = ctx.await?;
                 let data = data.clone();
-                let clean_client_data = ctx.clean_client_data.clone();
+                let interaction = interaction.clone();
+                let client = client_data(&ctx, &scope, source).await?;
+                let cooldowns = ctx.cooldowns.clone();
                 let debug = ctx.debug;
                 let config = ctx.config;
 
@@ -321,11 +264,33 @@ pub async fn dispatch(
                             resolved_source.clone().await?.executor.await?.asset_context;
                         let env = resolved_source.clone().await?.executor.await?.env;
 
-                        let clean_client_data = clean_client_data
-                            .try_lock()
-                            .expect("failed to lock `clean_client_data`")
-                            .clone()
-                            .unwrap_or(JsonValue::Object(serde_json::Map::default()));
+                        let cooldown = match definition {
+                            Some(definition) => definition.await?.cooldown.clone(),
+                            None => None,
+                        };
+                        if let (Some(cooldown), Some(command_name)) = (cooldown, &command_name) {
+                            let target = CooldownTarget::of_interaction(&interaction);
+                            if let Some(message) = enforce_cooldown(
+                                &*cooldowns,
+                                config,
+                                command_name,
+                                &cooldown,
+                                target,
+                                entry.ident().path(),
+                            )
+                            .await?
+                            {
+                                return respond_to_interaction(
+                                    &interaction,
+                                    json!({ "content": message, "ephemeral": true }),
+                                    entry,
+                                    issue_reporter,
+                                )
+                                .await;
+                            }
+                        }
+
+                        let options = interaction_options(&interaction, definition).await?;
 
                         let initial_val = evaluate(
                             entry,
@@ -337,7 +302,13 @@ pub async fn dispatch(
                             None,
                             vec![Vc::cell(json!({
                                 "interaction": data.clone(),
-                                "client": clean_client_data
+                                "client": client,
+                                "options": options.options,
+                                "subcommand": options.subcommand,
+                                // The language of the user, and of the guild when it was
+                                // invoked in one
+                                "locale": interaction.locale,
+                                "guildLocale": interaction.guild_locale,
                             }))],
                             resolved_source.get_commands_dir().routes_changed(config),
                             debug,
@@ -360,226 +331,8 @@ pub async fn dispatch(
                             parse_json_with_source_context(val.to_str()?)
                                 .context("Unable to deserialize response")?;
 
-                        let mut payload: Option<InteractionResponse> = None;
-
-                        // TODO(kijv) classes?
-                        // TODO(kijv) make these into functions so Value::Array has no duplicated
-                        // code ? string = pure text message
-                        // ? array = mix of any of the types with meaning (except array)
-                        match initial_return {
-                            JsonValue::String(text) => {
-                                payload = InteractionResponse {
-                                    r#type: InteractionCallbackType::ChannelMessageWithSource,
-                                    data: Some(InteractionCallbackData::Messages(
-                                        InteractionCallbackMessagesData {
-                                            tts: None,
-                                            content: Some(text.into()),
-                                            embeds: None,
-                                            allowed_mentions: None,
-                                            flags: None,
-                                            components: None,
-                                            attachments: None,
-                                        },
-                                    )),
-                                }
-                                .into();
-                            }
-                            JsonValue::Object(map) => {
-                                let value = JsonValue::Object(map);
-
-                                // Lilybird embeds in a format that the Discord API
-                                // already accepts, so we just send this object to
-                                // the response
-                                if let Ok(embed) = serde_json::from_value::<lilybird::Embed>(value)
-                                {
-                                    // Although most values are the same, the accepted value relies
-                                    // on the Turbo engine for
-                                    // caching abilities
-                                    let acceptable_embed = Embed {
-                                        title: embed.title.map(|str| str.into()),
-                                        r#type: embed.r#type.map(|r#type| match r#type {
-                                            lilybird::EmbedType::Rich => EmbedType::Rich,
-                                            lilybird::EmbedType::Image => EmbedType::Image,
-                                            lilybird::EmbedType::Video => EmbedType::Rich,
-                                            lilybird::EmbedType::Gif => EmbedType::Gifv,
-                                            lilybird::EmbedType::Article => EmbedType::Article,
-                                            lilybird::EmbedType::Link => EmbedType::Link,
-                                        }),
-                                        description: embed.description.map(|str| str.into()),
-                                        url: embed.url.map(|str| str.into()),
-                                        timestamp: embed.timestamp.map(|str| str.into()),
-                                        color: embed.color,
-                                        footer: embed
-                                            .footer
-                                            .map(|footer| {
-                                                Some(EmbedFooter {
-                                                    // TODO(kijv) use a helper function to
-                                                    // automatically
-                                                    // transform certain fields
-                                                    text: footer.text.into(),
-                                                    icon_url: footer.icon_url.map(|str| str.into()),
-                                                    proxy_icon_url: footer
-                                                        .proxy_icon_url
-                                                        .map(|str| str.into()),
-                                                })
-                                            })
-                                            .unwrap_or_default(),
-                                        image: embed
-                                            .image
-                                            .map(|image| {
-                                                Some(EmbedImage {
-                                                    url: image.url.into(),
-                                                    proxy_url: image
-                                                        .proxy_url
-                                                        .map(|str| str.into()),
-                                                    height: image.height,
-                                                    width: image.width,
-                                                })
-                                            })
-                                            .unwrap_or_default(),
-                                        thumbnail: embed
-                                            .thumbnail
-                                            .map(|thumbnail| {
-                                                Some(EmbedThumbnail {
-                                                    url: thumbnail.url.into(),
-                                                    proxy_url: thumbnail
-                                                        .proxy_url
-                                                        .map(|str| str.into()),
-                                                    height: thumbnail.height,
-                                                    width: thumbnail.width,
-                                                })
-                                            })
-                                            .unwrap_or_default(),
-                                        video: embed
-                                            .video
-                                            .map(|video| {
-                                                Some(EmbedVideo {
-                                                    // Discord API technically doesn't require the
-                                                    // url
-                                                    // (according to the docs)
-                                                    url: Some(video.url.into()),
-                                                    proxy_url: video
-                                                        .proxy_url
-                                                        .map(|str| str.into()),
-                                                    height: video.height,
-                                                    width: video.width,
-                                                })
-                                            })
-                                            .unwrap_or_default(),
-                                        provider: embed
-                                            .provider
-                                            .map(|provider| {
-                                                Some(EmbedProvider {
-                                                    name: provider.name.map(|str| str.into()),
-                                                    url: provider.url.map(|str| str.into()),
-                                                })
-                                            })
-                                            .unwrap_or_default(),
-                                        author: embed
-                                            .author
-                                            .map(|author| {
-                                                Some(EmbedAuthor {
-                                                    name: author.name.into(),
-                                                    url: author.url.map(|str| str.into()),
-                                                    icon_url: author.icon_url.map(|str| str.into()),
-                                                    proxy_icon_url: author
-                                                        .proxy_icon_url
-                                                        .map(|str| str.into()),
-                                                })
-                                            })
-                                            .unwrap_or_default(),
-                                        fields: embed
-                                            .fields
-                                            .map(|fields| {
-                                                Some(
-                                                    fields
-                                                        .iter()
-                                                        .map(|field| EmbedField {
-                                                            name: field.clone().name.into(),
-                                                            value: field.clone().value.into(),
-                                                            inline: field.inline,
-                                                        })
-                                                        .collect(),
-                                                )
-                                            })
-                                            .unwrap_or_default(),
-                                    };
-                                    payload = InteractionResponse {
-                                        r#type: InteractionCallbackType::ChannelMessageWithSource,
-                                        data: Some(InteractionCallbackData::Messages(
-                                            InteractionCallbackMessagesData {
-                                                tts: None,
-                                                content: None,
-                                                embeds: Some(vec![acceptable_embed]),
-                                                allowed_mentions: None,
-                                                flags: None,
-                                                components: None,
-                                                attachments: None,
-                                            },
-                                        )),
-                                    }
-                                    .into();
-                                }
-                            }
-                            JsonValue::Array(_) => {
-                                RuntimeIssue {
-                                    path: entry.ident().path(),
-                                    severity: Some(IssueSeverity::Error.cell()),
-                                    title: "Failed to parse command response".into(),
-                                    description: Some("Parsing arrays is not supported yet".into()),
-                                }
-                                .cell()
-                                .emit();
-                            }
-                            JsonValue::Null => {}
-                            json_value => {
-                                let r#typeof = match json_value {
-                                    JsonValue::Bool(_) => "boolean",
-                                    JsonValue::Number(_) => "number",
-                                    _ => unreachable!(),
-                                };
-
-                                RuntimeIssue {
-                                    path: entry.ident().path(),
-                                    severity: Some(IssueSeverity::Error.cell()),
-                                    title: "Failed to parse command response".into(),
-                                    description: Some(
-                                        format!(
-                                            "Return value of type {} is not supported",
-                                            r#typeof
-                                        )
-                                        .into(),
-                                    ),
-                                }
-                                .cell()
-                                .emit();
-                            }
-                        };
-
-                        if let Some(payload) = payload {
-                            let interaction_id: InteractionId =
-                                data["id"].as_str().unwrap().parse().unwrap();
-
-                            // should be passed to edit_interaction_response
-                            let error_interaction_response =
-                                discord_api::rest::interactions::create_interaction_response(
-                                    interaction_id.cell(),
-                                    Vc::cell(data["token"].as_str().unwrap().to_string().into()),
-                                    payload.cell(),
-                                    Some(entry.ident().path()),
-                                );
-
-                            return handle_issues(
-                                error_interaction_response,
-                                issue_reporter,
-                                IssueSeverity::Fatal.cell(),
-                                None,
-                                Some("create interaction response"),
-                            )
-                            .await;
-                        }
-
-                        Ok(())
+                        respond_to_interaction(&interaction, initial_return, entry, issue_reporter)
+                            .await
                     },
                 ));
                 ctx.ongoing_side_effects
@@ -594,70 +347,491 @@ pub async fn dispatch(
             let resolved_source = source.resolve_strongly_consistent().await?;
 
             // User-provided event
-            if let Some(event_entry) = get_event_entry(resolved_source, event_name.clone())
-                .await?
-                .as_ref()
-            {
-                let project_path = resolved_source.clone().await?.project_path;
-                let chunking_context = resolved_source
-                    .clone()
-                    .await?
-                    .executor
-                    .await?
-                    .chunking_context;
-                let asset_context = resolved_source.clone().await?.executor.await?.asset_context;
-                let env = resolved_source.clone().await?.executor.await?.env;
-
-                let initial_val = evaluate(
-                    *event_entry,
-                    project_path,
-                    env,
-                    FileSource::new(project_path).ident(),
-                    asset_context,
-                    Vc::upcast(chunking_context),
-                    None,
-                    vec![],
-                    resolved_source
-                        .get_events_dir()
-                        .routes_changed(ctx.await?.config),
-                    ctx.await?.debug,
-                );
-
-                let SingleValue::Single(_) = initial_val.await?.try_into_single().await? else {
-                    // An error happened, which has already been converted into an issue.
-                    handle_issues(
-                        initial_val,
-                        issue_reporter,
-                        IssueSeverity::Fatal.cell(),
-                        None,
-                        Some("parse evaluate response"),
-                    )
-                    .await?;
+            run_event_handlers(
+                &*ctx.await?,
+                resolved_source,
+                event_name,
+                json!({
+                    "data": data,
+                    "client": client_data(&*ctx.await?, &scope, source).await?,
+                }),
+                issue_reporter,
+                side_effects_reason,
+            )
+            .await?;
 
-                    return Ok(Default::default());
+            Ok(Default::default())
+        }
+    }
+}
+
+/// Applies the event to the cache and writes the changes to its backend, the
+/// cache is recreated for every new session
+async fn update_cache(
+    event: &DispatchEvent,
+    source: Vc<ContentSourceData>,
+    ctx: &WebsocketContext,
+) -> Result<()> {
+    // The lock isn't held while the backend is written to, so handlers can read
+    // the cache in the meantime
+    if let DispatchEvent::Ready(_) = event {
+        // The connection to the backend is kept between sessions, only the entities
+        // of the previous session are removed from it
+        let previous = ctx.cache.lock().await.take();
+        let backend = match previous {
+            Some(mut previous) => {
+                previous.clear();
+                if let Some(backend) = &previous.backend {
+                    if let Err(err) = backend.apply(previous.take_changes()).await {
+                        emit_cache_issue(source, "Unable to write to the cache backend", err)
+                            .await?;
+                    }
+                }
+                previous.backend
+            }
+            None => None,
+        };
+        let cache = match &ctx.config.await?.cache {
+            Some(options) => {
+                let backend = match backend {
+                    Some(backend) => Some(backend),
+                    None => match connect_backend(options.backend.as_ref()).await {
+                        Ok(backend) => backend,
+                        Err(err) => {
+                            emit_cache_issue(source, "Unable to connect to the cache backend", err)
+                                .await?;
+                            None
+                        }
+                    },
                 };
+                Some(EntityCache::new(options, backend))
             }
+            None => None,
+        };
+        *ctx.cache.lock().await = cache;
+    }
 
-            Ok(Default::default())
+    let (backend, changes) = {
+        let mut cache = ctx.cache.lock().await;
+        let Some(cache) = cache.as_mut() else {
+            return Ok(());
+        };
+        cache.update(event);
+        (cache.backend.clone(), cache.take_changes())
+    };
+
+    if let Some(backend) = backend {
+        if let Err(err) = backend.apply(changes).await {
+            emit_cache_issue(source, "Unable to write to the cache backend", err).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn emit_cache_issue(
+    source: Vc<ContentSourceData>,
+    title: &str,
+    err: anyhow::Error,
+) -> Result<()> {
+    RuntimeIssue {
+        path: source
+            .resolve_strongly_consistent()
+            .await?
+            .await?
+            .project_path,
+        severity: Some(IssueSeverity::Error.cell()),
+        title: title.into(),
+        description: Some(format!("{err:#}").into()),
+    }
+    .cell()
+    .emit();
+
+    Ok(())
+}
+
+/// Responds to an interaction with the value returned from a command
+async fn respond_to_interaction(
+    interaction: &Interaction,
+    value: JsonValue,
+    entry: Vc<Box<dyn Module>>,
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+) -> Result<()> {
+    let Some(CommandResponse { response, files }) =
+        command_return_to_response(value, entry.ident().path()).await?
+    else {
+        return Ok(());
+    };
+
+    // should be passed to edit_interaction_response
+    let error_interaction_response = discord_api::rest::interactions::create_interaction_response(
+        interaction.id.cell(),
+        Vc::cell(interaction.token.clone()),
+        response.cell(),
+        Vc::cell(files),
+        Some(entry.ident().path()),
+    );
+
+    handle_issues(
+        error_interaction_response,
+        issue_reporter,
+        IssueSeverity::Fatal.cell(),
+        None,
+        Some("create interaction response"),
+    )
+    .await
+}
+
+/// The client as it is passed to handlers: the data of READY, and the cached
+/// entities of the event when the cache is enabled. Entities that aren't in
+/// memory are read from the backend.
+pub(crate) async fn client_data(
+    ctx: &WebsocketContext,
+    scope: &EntityScope,
+    source: Vc<ContentSourceData>,
+) -> Result<JsonValue> {
+    let mut client = ctx
+        .clean_client_data
+        .lock()
+        .await
+        .clone()
+        .unwrap_or(JsonValue::Object(serde_json::Map::default()));
+
+    // The lock isn't held while the backend is read
+    let (mut entities, backend) = match &*ctx.cache.lock().await {
+        Some(cache) => (cache.to_json(scope), cache.backend.clone()),
+        None => return Ok(client),
+    };
+
+    if let Some(backend) = backend {
+        if let Err(err) = read_through(&*backend, scope, &mut entities).await {
+            emit_cache_issue(source, "Unable to read from the cache backend", err).await?;
         }
     }
+
+    if let (JsonValue::Object(client), JsonValue::Object(entities)) = (&mut client, entities) {
+        client.extend(entities);
+    }
+
+    Ok(client)
 }
 
+/// Resolves the options of an application command interaction, using the
+/// definition of the command to include the options that were left out
+async fn interaction_options(
+    interaction: &Interaction,
+    definition: Option<Vc<CommandDefinition>>,
+) -> Result<ResolvedOptions> {
+    let Some(data) = &interaction.data else {
+        return Ok(Default::default());
+    };
+    let resolved = data.resolved.clone().unwrap_or_default();
+
+    let definition = match definition {
+        Some(definition) => {
+            serde_json::from_value::<ApplicationCommand>(definition.await?.command.clone())
+                .ok()
+                .and_then(|command| command.options)
+        }
+        None => None,
+    };
+
+    Ok(resolve_options(
+        &data.options,
+        &resolved,
+        definition.as_deref(),
+    ))
+}
+
+/// A command in `commands/` with its definition, which is evaluated when the
+/// command is first invoked, e.g. to enforce its `cooldown`
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+pub struct CommandEntry {
+    pub entry: Vc<Box<dyn Module>>,
+    pub file_path: Vc<FileSystemPath>,
+    pub definition: Option<Vc<CommandDefinition>>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionCommandEntry(Option<Vc<CommandEntry>>);
+
+/// The command of an interaction, by its name
 #[turbo_tasks::function]
-pub async fn get_event_entry(
+pub async fn get_command_entry(
+    resolved_source: Vc<ContentSourceData>,
+    config: Vc<FujinokiConfig>,
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+    application_id: ApplicationId,
+    command_name: RcStr,
+) -> Result<Vc<OptionCommandEntry>> {
+    let commands = resolved_source.get_commands().await?;
+    let Some(metadata) = commands.iter().find(|command| command.name == command_name) else {
+        return Ok(Vc::cell(None));
+    };
+    let Some(entry) = *resolved_source
+        .get_entries()
+        .get_entry(metadata.file_path.to_string())
+        .await?
+    else {
+        return Ok(Vc::cell(None));
+    };
+
+    // Evaluated like when the commands are registered
+    let definition = DiscordApplicationCommandsUpdater::new(
+        config,
+        resolved_source,
+        issue_reporter,
+        application_id,
+        true,
+        false,
+    )
+    .application_command(
+        metadata.clone().cell(),
+        Vc::cell(Some(entry)),
+        Vc::cell(Some(
+            resolved_source.get_commands_dir().routes_changed(config),
+        )),
+    );
+
+    Ok(Vc::cell(Some(
+        CommandEntry {
+            entry,
+            file_path: metadata.file_path,
+            definition: *definition.await?,
+        }
+        .cell(),
+    )))
+}
+
+/// A handler of an event in `events/`
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+pub struct EventHandler {
+    pub entry: Vc<Box<dyn Module>>,
+    pub path: RcStr,
+    /// Only runs on the first occurrence of the event
+    pub once: bool,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct EventHandlers(Vec<EventHandler>);
+
+/// The handlers of an event in `events/`
+#[turbo_tasks::function]
+pub async fn get_event_entries(
     resolved_source: Vc<ContentSourceData>,
     event_name: RcStr,
-) -> Result<Vc<OptionModule>> {
+) -> Result<Vc<EventHandlers>> {
     let events = resolved_source.get_events().await?;
-    let events = events
-        .iter()
-        .map(|v| (v.name.clone(), v.file_path.clone()))
-        .collect::<BTreeMap<_, _>>();
-    let entries = resolved_source.clone().get_entries();
-
-    if let Some(file_path) = events.get(&event_name) {
-        Ok(Vc::cell(*entries.get_entry(file_path.to_string()).await?))
-    } else {
-        Ok(Vc::cell(None))
+    let entries = resolved_source.get_entries();
+
+    let mut handlers = vec![];
+    for event in events.iter().filter(|event| event.name == event_name) {
+        let path = event.file_path.to_string().await?.clone_value();
+        if let Some(entry) = *entries.get_entry(Vc::cell(path.clone())).await? {
+            handlers.push(EventHandler {
+                entry,
+                path,
+                once: event.once,
+            });
+        }
     }
+
+    Ok(Vc::cell(handlers))
+}
+
+/// Runs the handlers of an event with `payload`. Handlers run concurrently,
+/// unless they export an `order`: lower orders run first, handlers without one
+/// have an order of 0. The errors of a handler are reported on their own and
+/// don't stop the other handlers. `.once` handlers are skipped after their
+/// first run.
+async fn run_event_handlers(
+    ctx: &WebsocketContext,
+    resolved_source: Vc<ContentSourceData>,
+    event_name: RcStr,
+    payload: JsonValue,
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+    side_effects_reason: WebsocketMessageSideEffects,
+) -> Result<()> {
+    let handlers = get_event_entries(resolved_source, event_name).await?;
+    let handlers = {
+        let mut ran_once = ctx.ran_once_handlers.lock().await;
+        handlers
+            .iter()
+            .filter(|handler| !handler.once || ran_once.insert(handler.path.clone()))
+            .map(|handler| handler.entry)
+            .collect::<Vec<_>>()
+    };
+    if handlers.is_empty() {
+        return Ok(());
+    }
+
+    let single = handlers.len() == 1;
+    let debug = ctx.debug;
+    let config = ctx.config;
+
+    let join_handle = tokio::spawn(run_once_with_reason(
+        ctx.turbo_tasks.clone(),
+        side_effects_reason,
+        async move {
+            let invalidation = resolved_source.get_events_dir().routes_changed(config);
+
+            let mut ordered = vec![];
+            for entry in handlers {
+                // The order only matters when there is more than one handler
+                let order = match single {
+                    true => 0.0,
+                    false => handler_order(entry, resolved_source, invalidation).await?,
+                };
+                ordered.push((order, entry));
+            }
+            // Stable, so handlers with the same order keep the order of their files
+            ordered.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+            let mut ordered = ordered.into_iter().peekable();
+            while let Some((order, entry)) = ordered.next() {
+                let mut group = vec![entry];
+                while let Some((_, entry)) = ordered.next_if(|(next, _)| *next == order) {
+                    group.push(entry);
+                }
+
+                let results = join_all(group.iter().map(|entry| {
+                    run_event_handler(
+                        *entry,
+                        resolved_source,
+                        payload.clone(),
+                        invalidation,
+                        issue_reporter,
+                        debug,
+                    )
+                }))
+                .await;
+
+                // A failed handler doesn't stop the other handlers, nor the later orders
+                for (entry, result) in group.into_iter().zip(results) {
+                    if let Err(err) = result {
+                        let issue = RuntimeIssue {
+                            path: entry.ident().path(),
+                            severity: Some(IssueSeverity::Error.cell()),
+                            title: "Event handler failed".into(),
+                            description: Some(format!("{err:#}").into()),
+                        }
+                        .cell();
+                        issue.emit();
+                        handle_issues(
+                            issue,
+                            issue_reporter,
+                            IssueSeverity::Fatal.cell(),
+                            None,
+                            None,
+                        )
+                        .await?;
+                    }
+                }
+            }
+
+            Ok(())
+        },
+    ));
+    ctx.ongoing_side_effects
+        .lock()
+        .await
+        .push_back(Arc::new(Mutex::new(Some(join_handle))));
+
+    Ok(())
+}
+
+/// Runs the handlers of one of fujinoki's own events, which receive `{ data,
+/// client }` like the events Discord dispatches
+pub(crate) async fn dispatch_lifecycle_event(
+    ctx: &WebsocketContext,
+    source: Vc<ContentSourceData>,
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+    event: LifecycleEvent,
+    data: JsonValue,
+) -> Result<()> {
+    let resolved_source = source.resolve_strongly_consistent().await?;
+
+    run_event_handlers(
+        ctx,
+        resolved_source,
+        event.name().into(),
+        json!({
+            "data": data,
+            "client": client_data(ctx, &EntityScope::default(), source).await?,
+        }),
+        issue_reporter,
+        WebsocketMessageSideEffects {
+            opcode: OpCode::Dispatch,
+            event: Some(event.name().to_string()),
+        },
+    )
+    .await
+}
+
+async fn run_event_handler(
+    entry: Vc<Box<dyn Module>>,
+    resolved_source: Vc<ContentSourceData>,
+    payload: JsonValue,
+    invalidation: Vc<Completion>,
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+    debug: bool,
+) -> Result<()> {
+    let project_path = resolved_source.await?.project_path;
+    let executor = resolved_source.await?.executor.await?;
+
+    let evaluated = evaluate(
+        entry,
+        project_path,
+        executor.env,
+        FileSource::new(project_path).ident(),
+        executor.asset_context,
+        Vc::upcast(executor.chunking_context),
+        None,
+        vec![Vc::cell(payload)],
+        invalidation,
+        debug,
+    );
+
+    handle_issues(
+        evaluated,
+        issue_reporter,
+        IssueSeverity::Fatal.cell(),
+        None,
+        Some("evaluate js"),
+    )
+    .await
+}
+
+/// The `order` export of an event handler, 0 when it has none
+async fn handler_order(
+    entry: Vc<Box<dyn Module>>,
+    resolved_source: Vc<ContentSourceData>,
+    additional_invalidation: Vc<Completion>,
+) -> Result<f64> {
+    let project_path = resolved_source.await?.project_path;
+    let executor = resolved_source.await?.executor.await?;
+
+    let exported = custom_evaluate(ExportsContext {
+        args: vec![Vc::cell("order".into())],
+        module_asset: entry,
+        cwd: project_path,
+        env: executor.env,
+        context_ident_for_issue: FileSource::new(project_path).ident(),
+        asset_context: executor.asset_context,
+        chunking_context: Vc::upcast(executor.chunking_context),
+        resolve_options_context: None,
+        additional_invalidation,
+    });
+    let SingleValue::Single(val) = exported.await?.try_into_single().await? else {
+        return Ok(0.0);
+    };
+    let exported: JsonValue =
+        parse_json_with_source_context(val.to_str()?).context("Unable to deserialize exports")?;
+
+    Ok(exported
+        .get("order")
+        .and_then(JsonValue::as_f64)
+        .unwrap_or_default())
 }
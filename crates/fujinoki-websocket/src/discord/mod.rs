@@ -1,10 +1,13 @@
 // TODO move to crate named discord-websocket
 pub mod commands;
+pub mod cooldown;
 pub mod dispatch;
 mod external;
 pub mod heartbeat;
 pub mod identity;
 pub mod issue;
+pub mod prefix;
+pub mod response;
 
 pub use dispatch::dispatch;
 pub use heartbeat::heartbeat;
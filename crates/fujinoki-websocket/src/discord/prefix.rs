@@ -0,0 +1,482 @@
+//! Commands invoked by messages, e.g. `!ban @user "being rude"`. They are the
+//! files of `message-commands/`, and the files of `commands/` that export
+//! `prefix = true`. The default export of a command is called with
+//! `{ message, client, args, prefix, command }` and its return value is sent as
+//! a reply to the message. Like application commands, they can export a
+//! `cooldown`.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use discord_api::{
+    gateway::event::MessageCreateEvent,
+    id::{ChannelId, MessageId, UserId},
+};
+use fujinoki_node::transforms::exports::ExportsContext;
+use serde_json::{json, Map, Value as JsonValue};
+use tokio::sync::Mutex;
+use turbopack_binding::{
+    turbo::{
+        tasks::{run_once_with_reason, Completion, RcStr, ValueToString, Vc},
+        tasks_bytes::stream::SingleValue,
+        tasks_fs::json::parse_json_with_source_context,
+    },
+    turbopack::{
+        core::{
+            file_source::FileSource,
+            issue::{handle_issues, IssueReporter, IssueSeverity},
+            module::Module,
+            source::Source,
+        },
+        node::evaluate::{custom_evaluate, evaluate},
+    },
+};
+
+use super::{
+    cooldown::{enforce_cooldown, CooldownTarget},
+    dispatch::client_data,
+    response::command_return_to_response,
+};
+use crate::{
+    cache::EntityScope,
+    invalidation::WebsocketMessageSideEffects,
+    source::{ContentSource, ContentSourceData},
+    WebsocketContext,
+};
+
+/// A message that invokes a command
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrefixInvocation {
+    /// The prefix that was used, a mention of the bot is `@mention`
+    pub prefix: String,
+    /// Lowercased, the way the files are named
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+/// Parses the content of a message, returns `None` when it doesn't start with
+/// one of the prefixes or a mention of the bot
+pub fn parse_invocation(
+    content: &str,
+    prefixes: &[RcStr],
+    mention: Option<UserId>,
+) -> Option<PrefixInvocation> {
+    let (prefix, rest) = strip_prefix(content.trim_start(), prefixes, mention)?;
+    let mut args = split_arguments(rest).into_iter();
+    let command = args.next()?.to_lowercase();
+
+    Some(PrefixInvocation {
+        prefix,
+        command,
+        args: args.collect(),
+    })
+}
+
+fn strip_prefix<'a>(
+    content: &'a str,
+    prefixes: &[RcStr],
+    mention: Option<UserId>,
+) -> Option<(String, &'a str)> {
+    if let Some(user_id) = mention {
+        for mention in [format!("<@{user_id}>"), format!("<@!{user_id}>")] {
+            if let Some(rest) = content.strip_prefix(&mention) {
+                return Some(("@mention".to_string(), rest.trim_start()));
+            }
+        }
+    }
+
+    // The longest prefix wins, so `!!` isn't read as `!` followed by `!`
+    let mut prefixes = prefixes
+        .iter()
+        .filter(|prefix| !prefix.is_empty())
+        .collect::<Vec<_>>();
+    prefixes.sort_by_key(|prefix| std::cmp::Reverse(prefix.len()));
+
+    prefixes.into_iter().find_map(|prefix| {
+        content
+            .strip_prefix(prefix.as_str())
+            .map(|rest| (prefix.to_string(), rest))
+    })
+}
+
+/// Splits the arguments on whitespace, text between double or single quotes is
+/// a single argument. Inside quotes a backslash escapes the next character, an
+/// unclosed quote runs until the end.
+pub fn split_arguments(input: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut current = String::new();
+    // Whether the current argument exists, as `""` is an empty argument
+    let mut in_argument = false;
+    let mut quote = None;
+    let mut chars = input.chars();
+
+    while let Some(char) = chars.next() {
+        match (quote, char) {
+            (Some(_), '\\') => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            (Some(open), char) if char == open => quote = None,
+            (Some(_), char) => current.push(char),
+            (None, '"' | '\'') if !in_argument => {
+                quote = Some(char);
+                in_argument = true;
+            }
+            (None, char) if char.is_whitespace() => {
+                if in_argument {
+                    args.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            (None, char) => {
+                current.push(char);
+                in_argument = true;
+            }
+        }
+    }
+    if in_argument {
+        args.push(current);
+    }
+
+    args
+}
+
+/// Runs the command a message invokes, when prefix commands are enabled
+pub async fn dispatch_prefix_command(
+    event: &MessageCreateEvent,
+    data: &Map<String, JsonValue>,
+    scope: &EntityScope,
+    source: Vc<ContentSourceData>,
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+    ctx: Vc<WebsocketContext>,
+    side_effects_reason: WebsocketMessageSideEffects,
+) -> Result<()> {
+    let ctx = ctx.await?;
+    let options = ctx.config.prefix_commands().await?;
+    let Some(options) = &*options else {
+        return Ok(());
+    };
+    // Bots (including this one) can't invoke commands
+    if event
+        .message
+        .author
+        .as_ref()
+        .map_or(true, |author| author.bot.unwrap_or(false))
+    {
+        return Ok(());
+    }
+
+    let client = client_data(&ctx, scope, source).await?;
+    let bot_id = options
+        .mention()
+        .then(|| serde_json::from_value::<UserId>(client["user"]["id"].clone()).ok())
+        .flatten();
+    let Some(invocation) = parse_invocation(&event.message.content, &options.prefixes(), bot_id)
+    else {
+        return Ok(());
+    };
+
+    let resolved_source = source.resolve_strongly_consistent().await?;
+    let entries = resolved_source.get_entries();
+    let message_command = resolved_source
+        .get_message_commands()
+        .await?
+        .iter()
+        .find(|command| command.name.as_str() == invocation.command)
+        .map(|command| command.file_path);
+    // `message-commands/` take precedence, the commands of `commands/` still need
+    // to export `prefix`
+    let (file_path, requires_export) = match message_command {
+        Some(file_path) => (file_path, false),
+        None => {
+            let commands = resolved_source.get_commands().await?;
+            let Some(command) = commands
+                .iter()
+                .find(|command| command.name.as_str() == invocation.command)
+            else {
+                return Ok(());
+            };
+            (command.file_path, true)
+        }
+    };
+    let Some(entry) = *entries.get_entry(file_path.to_string()).await? else {
+        return Ok(());
+    };
+
+    let message_id = event.message.id;
+    let channel_id = event.message.channel_id;
+    let target = CooldownTarget {
+        user_id: event.message.author.as_ref().map(|author| author.id),
+        guild_id: event.guild_id,
+        channel_id: Some(channel_id),
+    };
+    let cooldowns = ctx.cooldowns.clone();
+    let data = data.clone();
+    let token = ctx.config.client().token().await?.clone_value();
+    let debug = ctx.debug;
+    let config = ctx.config;
+
+    let join_handle = tokio::spawn(run_once_with_reason(
+        ctx.turbo_tasks.clone(),
+        side_effects_reason,
+        async move {
+            let project_path = resolved_source.await?.project_path;
+            let executor = resolved_source.await?.executor.await?;
+            let commands_dir = if requires_export {
+                resolved_source.get_commands_dir()
+            } else {
+                resolved_source.get_message_commands_dir()
+            };
+
+            let exports =
+                command_exports(entry, resolved_source, commands_dir.routes_changed(config))
+                    .await?;
+            if requires_export
+                && matches!(
+                    exports.get("prefix"),
+                    None | Some(JsonValue::Null | JsonValue::Bool(false))
+                )
+            {
+                return Ok(());
+            }
+
+            if let Some(cooldown) = exports.get("cooldown").filter(|value| !value.is_null()) {
+                if let Some(message) = enforce_cooldown(
+                    &*cooldowns,
+                    config,
+                    &invocation.command,
+                    cooldown,
+                    target,
+                    entry.ident().path(),
+                )
+                .await?
+                {
+                    return reply(
+                        json!({ "content": message }),
+                        message_id,
+                        channel_id,
+                        token,
+                        entry,
+                        issue_reporter,
+                    )
+                    .await;
+                }
+            }
+
+            let initial_val = evaluate(
+                entry,
+                project_path,
+                executor.env,
+                FileSource::new(project_path).ident(),
+                executor.asset_context,
+                Vc::upcast(executor.chunking_context),
+                None,
+                vec![Vc::cell(json!({
+                    "message": data,
+                    "client": client,
+                    "args": invocation.args,
+                    "prefix": invocation.prefix,
+                    "command": invocation.command,
+                }))],
+                commands_dir.routes_changed(config),
+                debug,
+            );
+
+            let SingleValue::Single(val) = initial_val.await?.try_into_single().await? else {
+                // An error happened, which has already been converted into an issue.
+                return handle_issues(
+                    initial_val,
+                    issue_reporter,
+                    IssueSeverity::Fatal.cell(),
+                    None,
+                    None,
+                )
+                .await;
+            };
+            let initial_return: JsonValue = parse_json_with_source_context(val.to_str()?)
+                .context("Unable to deserialize response")?;
+
+            reply(
+                initial_return,
+                message_id,
+                channel_id,
+                token,
+                entry,
+                issue_reporter,
+            )
+            .await
+        },
+    ));
+    ctx.ongoing_side_effects
+        .lock()
+        .await
+        .push_back(Arc::new(Mutex::new(Some(join_handle))));
+
+    Ok(())
+}
+
+/// Replies to the message with the value returned from a command
+async fn reply(
+    value: JsonValue,
+    message_id: MessageId,
+    channel_id: ChannelId,
+    token: RcStr,
+    entry: Vc<Box<dyn Module>>,
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+) -> Result<()> {
+    let Some(response) = command_return_to_response(value, entry.ident().path()).await? else {
+        return Ok(());
+    };
+    let (message, files) = response.into_reply(message_id)?;
+
+    let reply = discord_api::rest::channel::create_message(
+        channel_id,
+        &message,
+        &files,
+        &token,
+        Some(entry.ident().path()),
+    )
+    .await?;
+    // Failed requests emit an issue in this task, which is reported along with
+    // the cell
+    let replied: Vc<bool> = Vc::cell(reply.is_some());
+
+    handle_issues(
+        replied,
+        issue_reporter,
+        IssueSeverity::Fatal.cell(),
+        None,
+        Some("reply to prefix command"),
+    )
+    .await
+}
+
+/// The `prefix` and `cooldown` exports of a command. The commands of
+/// `commands/` need `prefix` to opt into being invoked by prefix.
+async fn command_exports(
+    entry: Vc<Box<dyn Module>>,
+    resolved_source: Vc<ContentSourceData>,
+    additional_invalidation: Vc<Completion>,
+) -> Result<JsonValue> {
+    let project_path = resolved_source.await?.project_path;
+    let executor = resolved_source.await?.executor.await?;
+
+    let exported = custom_evaluate(ExportsContext {
+        args: vec![Vc::cell("prefix".into()), Vc::cell("cooldown".into())],
+        module_asset: entry,
+        cwd: project_path,
+        env: executor.env,
+        context_ident_for_issue: FileSource::new(project_path).ident(),
+        asset_context: executor.asset_context,
+        chunking_context: Vc::upcast(executor.chunking_context),
+        resolve_options_context: None,
+        additional_invalidation,
+    });
+    let SingleValue::Single(val) = exported.await?.try_into_single().await? else {
+        return Ok(JsonValue::Null);
+    };
+
+    parse_json_with_source_context(val.to_str()?).context("Unable to deserialize exports")
+}
+
+#[cfg(test)]
+mod tests {
+    use discord_api::id::UserId;
+    use turbopack_binding::turbo::tasks::RcStr;
+
+    use super::{parse_invocation, split_arguments, PrefixInvocation};
+
+    fn invocation(prefix: &str, command: &str, args: &[&str]) -> Option<PrefixInvocation> {
+        Some(PrefixInvocation {
+            prefix: prefix.to_string(),
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn test_split_arguments() {
+        assert_eq!(split_arguments("  a   b\tc  "), vec!["a", "b", "c"]);
+        assert_eq!(split_arguments(""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_split_quoted_arguments() {
+        assert_eq!(
+            split_arguments(r#"say "hello world" 'single quoted' end"#),
+            vec!["say", "hello world", "single quoted", "end"]
+        );
+        // Quotes inside an argument are kept
+        assert_eq!(split_arguments("it's fine"), vec!["it's", "fine"]);
+    }
+
+    #[test]
+    fn test_split_empty_arguments() {
+        assert_eq!(split_arguments(r#"a "" b"#), vec!["a", "", "b"]);
+        assert_eq!(split_arguments("''"), vec![""]);
+    }
+
+    #[test]
+    fn test_split_unclosed_quote() {
+        assert_eq!(split_arguments(r#"a "b  c"#), vec!["a", "b  c"]);
+        assert_eq!(split_arguments(r#"a ""#), vec!["a", ""]);
+    }
+
+    #[test]
+    fn test_split_escapes() {
+        assert_eq!(
+            split_arguments(r#""say \"hi\"" 'it\'s' "\\""#),
+            vec![r#"say "hi""#, "it's", r"\"]
+        );
+        // Backslashes are only escapes inside quotes
+        assert_eq!(split_arguments(r"a\b"), vec![r"a\b"]);
+    }
+
+    #[test]
+    fn test_parse_invocation() {
+        let prefixes: Vec<RcStr> = vec!["!".into()];
+
+        assert_eq!(
+            parse_invocation("  !Ping a \"b c\"", &prefixes, None),
+            invocation("!", "ping", &["a", "b c"])
+        );
+        assert_eq!(parse_invocation("ping", &prefixes, None), None);
+        // A prefix without a command
+        assert_eq!(parse_invocation("!  ", &prefixes, None), None);
+    }
+
+    #[test]
+    fn test_parse_longest_prefix() {
+        let prefixes: Vec<RcStr> = vec!["!".into(), "!!".into(), "".into()];
+
+        assert_eq!(
+            parse_invocation("!!ping", &prefixes, None),
+            invocation("!!", "ping", &[])
+        );
+        assert_eq!(
+            parse_invocation("!ping", &prefixes, None),
+            invocation("!", "ping", &[])
+        );
+        // Empty prefixes are ignored, so not every message is a command
+        assert_eq!(parse_invocation("ping", &prefixes, None), None);
+    }
+
+    #[test]
+    fn test_parse_mention_prefix() {
+        let prefixes: Vec<RcStr> = vec!["!".into()];
+        let bot_id = Some(UserId(123));
+
+        assert_eq!(
+            parse_invocation("<@123> ping a", &prefixes, bot_id),
+            invocation("@mention", "ping", &["a"])
+        );
+        assert_eq!(
+            parse_invocation("<@!123>ping", &prefixes, bot_id),
+            invocation("@mention", "ping", &[])
+        );
+        // Mentions of other users, or when mentions are disabled
+        assert_eq!(parse_invocation("<@456> ping", &prefixes, bot_id), None);
+        assert_eq!(parse_invocation("<@123> ping", &prefixes, None), None);
+    }
+}
@@ -0,0 +1,613 @@
+use anyhow::Result;
+use discord_api::{
+    channel::{
+        embed::{
+            Embed, EmbedAuthor, EmbedField, EmbedFooter, EmbedImage, EmbedProvider, EmbedThumbnail,
+            EmbedType, EmbedVideo,
+        },
+        message::MessageFlags,
+        AllowedMentionTypes, AllowedMentions, AttachmentForMessageCreateOrEdit,
+    },
+    id::{AttachmentId, MessageId, RoleId, UserId},
+    interactions::{
+        InteractionCallbackData, InteractionCallbackMessagesData, InteractionCallbackType,
+        InteractionResponse,
+    },
+    rest::http::HttpFile,
+};
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use turbopack_binding::{
+    turbo::{
+        tasks::Vc,
+        tasks_fs::{FileContent, FileSystemPath},
+    },
+    turbopack::core::issue::{IssueExt, IssueSeverity},
+};
+
+use super::{external::lilybird, issue::RuntimeIssue};
+
+/// Keys that mark a returned object as message options rather than an embed
+const MESSAGE_OPTION_KEYS: [&str; 7] = [
+    "content",
+    "embeds",
+    "files",
+    "tts",
+    "ephemeral",
+    "suppressEmbeds",
+    "allowedMentions",
+];
+
+/// A message returned from a command handler, e.g.
+/// `{ content: "pong", ephemeral: true }`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommandMessage {
+    content: Option<String>,
+    #[serde(default)]
+    embeds: Vec<JsonValue>,
+    #[serde(default)]
+    files: Vec<CommandFile>,
+    tts: Option<bool>,
+    #[serde(default)]
+    ephemeral: bool,
+    #[serde(default)]
+    suppress_embeds: bool,
+    allowed_mentions: Option<CommandAllowedMentions>,
+}
+
+/// A file attached to a command's response. Paths are relative to the command
+/// file.
+///
+/// * `"./image.png"`
+/// * `Buffer`
+/// * `{ attachment: "./image.png" | Buffer, name?, description? }`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum CommandFile {
+    Source(CommandFileSource),
+    Attachment {
+        attachment: CommandFileSource,
+        name: Option<String>,
+        description: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum CommandFileSource {
+    Path(String),
+    Buffer(NodeBuffer),
+}
+
+/// The `JSON.stringify` representation of a Node.js `Buffer`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type")]
+enum NodeBuffer {
+    Buffer { data: Vec<u8> },
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommandAllowedMentions {
+    parse: Option<Vec<AllowedMentionTypes>>,
+    #[serde(default)]
+    users: Vec<UserId>,
+    #[serde(default)]
+    roles: Vec<RoleId>,
+    #[serde(default)]
+    replied_user: bool,
+}
+
+impl From<CommandAllowedMentions> for AllowedMentions {
+    fn from(value: CommandAllowedMentions) -> Self {
+        // Discord rejects a mention type that is both parsed and given as a list of
+        // ids, so explicit ids take precedence over the default parse types
+        let parse = value.parse.unwrap_or_else(|| {
+            AllowedMentions::default()
+                .parse
+                .into_iter()
+                .filter(|r#type| match r#type {
+                    AllowedMentionTypes::Users => value.users.is_empty(),
+                    AllowedMentionTypes::Roles => value.roles.is_empty(),
+                    AllowedMentionTypes::Everyone => false,
+                })
+                .collect()
+        });
+
+        AllowedMentions {
+            parse,
+            roles: value.roles,
+            users: value.users,
+            replied_user: value.replied_user,
+        }
+    }
+}
+
+/// An interaction response along with the files that are uploaded with it
+pub struct CommandResponse {
+    pub response: InteractionResponse,
+    pub files: Vec<HttpFile>,
+}
+
+impl CommandResponse {
+    /// The response as a message replying to `message_id`, which is how prefix
+    /// commands respond. Messages can't be ephemeral, so that flag is dropped.
+    pub fn into_reply(self, message_id: MessageId) -> Result<(JsonValue, Vec<HttpFile>)> {
+        let mut data = match self.response.data {
+            Some(InteractionCallbackData::Messages(data)) => data,
+            None => Default::default(),
+        };
+        data.flags = data
+            .flags
+            .map(|flags| flags - MessageFlags::EPHEMERAL)
+            .filter(|flags| !flags.is_empty());
+
+        let mut message = serde_json::to_value(data)?;
+        message["message_reference"] = json!({
+            "message_id": message_id,
+            // The message may have been deleted in the meantime
+            "fail_if_not_exists": false,
+        });
+
+        Ok((message, self.files))
+    }
+}
+
+/// Transforms the value returned from a command handler into an interaction
+/// response, emitting an issue for the command file when it can't be used.
+///
+/// * string = pure text message
+/// * object = message options (`content`, `embeds`, `files`, `ephemeral`,
+///   `allowedMentions`, `suppressEmbeds`, `tts`) or a single embed
+pub async fn command_return_to_response(
+    value: JsonValue,
+    path: Vc<FileSystemPath>,
+) -> Result<Option<CommandResponse>> {
+    let mut message = match parse_command_message(value) {
+        Ok(Some(message)) => message,
+        Ok(None) => return Ok(None),
+        Err(description) => {
+            emit_response_issue(path, description);
+            return Ok(None);
+        }
+    };
+    let command_embeds = std::mem::take(&mut message.embeds);
+    let command_files = std::mem::take(&mut message.files);
+
+    let mut embeds = vec![];
+    for embed in command_embeds {
+        // Lilybird embeds in a format that the Discord API already accepts
+        match serde_json::from_value::<lilybird::Embed>(embed) {
+            Ok(embed) => embeds.push(lilybird_embed_to_embed(embed)),
+            Err(err) => {
+                emit_response_issue(path, format!("Unsupported embed: {err}"));
+                return Ok(None);
+            }
+        }
+    }
+
+    let mut files = vec![];
+    let mut attachments = vec![];
+    for (index, file) in command_files.into_iter().enumerate() {
+        let (source, name, description) = match file {
+            CommandFile::Source(source) => (source, None, None),
+            CommandFile::Attachment {
+                attachment,
+                name,
+                description,
+            } => (attachment, name, description),
+        };
+
+        let (content, filename) = match source {
+            CommandFileSource::Buffer(NodeBuffer::Buffer { data }) => (data, None),
+            CommandFileSource::Path(request) => {
+                let Some(file_path) = *path.parent().try_join(request.clone().into()).await? else {
+                    emit_response_issue(path, format!("File {request} is outside of the project"));
+                    return Ok(None);
+                };
+                let FileContent::Content(file) = &*file_path.read().await? else {
+                    emit_response_issue(path, format!("File {request} could not be found"));
+                    return Ok(None);
+                };
+
+                (
+                    file.content().to_bytes()?.to_vec(),
+                    Some(file_path.await?.file_name().to_string()),
+                )
+            }
+        };
+        let Some(filename) = name.or(filename) else {
+            emit_response_issue(path, "A name is required for Buffer files".to_string());
+            return Ok(None);
+        };
+
+        attachments.push(AttachmentForMessageCreateOrEdit {
+            id: AttachmentId(index as u64),
+            filename: Some(filename.clone().into()),
+            description: description.map(|str| str.into()),
+            content_type: None,
+            size: None,
+            url: None,
+            proxy_url: None,
+            height: None,
+            width: None,
+            ephemeral: None,
+            duration_secs: None,
+            waveform: None,
+            flags: None,
+        });
+        files.push(HttpFile {
+            filename: filename.into(),
+            content_type: None,
+            content,
+        });
+    }
+
+    let mut data = message_data(message);
+    data.embeds = (!embeds.is_empty()).then_some(embeds);
+    data.attachments = (!attachments.is_empty()).then_some(attachments);
+
+    let response = InteractionResponse {
+        r#type: InteractionCallbackType::ChannelMessageWithSource,
+        data: Some(InteractionCallbackData::Messages(data)),
+    };
+
+    Ok(Some(CommandResponse { response, files }))
+}
+
+/// Reads the message options out of a handler's return value, the error is the
+/// description of the issue to emit
+fn parse_command_message(value: JsonValue) -> Result<Option<CommandMessage>, String> {
+    // TODO(kijv) classes?
+    // ? array = mix of any of the types with meaning (except array)
+    match value {
+        JsonValue::String(text) => Ok(Some(CommandMessage {
+            content: Some(text),
+            ..Default::default()
+        })),
+        JsonValue::Object(map) => {
+            if MESSAGE_OPTION_KEYS.iter().any(|key| map.contains_key(*key)) {
+                serde_json::from_value::<CommandMessage>(JsonValue::Object(map))
+                    .map(Some)
+                    .map_err(|err| format!("Invalid message options: {err}"))
+            } else {
+                Ok(Some(CommandMessage {
+                    embeds: vec![JsonValue::Object(map)],
+                    ..Default::default()
+                }))
+            }
+        }
+        JsonValue::Array(_) => Err("Parsing arrays is not supported yet".to_string()),
+        JsonValue::Null => Ok(None),
+        json_value => {
+            let r#typeof = match json_value {
+                JsonValue::Bool(_) => "boolean",
+                JsonValue::Number(_) => "number",
+                _ => unreachable!(),
+            };
+
+            Err(format!(
+                "Return value of type {} is not supported",
+                r#typeof
+            ))
+        }
+    }
+}
+
+/// The message data of `message`, without its embeds and files
+fn message_data(message: CommandMessage) -> InteractionCallbackMessagesData {
+    let mut flags = MessageFlags::empty();
+    if message.ephemeral {
+        flags |= MessageFlags::EPHEMERAL;
+    }
+    if message.suppress_embeds {
+        flags |= MessageFlags::SUPPRESS_EMBEDS;
+    }
+
+    InteractionCallbackMessagesData {
+        tts: message.tts,
+        content: message.content.map(|content| content.into()),
+        embeds: None,
+        allowed_mentions: Some(
+            message
+                .allowed_mentions
+                .map(AllowedMentions::from)
+                .unwrap_or_default(),
+        ),
+        flags: (!flags.is_empty()).then_some(flags),
+        components: None,
+        attachments: None,
+    }
+}
+
+fn emit_response_issue(path: Vc<FileSystemPath>, description: String) {
+    RuntimeIssue {
+        path,
+        severity: Some(IssueSeverity::Error.cell()),
+        title: "Failed to parse command response".into(),
+        description: Some(description.into()),
+    }
+    .cell()
+    .emit();
+}
+
+// Although most values are the same, the accepted value relies on the Turbo
+// engine for caching abilities
+fn lilybird_embed_to_embed(embed: lilybird::Embed) -> Embed {
+    Embed {
+        title: embed.title.map(|str| str.into()),
+        r#type: embed.r#type.map(|r#type| match r#type {
+            lilybird::EmbedType::Rich => EmbedType::Rich,
+            lilybird::EmbedType::Image => EmbedType::Image,
+            lilybird::EmbedType::Video => EmbedType::Rich,
+            lilybird::EmbedType::Gif => EmbedType::Gifv,
+            lilybird::EmbedType::Article => EmbedType::Article,
+            lilybird::EmbedType::Link => EmbedType::Link,
+        }),
+        description: embed.description.map(|str| str.into()),
+        url: embed.url.map(|str| str.into()),
+        timestamp: embed.timestamp.map(|str| str.into()),
+        color: embed.color,
+        footer: embed.footer.map(|footer| EmbedFooter {
+            // TODO(kijv) use a helper function to automatically transform certain
+            // fields
+            text: footer.text.into(),
+            icon_url: footer.icon_url.map(|str| str.into()),
+            proxy_icon_url: footer.proxy_icon_url.map(|str| str.into()),
+        }),
+        image: embed.image.map(|image| EmbedImage {
+            url: image.url.into(),
+            proxy_url: image.proxy_url.map(|str| str.into()),
+            height: image.height,
+            width: image.width,
+        }),
+        thumbnail: embed.thumbnail.map(|thumbnail| EmbedThumbnail {
+            url: thumbnail.url.into(),
+            proxy_url: thumbnail.proxy_url.map(|str| str.into()),
+            height: thumbnail.height,
+            width: thumbnail.width,
+        }),
+        video: embed.video.map(|video| EmbedVideo {
+            // Discord API technically doesn't require the url (according to the docs)
+            url: Some(video.url.into()),
+            proxy_url: video.proxy_url.map(|str| str.into()),
+            height: video.height,
+            width: video.width,
+        }),
+        provider: embed.provider.map(|provider| EmbedProvider {
+            name: provider.name.map(|str| str.into()),
+            url: provider.url.map(|str| str.into()),
+        }),
+        author: embed.author.map(|author| EmbedAuthor {
+            name: author.name.into(),
+            url: author.url.map(|str| str.into()),
+            icon_url: author.icon_url.map(|str| str.into()),
+            proxy_icon_url: author.proxy_icon_url.map(|str| str.into()),
+        }),
+        fields: embed.fields.map(|fields| {
+            fields
+                .iter()
+                .map(|field| EmbedField {
+                    name: field.clone().name.into(),
+                    value: field.clone().value.into(),
+                    inline: field.inline,
+                })
+                .collect()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use discord_api::{
+        channel::message::MessageFlags,
+        id::MessageId,
+        interactions::{InteractionCallbackData, InteractionCallbackType, InteractionResponse},
+    };
+    use serde_json::{json, Value as JsonValue};
+
+    use super::{
+        message_data, parse_command_message, CommandFile, CommandFileSource, CommandMessage,
+        CommandResponse, NodeBuffer,
+    };
+
+    fn message(value: JsonValue) -> CommandMessage {
+        parse_command_message(value).unwrap().unwrap()
+    }
+
+    fn flags(value: JsonValue) -> Option<u32> {
+        message_data(message(value)).flags.map(|flags| flags.bits())
+    }
+
+    fn allowed_mentions(value: JsonValue) -> JsonValue {
+        serde_json::to_value(message_data(message(value)).allowed_mentions).unwrap()
+    }
+
+    #[test]
+    fn test_text_message() {
+        let data = message_data(message(json!("pong")));
+
+        assert_eq!(data.content.as_deref(), Some("pong"));
+        assert_eq!(data.flags, None);
+        assert_eq!(data.tts, None);
+    }
+
+    #[test]
+    fn test_single_embed() {
+        let message = message(json!({ "title": "Hello", "description": "World" }));
+
+        assert_eq!(message.content, None);
+        assert_eq!(
+            message.embeds,
+            vec![json!({ "title": "Hello", "description": "World" })]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_values() {
+        assert!(parse_command_message(JsonValue::Null).unwrap().is_none());
+        assert_eq!(
+            parse_command_message(json!([])).unwrap_err(),
+            "Parsing arrays is not supported yet"
+        );
+        assert_eq!(
+            parse_command_message(json!(true)).unwrap_err(),
+            "Return value of type boolean is not supported"
+        );
+        assert_eq!(
+            parse_command_message(json!(1)).unwrap_err(),
+            "Return value of type number is not supported"
+        );
+        assert!(parse_command_message(json!({ "ephemeral": "yes" }))
+            .unwrap_err()
+            .starts_with("Invalid message options"));
+    }
+
+    #[test]
+    fn test_flags() {
+        let ephemeral = MessageFlags::EPHEMERAL.bits();
+        let suppress_embeds = MessageFlags::SUPPRESS_EMBEDS.bits();
+
+        assert_eq!(flags(json!({ "content": "a" })), None);
+        assert_eq!(
+            flags(json!({ "content": "a", "ephemeral": false, "suppressEmbeds": false })),
+            None
+        );
+        assert_eq!(
+            flags(json!({ "content": "a", "ephemeral": true })),
+            Some(ephemeral)
+        );
+        assert_eq!(
+            flags(json!({ "content": "a", "suppressEmbeds": true })),
+            Some(suppress_embeds)
+        );
+        assert_eq!(
+            flags(json!({ "content": "a", "ephemeral": true, "suppressEmbeds": true })),
+            Some(ephemeral | suppress_embeds)
+        );
+    }
+
+    #[test]
+    fn test_allowed_mentions() {
+        // @everyone and @here are never parsed by default
+        assert_eq!(
+            allowed_mentions(json!("hi @everyone")),
+            json!({ "parse": ["users", "roles"], "replied_user": false })
+        );
+        assert_eq!(
+            allowed_mentions(json!({ "content": "a", "allowedMentions": {} })),
+            json!({ "parse": ["users", "roles"], "replied_user": false })
+        );
+        // Explicit ids replace the parse type of the same kind
+        assert_eq!(
+            allowed_mentions(json!({
+                "content": "a",
+                "allowedMentions": { "users": ["1"], "repliedUser": true },
+            })),
+            json!({ "parse": ["roles"], "users": ["1"], "replied_user": true })
+        );
+        assert_eq!(
+            allowed_mentions(json!({
+                "content": "a",
+                "allowedMentions": { "roles": ["2"] },
+            })),
+            json!({ "parse": ["users"], "roles": ["2"], "replied_user": false })
+        );
+        assert_eq!(
+            allowed_mentions(json!({
+                "content": "a",
+                "allowedMentions": { "parse": ["everyone"] },
+            })),
+            json!({ "parse": ["everyone"], "replied_user": false })
+        );
+        assert_eq!(
+            allowed_mentions(json!({
+                "content": "a",
+                "allowedMentions": { "parse": [] },
+            })),
+            json!({ "parse": [], "replied_user": false })
+        );
+    }
+
+    #[test]
+    fn test_files() {
+        let message = message(json!({
+            "files": [
+                "./image.png",
+                { "type": "Buffer", "data": [1, 2, 3] },
+                {
+                    "attachment": { "type": "Buffer", "data": [4] },
+                    "name": "data.bin",
+                    "description": "Some data",
+                },
+                { "attachment": "./other.png" },
+            ],
+        }));
+
+        let [path, buffer, attachment, path_attachment] = &message.files[..] else {
+            panic!("expected 4 files, got {:?}", message.files);
+        };
+        assert!(matches!(
+            path,
+            CommandFile::Source(CommandFileSource::Path(path)) if path == "./image.png"
+        ));
+        assert!(matches!(
+            buffer,
+            CommandFile::Source(CommandFileSource::Buffer(NodeBuffer::Buffer { data }))
+                if data == &[1, 2, 3]
+        ));
+        assert!(matches!(
+            attachment,
+            CommandFile::Attachment {
+                attachment: CommandFileSource::Buffer(NodeBuffer::Buffer { data }),
+                name: Some(name),
+                description: Some(description),
+            } if data == &[4] && name == "data.bin" && description == "Some data"
+        ));
+        assert!(matches!(
+            path_attachment,
+            CommandFile::Attachment {
+                attachment: CommandFileSource::Path(path),
+                name: None,
+                description: None,
+            } if path == "./other.png"
+        ));
+
+        // Only the `JSON.stringify` form of a Buffer is accepted
+        assert!(parse_command_message(json!({ "files": [{ "data": [1] }] })).is_err());
+        assert!(parse_command_message(json!({ "files": [[1, 2]] })).is_err());
+    }
+
+    #[test]
+    fn test_reply_drops_ephemeral() {
+        let reply = |value| {
+            CommandResponse {
+                response: InteractionResponse {
+                    r#type: InteractionCallbackType::ChannelMessageWithSource,
+                    data: Some(InteractionCallbackData::Messages(message_data(message(
+                        value,
+                    )))),
+                },
+                files: vec![],
+            }
+            .into_reply(MessageId(10))
+            .unwrap()
+            .0
+        };
+
+        let reply_message = reply(json!({ "content": "a", "ephemeral": true }));
+        assert_eq!(reply_message.get("flags"), None);
+        assert_eq!(
+            reply_message["message_reference"]["fail_if_not_exists"],
+            false
+        );
+
+        let reply_message =
+            reply(json!({ "content": "a", "ephemeral": true, "suppressEmbeds": true }));
+        assert_eq!(
+            reply_message["flags"],
+            json!(MessageFlags::SUPPRESS_EMBEDS.bits())
+        );
+    }
+}
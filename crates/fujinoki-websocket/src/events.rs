@@ -2,8 +2,9 @@ use std::{sync::Arc, time::Duration};
 
 use anyhow::{anyhow, Result};
 use discord_api::gateway::{OpCode, Payload};
+use fujinoki_core::structures::events::LifecycleEvent;
 use futures::{SinkExt, StreamExt};
-use serde_json::json;
+use serde_json::{json, Value as JsonValue};
 use tokio_tungstenite::tungstenite::{protocol::CloseFrame, Message};
 use tracing::{event, Level, Span};
 use turbopack_binding::{
@@ -16,7 +17,7 @@ use url::Url;
 use super::WebsocketContext;
 use crate::{
     connect_to_gateway,
-    discord::{self, dispatch},
+    discord::{self, dispatch, dispatch::dispatch_lifecycle_event, issue::RuntimeIssue},
     invalidation::WebsocketMessage,
     issue::WebsocketIssue,
     source::ContentSourceSideEffect,
@@ -113,6 +114,15 @@ impl WebsocketEvents {
                 }
                 // TODO redo (match djs?)
                 OpCode::Reconnect => {
+                    dispatch_lifecycle_event(
+                        &ctx,
+                        source_provider.get_source(),
+                        get_issue_reporter(),
+                        LifecycleEvent::Reconnecting,
+                        json!({ "reason": "Discord requested a reconnect" }),
+                    )
+                    .await?;
+
                     let resume_gateway_url = ctx
                         .resume_gateway_url
                         .try_lock()
@@ -160,6 +170,15 @@ impl WebsocketEvents {
                         .unwrap_or(false);
 
                     if should_reconnect {
+                        dispatch_lifecycle_event(
+                            &ctx,
+                            source_provider.get_source(),
+                            get_issue_reporter(),
+                            LifecycleEvent::Reconnecting,
+                            json!({ "reason": "The session was invalidated" }),
+                        )
+                        .await?;
+
                         let resume_gateway_url = ctx
                             .resume_gateway_url
                             .try_lock()
@@ -226,6 +245,21 @@ impl WebsocketEvents {
                         .expect("failed to lock `heartbeat_ack`");
 
                     *heartbeat_ack = true;
+                    drop(heartbeat_ack);
+
+                    let latency = ctx
+                        .last_heartbeat
+                        .try_lock()
+                        .expect("failed to lock `last_heartbeat`")
+                        .elapsed();
+                    dispatch_lifecycle_event(
+                        &ctx,
+                        source_provider.get_source(),
+                        get_issue_reporter(),
+                        LifecycleEvent::Heartbeat,
+                        json!({ "latency": latency.as_millis() as u64 }),
+                    )
+                    .await?;
                 }
                 _ => {
                     dbg!(data);
@@ -247,6 +281,13 @@ impl WebsocketEvents {
                 let reconnect_codes = vec![4000, 4001, 4002, 4003, 4005, 4007, 4008, 4009];
 
                 if reconnect_codes.contains(&message.code.into()) {
+                    self.lifecycle_event(
+                        source_provider,
+                        LifecycleEvent::Reconnecting,
+                        json!({ "reason": message.reason.to_string(), "code": u16::from(message.code) }),
+                    )
+                    .await?;
+
                     let resume_gateway_url = self
                         .ctx
                         .resume_gateway_url
@@ -303,6 +344,13 @@ impl WebsocketEvents {
                         false => message.reason.to_string(),
                     };
 
+                    self.lifecycle_event(
+                        source_provider.clone(),
+                        LifecycleEvent::Disconnect,
+                        json!({ "reason": reason, "code": u16::from(message.code) }),
+                    )
+                    .await?;
+
                     let get_issue_reporter = self.get_issue_reporter.clone();
                     let source_provider = source_provider.clone();
 
@@ -344,6 +392,13 @@ impl WebsocketEvents {
                 }
             }
             None => {
+                self.lifecycle_event(
+                    source_provider.clone(),
+                    LifecycleEvent::Disconnect,
+                    json!({ "reason": null, "code": null }),
+                )
+                .await?;
+
                 let get_issue_reporter = self.get_issue_reporter.clone();
                 let source_provider = source_provider.clone();
 
@@ -385,4 +440,104 @@ impl WebsocketEvents {
             }
         }
     }
+
+    /// Reports an error that ends the connection to the `FUJINOKI_ERROR`
+    /// handlers
+    pub async fn error(
+        &self,
+        source_provider: impl SourceProvider + Sync,
+        message: String,
+    ) -> Result<()> {
+        self.lifecycle_event(
+            source_provider,
+            LifecycleEvent::Error,
+            json!({ "message": message }),
+        )
+        .await
+    }
+
+    /// Runs the handlers of one of fujinoki's own events outside of a gateway
+    /// message
+    async fn lifecycle_event(
+        &self,
+        source_provider: impl SourceProvider + Sync,
+        event: LifecycleEvent,
+        data: JsonValue,
+    ) -> Result<()> {
+        let ctx = self.ctx.clone();
+        let get_issue_reporter = self.get_issue_reporter.clone();
+        let handlers_source_provider = source_provider.clone();
+
+        // The errors of the handlers are only reported, so they don't replace the error
+        // that caused the event
+        let result = run_once(self.tt.clone(), async move {
+            dispatch_lifecycle_event(
+                &ctx,
+                handlers_source_provider.get_source(),
+                get_issue_reporter(),
+                event,
+                data,
+            )
+            .await
+        })
+        .await;
+        if let Err(err) = result {
+            self.report_failure(
+                source_provider.clone(),
+                format!("Unable to run the {} handlers", event.name()),
+                err,
+            )
+            .await?;
+        }
+
+        // The connection is gone after these, so their handlers have to finish first
+        if matches!(event, LifecycleEvent::Disconnect | LifecycleEvent::Error) {
+            if let Err(err) = self.ctx.settle_side_effects().await {
+                self.report_failure(
+                    source_provider,
+                    format!("A {} handler failed", event.name()),
+                    err,
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reports an error of a lifecycle event that happened outside of a task
+    async fn report_failure(
+        &self,
+        source_provider: impl SourceProvider + Sync,
+        title: String,
+        err: anyhow::Error,
+    ) -> Result<()> {
+        let get_issue_reporter = self.get_issue_reporter.clone();
+
+        run_once(self.tt.clone(), async move {
+            let issue = RuntimeIssue {
+                path: source_provider
+                    .get_source()
+                    .resolve_strongly_consistent()
+                    .await?
+                    .await?
+                    .project_path,
+                severity: Some(IssueSeverity::Error.cell()),
+                title: title.into(),
+                description: Some(format!("{err:#}").into()),
+            }
+            .cell();
+            issue.emit();
+
+            handle_issues(
+                issue,
+                get_issue_reporter(),
+                IssueSeverity::Fatal.cell(),
+                None,
+                None,
+            )
+            .await
+        })
+        .await
+    }
 }
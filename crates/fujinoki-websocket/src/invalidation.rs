@@ -3,7 +3,7 @@ use std::fmt::{Display, Formatter};
 use discord_api::gateway::{OpCode, OpCodeName};
 use indexmap::IndexSet;
 use turbopack_binding::turbo::tasks::{
-    util::StaticOrArc, InvalidationReason, InvalidationReasonKind,
+    util::StaticOrArc, InvalidationReason, InvalidationReasonKind, RcStr,
 };
 
 /// Computation was caused by a event from the gateway.
@@ -137,3 +137,47 @@ impl InvalidationReasonKind for WebsocketMessageSideEffectsKind {
         )
     }
 }
+
+/// Side effect that was caused by a scheduled task of `tasks/`.
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct TaskSideEffects {
+    pub task: RcStr,
+}
+
+impl InvalidationReason for TaskSideEffects {
+    fn kind(&self) -> Option<StaticOrArc<dyn InvalidationReasonKind>> {
+        Some(StaticOrArc::Static(&TASK_SIDE_EFFECTS_KIND))
+    }
+}
+
+impl Display for TaskSideEffects {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "side effects of task {}", self.task)
+    }
+}
+
+/// Invalidation kind for [TaskSideEffects]
+#[derive(PartialEq, Eq, Hash)]
+struct TaskSideEffectsKind;
+
+static TASK_SIDE_EFFECTS_KIND: TaskSideEffectsKind = TaskSideEffectsKind;
+
+impl InvalidationReasonKind for TaskSideEffectsKind {
+    fn fmt(
+        &self,
+        reasons: &IndexSet<StaticOrArc<dyn InvalidationReason>>,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        let example = reasons
+            .into_iter()
+            .map(|reason| reason.as_any().downcast_ref::<TaskSideEffects>().unwrap())
+            .min_by_key(|reason| reason.task.len())
+            .unwrap();
+        write!(
+            f,
+            "side effects of {} tasks ({}, ...)",
+            reasons.len(),
+            example.task
+        )
+    }
+}
@@ -1,7 +1,11 @@
 #![feature(arbitrary_self_types)]
 #![feature(async_closure)]
 
-use std::{collections::VecDeque, sync::Arc, time::Duration};
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::{Context, Result};
 use discord_api::gateway::{OpCode, Payload, PayloadToString};
@@ -26,7 +30,7 @@ use tokio_tungstenite::{
 use turbopack_binding::{
     turbo::{
         tasks as turbo_tasks,
-        tasks::{run_once, TurboTasksApi, Vc},
+        tasks::{run_once, RcStr, TurboTasksApi, Vc},
     },
     turbopack::{
         core::issue::{handle_issues, IssueExt, IssueReporter, IssueSeverity},
@@ -36,12 +40,20 @@ use turbopack_binding::{
 };
 use url::Url;
 
-use crate::{events::WebsocketEvents, issue::WebsocketIssue};
+use crate::{
+    cache::EntityCache,
+    discord::cooldown::{CooldownStore, MemoryCooldownStore},
+    events::WebsocketEvents,
+    issue::WebsocketIssue,
+    scheduler::{SchedulerEvent, SchedulerLog, TaskScheduler},
+};
 
+pub mod cache;
 pub mod discord;
 mod events;
 pub mod invalidation;
 pub mod issue;
+pub mod scheduler;
 pub mod source;
 mod util;
 
@@ -103,12 +115,57 @@ pub struct WebsocketContext {
     pub resume_gateway_url: Arc<Mutex<Option<String>>>,
     #[turbo_tasks(trace_ignore, debug_ignore)]
     pub clean_client_data: Arc<Mutex<Option<JsonValue>>>,
+    /// Only set when the cache is enabled in the config
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    pub cache: Arc<Mutex<Option<EntityCache>>>,
+    /// The paths of the `.once` event handlers that already ran
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    pub ran_once_handlers: Arc<Mutex<HashSet<RcStr>>>,
+    /// Running cooldowns of commands, see [`Websocket::cooldown_store`]
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    pub cooldowns: Arc<dyn CooldownStore>,
+}
+
+impl WebsocketContext {
+    /// Waits until all ongoing side effects are completed
+    pub(crate) async fn settle_side_effects(&self) -> Result<()> {
+        // We only need to wait for the ongoing side effects that were started
+        // before this request. Later added side effects are not relevant for this.
+        let current_ongoing_side_effects = {
+            // Cleanup the ongoing_side_effects list
+            let mut guard = self.ongoing_side_effects.lock().await;
+            while let Some(front) = guard.front() {
+                let Ok(front_guard) = front.try_lock() else {
+                    break;
+                };
+                if front_guard.is_some() {
+                    break;
+                }
+                drop(front_guard);
+                guard.pop_front();
+            }
+            // Get a clone of the remaining list
+            (*guard).clone()
+        };
+        // Wait for the side effects to complete
+        for side_effect_mutex in current_ongoing_side_effects {
+            let mut guard = side_effect_mutex.lock().await;
+            if let Some(join_handle) = guard.take() {
+                join_handle.await??;
+            }
+            drop(guard);
+        }
+
+        Ok(())
+    }
 }
 
 pub struct Websocket {
     tt: Arc<dyn TurboTasksApi>,
     get_issue_reporter: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync>,
     config: Vc<FujinokiConfig>,
+    cooldowns: Arc<dyn CooldownStore>,
+    scheduler_log: SchedulerLog,
 }
 
 impl Websocket {
@@ -121,9 +178,25 @@ impl Websocket {
             tt,
             get_issue_reporter,
             config,
+            cooldowns: Arc::new(MemoryCooldownStore::new()),
+            scheduler_log: Arc::new(|_: SchedulerEvent| {}),
         }
     }
 
+    /// Where the cooldowns of commands are kept, defaults to the memory of this
+    /// process
+    pub fn cooldown_store(mut self, cooldowns: Arc<dyn CooldownStore>) -> Self {
+        self.cooldowns = cooldowns;
+        self
+    }
+
+    /// Receives when the tasks of `tasks/` run next, nothing is logged by
+    /// default
+    pub fn scheduler_log(mut self, log: SchedulerLog) -> Self {
+        self.scheduler_log = log;
+        self
+    }
+
     // TODO use turbo_tasks span macros for better trace logs
     pub async fn serve(
         self,
@@ -148,6 +221,9 @@ impl Websocket {
             last_heartbeat: Arc::new(Mutex::new(Instant::now())),
             heartbeat_ack: Arc::new(Mutex::new(true)),
             clean_client_data: Arc::new(Mutex::new(None)),
+            cache: Arc::new(Mutex::new(None)),
+            ran_once_handlers: Arc::new(Mutex::new(HashSet::new())),
+            cooldowns: self.cooldowns.clone(),
         };
         // TODO rename `events` to `message_handler` (same with struct name)
         let events = WebsocketEvents::new(self.tt.clone(), ctx.clone(), get_issue_reporter);
@@ -186,7 +262,17 @@ impl Websocket {
             })
         };
 
-        self.serve_inner(source_provider, ctx, events).await
+        // The tasks of `tasks/` run next to the gateway
+        let scheduler = TaskScheduler::new(
+            ctx.clone(),
+            self.get_issue_reporter.clone(),
+            self.scheduler_log.clone(),
+        )
+        .run(source_provider.clone());
+
+        futures::future::try_join(self.serve_inner(source_provider, ctx, events), scheduler)
+            .await
+            .map(|_| ())
     }
 
     async fn serve_inner(
@@ -196,35 +282,14 @@ impl Websocket {
         events: WebsocketEvents,
     ) -> Result<()> {
         loop {
-            // Wait until all ongoing side effects are completed
-            // We only need to wait for the ongoing side effects that were started
-            // before this request. Later added side effects are not relevant for this.
-            let current_ongoing_side_effects = {
-                // Cleanup the ongoing_side_effects list
-                let mut guard = ctx.ongoing_side_effects.lock().await;
-                while let Some(front) = guard.front() {
-                    let Ok(front_guard) = front.try_lock() else {
-                        break;
-                    };
-                    if front_guard.is_some() {
-                        break;
-                    }
-                    drop(front_guard);
-                    guard.pop_front();
-                }
-                // Get a clone of the remaining list
-                (*guard).clone()
-            };
-            // Wait for the side effects to complete
-            for side_effect_mutex in current_ongoing_side_effects {
-                let mut guard = side_effect_mutex.lock().await;
-                if let Some(join_handle) = guard.take() {
-                    join_handle.await??;
-                }
-                drop(guard);
-            }
+            ctx.settle_side_effects().await?;
 
-            discord::heartbeat(ctx.clone(), false).await?;
+            if let Err(err) = discord::heartbeat(ctx.clone(), false).await {
+                events
+                    .error(source_provider.clone(), err.to_string())
+                    .await?;
+                return Err(err);
+            }
 
             let read = ctx.api.read.clone();
             let mut read = read.try_lock().expect("failed to lock `read` stream");
@@ -246,6 +311,8 @@ impl Websocket {
                     ).await?,
                     Ok(Message::Frame(_)) => todo!("Message::Frame"),
                     Err(err) => {
+                        events.error(source_provider.clone(), err.to_string()).await?;
+
                         let get_issue_reporter = self.get_issue_reporter.clone();
                         let source_provider = source_provider.clone();
 
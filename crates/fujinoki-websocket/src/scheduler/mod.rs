@@ -0,0 +1,429 @@
+//! Runs the tasks of `tasks/` on the `schedule` they export. The default export
+//! of a task is called with `{ client, task }`, once the bot is ready.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result};
+use fujinoki_core::structures::tasks::TaskMetadata;
+use fujinoki_node::transforms::exports::ExportsContext;
+use serde_json::{json, Value as JsonValue};
+use tokio::{sync::Mutex, task::JoinHandle, time::sleep};
+use turbopack_binding::{
+    turbo::{
+        tasks::{run_once, run_once_with_reason, Completion, RcStr, ValueToString, Vc},
+        tasks_bytes::stream::SingleValue,
+        tasks_fs::json::parse_json_with_source_context,
+    },
+    turbopack::{
+        core::{
+            file_source::FileSource,
+            issue::{handle_issues, IssueExt, IssueReporter, IssueSeverity},
+            module::Module,
+            source::Source,
+        },
+        node::evaluate::{custom_evaluate, evaluate},
+    },
+};
+
+use self::schedule::{format_utc, Schedule};
+use crate::{
+    cache::EntityScope,
+    discord::{dispatch::client_data, issue::RuntimeIssue},
+    invalidation::TaskSideEffects,
+    source::{ContentSource, ContentSourceData},
+    SourceProvider, WebsocketContext,
+};
+
+pub mod schedule;
+
+/// How often the tasks are checked, which is also how late a run can be
+const TICK: Duration = Duration::from_secs(1);
+
+/// Receives what the scheduler has to say that isn't an issue
+pub type SchedulerLog = Arc<dyn Fn(SchedulerEvent) + Send + Sync>;
+
+#[derive(Clone, Debug)]
+pub enum SchedulerEvent {
+    NextRun {
+        task: RcStr,
+        at: SystemTime,
+    },
+    NeverRuns {
+        task: RcStr,
+    },
+    /// The tasks couldn't be checked and the error couldn't be reported as an
+    /// issue either
+    Failed {
+        error: String,
+    },
+}
+
+impl fmt::Display for SchedulerEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NextRun { task, at } => {
+                write!(f, "task `{task}` runs next at {}", format_utc(*at))
+            }
+            Self::NeverRuns { task } => write!(f, "task `{task}` is never going to run"),
+            Self::Failed { error } => write!(f, "unable to run the scheduled tasks: {error}"),
+        }
+    }
+}
+
+pub struct TaskScheduler {
+    ctx: WebsocketContext,
+    get_issue_reporter: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync>,
+    log: SchedulerLog,
+    scheduled: Arc<Mutex<HashMap<RcStr, ScheduledTask>>>,
+}
+
+struct ScheduledTask {
+    /// The `schedule` export, the task is rescheduled when it changes
+    export: JsonValue,
+    /// `None` when the export is invalid
+    schedule: Option<Schedule>,
+    next_run: Option<SystemTime>,
+    running: Option<JoinHandle<Result<()>>>,
+}
+
+impl TaskScheduler {
+    pub fn new(
+        ctx: WebsocketContext,
+        get_issue_reporter: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync>,
+        log: SchedulerLog,
+    ) -> Self {
+        Self {
+            ctx,
+            get_issue_reporter,
+            log,
+            scheduled: Default::default(),
+        }
+    }
+
+    pub async fn run(self, source_provider: impl SourceProvider + Sync) -> Result<()> {
+        loop {
+            sleep(TICK).await;
+
+            // Tasks need the client, which is only known once the bot is ready
+            if self.ctx.clean_client_data.lock().await.is_none() {
+                continue;
+            }
+
+            let ctx = self.ctx.clone();
+            let get_issue_reporter = self.get_issue_reporter.clone();
+            let source_provider = source_provider.clone();
+            let log = self.log.clone();
+            let scheduled = self.scheduled.clone();
+
+            // Errors are reported and the next tick tries again, they don't stop the
+            // gateway
+            let result = run_once(self.ctx.turbo_tasks.clone(), async move {
+                let source = source_provider.get_source();
+                if let Err(err) =
+                    tick(ctx, source, get_issue_reporter.clone(), log, scheduled).await
+                {
+                    report_issue(
+                        RuntimeIssue {
+                            path: source
+                                .resolve_strongly_consistent()
+                                .await?
+                                .await?
+                                .project_path,
+                            severity: Some(IssueSeverity::Error.cell()),
+                            title: "Unable to run the scheduled tasks".into(),
+                            description: Some(format!("{err:#}").into()),
+                        },
+                        get_issue_reporter(),
+                    )
+                    .await?;
+                }
+
+                Ok(())
+            })
+            .await;
+            if let Err(err) = result {
+                (self.log)(SchedulerEvent::Failed {
+                    error: format!("{err:#}"),
+                });
+            }
+        }
+    }
+}
+
+/// Schedules new and changed tasks, and starts the ones that are due
+async fn tick(
+    ctx: WebsocketContext,
+    source: Vc<ContentSourceData>,
+    get_issue_reporter: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync>,
+    log: SchedulerLog,
+    scheduled: Arc<Mutex<HashMap<RcStr, ScheduledTask>>>,
+) -> Result<()> {
+    let resolved_source = source.resolve_strongly_consistent().await?;
+    let tasks = resolved_source.get_tasks().await?;
+    let entries = resolved_source.get_entries();
+    let tasks_dir = resolved_source.get_tasks_dir();
+    let now = SystemTime::now();
+
+    let mut scheduled = scheduled.lock().await;
+    // The files of these tasks were removed
+    scheduled.retain(|name, _| tasks.iter().any(|task| task.name == *name));
+
+    for task in tasks.iter() {
+        let Some(entry) = *entries.get_entry(task.file_path.to_string()).await? else {
+            continue;
+        };
+        let export =
+            schedule_export(entry, resolved_source, tasks_dir.routes_changed(ctx.config)).await?;
+
+        let changed = scheduled
+            .get(&task.name)
+            .map_or(true, |scheduled_task| scheduled_task.export != export);
+        if changed {
+            let schedule = match Schedule::parse(&export) {
+                Ok(schedule) => Some(schedule),
+                Err(err) => {
+                    report_issue(
+                        RuntimeIssue {
+                            path: task.file_path,
+                            severity: Some(IssueSeverity::Error.cell()),
+                            title: format!("Invalid schedule of task `{}`", task.name).into(),
+                            description: Some(err.into()),
+                        },
+                        get_issue_reporter(),
+                    )
+                    .await?;
+                    None
+                }
+            };
+            let next_run = schedule
+                .as_ref()
+                .and_then(|schedule| schedule.next_after(now));
+            log_next_run(&log, &task.name, schedule.as_ref(), next_run);
+
+            // A run of the previous schedule keeps running
+            let running = scheduled
+                .remove(&task.name)
+                .and_then(|scheduled_task| scheduled_task.running);
+            scheduled.insert(
+                task.name.clone(),
+                ScheduledTask {
+                    export,
+                    schedule,
+                    next_run,
+                    running,
+                },
+            );
+            continue;
+        }
+        let Some(scheduled_task) = scheduled.get_mut(&task.name) else {
+            continue;
+        };
+
+        let (Some(schedule), Some(next_run)) = (&scheduled_task.schedule, scheduled_task.next_run)
+        else {
+            continue;
+        };
+        if next_run > now {
+            continue;
+        }
+        // Runs that were missed, e.g. while the machine was asleep, aren't caught up on
+        scheduled_task.next_run = schedule.next_after(now);
+
+        match scheduled_task.running.take() {
+            Some(running) if !running.is_finished() => {
+                scheduled_task.running = Some(running);
+                report_issue(
+                    RuntimeIssue {
+                        path: task.file_path,
+                        severity: Some(IssueSeverity::Warning.cell()),
+                        title: format!("Task `{}` is still running", task.name).into(),
+                        description: Some(
+                            format!(
+                                "The run at {} was skipped, as the previous run hasn't finished \
+                                 yet",
+                                format_utc(next_run)
+                            )
+                            .into(),
+                        ),
+                    },
+                    get_issue_reporter(),
+                )
+                .await?;
+            }
+            previous => {
+                // The run reports its own errors, this only fails when it panicked or its
+                // errors couldn't be reported
+                let previous = match previous {
+                    Some(previous) => previous
+                        .await
+                        .map_err(anyhow::Error::from)
+                        .and_then(|result| result),
+                    None => Ok(()),
+                };
+                if let Err(err) = previous {
+                    report_issue(
+                        RuntimeIssue {
+                            path: task.file_path,
+                            severity: Some(IssueSeverity::Error.cell()),
+                            title: format!("Task `{}` failed", task.name).into(),
+                            description: Some(format!("{err:#}").into()),
+                        },
+                        get_issue_reporter(),
+                    )
+                    .await?;
+                }
+                scheduled_task.running = Some(
+                    spawn_task(&ctx, task, entry, resolved_source, get_issue_reporter()).await?,
+                );
+            }
+        }
+
+        log_next_run(&log, &task.name, Some(schedule), scheduled_task.next_run);
+    }
+
+    Ok(())
+}
+
+/// Runs a task in the background, errors thrown by the task are reported as
+/// issues
+async fn spawn_task(
+    ctx: &WebsocketContext,
+    task: &TaskMetadata,
+    entry: Vc<Box<dyn Module>>,
+    resolved_source: Vc<ContentSourceData>,
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+) -> Result<JoinHandle<Result<()>>> {
+    // Tasks don't happen in a guild, so they only see the data of READY
+    let client = client_data(ctx, &EntityScope::default(), resolved_source).await?;
+    let turbo_tasks = ctx.turbo_tasks.clone();
+    let config = ctx.config;
+    let debug = ctx.debug;
+    let name = task.name.clone();
+    let file_path = task.file_path;
+
+    let reason = TaskSideEffects { task: name.clone() };
+    Ok(tokio::spawn(run_once_with_reason(
+        turbo_tasks,
+        reason,
+        async move {
+            let run = async {
+                let project_path = resolved_source.await?.project_path;
+                let executor = resolved_source.await?.executor.await?;
+
+                let initial_val = evaluate(
+                    entry,
+                    project_path,
+                    executor.env,
+                    FileSource::new(project_path).ident(),
+                    executor.asset_context,
+                    Vc::upcast(executor.chunking_context),
+                    None,
+                    vec![Vc::cell(json!({
+                        "client": client,
+                        "task": name,
+                    }))],
+                    resolved_source.get_tasks_dir().routes_changed(config),
+                    debug,
+                );
+
+                let SingleValue::Single(_) = initial_val.await?.try_into_single().await? else {
+                    // An error happened, which has already been converted into an issue.
+                    return handle_issues(
+                        initial_val,
+                        issue_reporter,
+                        IssueSeverity::Fatal.cell(),
+                        None,
+                        None,
+                    )
+                    .await;
+                };
+
+                anyhow::Ok(())
+            };
+
+            // A failed run doesn't stop the scheduler, the next run is tried as usual
+            if let Err(err) = run.await {
+                report_issue(
+                    RuntimeIssue {
+                        path: file_path,
+                        severity: Some(IssueSeverity::Error.cell()),
+                        title: format!("Task `{name}` failed").into(),
+                        description: Some(format!("{err:#}").into()),
+                    },
+                    issue_reporter,
+                )
+                .await?;
+            }
+
+            Ok(())
+        },
+    )))
+}
+
+/// The `schedule` export of a task
+async fn schedule_export(
+    entry: Vc<Box<dyn Module>>,
+    resolved_source: Vc<ContentSourceData>,
+    additional_invalidation: Vc<Completion>,
+) -> Result<JsonValue> {
+    let project_path = resolved_source.await?.project_path;
+    let executor = resolved_source.await?.executor.await?;
+
+    let exported = custom_evaluate(ExportsContext {
+        args: vec![Vc::cell("schedule".into())],
+        module_asset: entry,
+        cwd: project_path,
+        env: executor.env,
+        context_ident_for_issue: FileSource::new(project_path).ident(),
+        asset_context: executor.asset_context,
+        chunking_context: Vc::upcast(executor.chunking_context),
+        resolve_options_context: None,
+        additional_invalidation,
+    });
+    let SingleValue::Single(val) = exported.await?.try_into_single().await? else {
+        return Ok(JsonValue::Null);
+    };
+    let exported: JsonValue =
+        parse_json_with_source_context(val.to_str()?).context("Unable to deserialize exports")?;
+
+    Ok(exported.get("schedule").cloned().unwrap_or_default())
+}
+
+async fn report_issue(
+    issue: RuntimeIssue,
+    issue_reporter: Vc<Box<dyn IssueReporter>>,
+) -> Result<()> {
+    let issue = issue.cell();
+    issue.emit();
+
+    handle_issues(
+        issue,
+        issue_reporter,
+        IssueSeverity::Fatal.cell(),
+        None,
+        None,
+    )
+    .await
+}
+
+fn log_next_run(
+    log: &SchedulerLog,
+    name: &RcStr,
+    schedule: Option<&Schedule>,
+    next_run: Option<SystemTime>,
+) {
+    match (schedule, next_run) {
+        (Some(_), Some(at)) => log(SchedulerEvent::NextRun {
+            task: name.clone(),
+            at,
+        }),
+        (Some(_), None) => log(SchedulerEvent::NeverRuns { task: name.clone() }),
+        // The issue was already reported
+        (None, _) => {}
+    }
+}
@@ -0,0 +1,280 @@
+//! The `schedule` export of a task, either a cron expression or an interval,
+//! e.g. `"0 9 * * mon-fri"`, `"@hourly"`, `"1h30m"` or `5000` (milliseconds).
+//! Cron expressions are evaluated in UTC.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value as JsonValue;
+
+const MINUTES_PER_DAY: u64 = 24 * 60;
+/// How far ahead a cron expression is searched, an expression like
+/// `0 0 30 2 *` never matches
+const MAX_DAYS_AHEAD: u64 = 366 * 5;
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Schedule {
+    Interval(Duration),
+    Cron(CronSchedule),
+}
+
+impl Schedule {
+    pub fn parse(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Number(number) => number
+                .as_f64()
+                .filter(|millis| *millis >= 1.0)
+                .and_then(|millis| Duration::try_from_secs_f64(millis / 1000.0).ok())
+                .map(Schedule::Interval)
+                .ok_or_else(|| format!("`{number}` is not a positive number of milliseconds")),
+            JsonValue::String(value) => {
+                let value = value.trim();
+                if value.starts_with('@') || value.contains(char::is_whitespace) {
+                    CronSchedule::parse(value).map(Schedule::Cron)
+                } else {
+                    parse_interval(value).map(Schedule::Interval)
+                }
+            }
+            JsonValue::Null => Err("a task must export a `schedule`".to_string()),
+            _ => Err(
+                "`schedule` must be a cron expression, an interval like `5m` or a number of \
+                 milliseconds"
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// The first run after `after`, where `after` is the previous run or the
+    /// moment the task was scheduled
+    pub fn next_after(&self, after: SystemTime) -> Option<SystemTime> {
+        match self {
+            Schedule::Interval(interval) => after.checked_add(*interval),
+            Schedule::Cron(cron) => cron.next_after(after),
+        }
+    }
+}
+
+/// Parses intervals like `30s`, `5m` or `1h30m`, the units are `ms`, `s`, `m`,
+/// `h` and `d`
+fn parse_interval(value: &str) -> Result<Duration, String> {
+    let invalid = || format!("`{value}` is not a valid interval, e.g. `30s`, `5m` or `1h30m`");
+
+    let mut total = Duration::ZERO;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|char: char| !char.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let amount: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+
+        let unit = rest
+            .find(|char: char| char.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let millis = match &rest[..unit] {
+            "ms" => 1,
+            "s" => 1000,
+            "m" => 60 * 1000,
+            "h" => 60 * 60 * 1000,
+            "d" => 24 * 60 * 60 * 1000,
+            _ => return Err(invalid()),
+        };
+        rest = &rest[unit..];
+
+        let millis = amount.checked_mul(millis).ok_or_else(invalid)?;
+        total = total
+            .checked_add(Duration::from_millis(millis))
+            .ok_or_else(invalid)?;
+    }
+
+    if total.is_zero() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+/// A cron expression with the fields `minute hour day-of-month month
+/// day-of-week`. Each field is a bit set of the values it matches.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// When both days are restricted a day matches either of them, like in
+    /// crontab
+    days_restricted: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = match expression {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            expression if expression.starts_with('@') => {
+                return Err(format!("`{expression}` is not a known cron macro"))
+            }
+            expression => expression,
+        };
+
+        let fields = expression.split_whitespace().collect::<Vec<_>>();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!(
+                "`{expression}` must have 5 fields: minute, hour, day of month, month and day of \
+                 week"
+            ));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, "day of week", 0, 7, &WEEKDAYS)?;
+        // Both 0 and 7 are sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(CronSchedule {
+            minutes: parse_field(minute, "minute", 0, 59, &[])?,
+            hours: parse_field(hour, "hour", 0, 23, &[])?,
+            days_of_month: parse_field(day_of_month, "day of month", 1, 31, &[])?,
+            months: parse_field(month, "month", 1, 12, &MONTHS)?,
+            days_of_week,
+            days_restricted: !day_of_month.starts_with('*') && !day_of_week.starts_with('*'),
+        })
+    }
+
+    /// The first minute after `after` that matches
+    pub fn next_after(&self, after: SystemTime) -> Option<SystemTime> {
+        let minute = after.duration_since(UNIX_EPOCH).ok()?.as_secs() / 60 + 1;
+        let (mut day, mut minute_of_day) = (minute / MINUTES_PER_DAY, minute % MINUTES_PER_DAY);
+
+        for _ in 0..MAX_DAYS_AHEAD {
+            if self.matches_day(day) {
+                for minute_of_day in minute_of_day..MINUTES_PER_DAY {
+                    if has(self.hours, minute_of_day / 60) && has(self.minutes, minute_of_day % 60)
+                    {
+                        let minutes = day * MINUTES_PER_DAY + minute_of_day;
+                        return Some(UNIX_EPOCH + Duration::from_secs(minutes * 60));
+                    }
+                }
+            }
+            day += 1;
+            minute_of_day = 0;
+        }
+
+        None
+    }
+
+    fn matches_day(&self, days_since_epoch: u64) -> bool {
+        let (_, month, day) = civil_from_days(days_since_epoch);
+        // 1970-01-01 was a thursday
+        let weekday = (days_since_epoch + 4) % 7;
+
+        if !has(self.months, month) {
+            return false;
+        }
+        let day_of_month = has(self.days_of_month, day);
+        let day_of_week = has(self.days_of_week, weekday);
+        if self.days_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+fn has(set: u64, value: u64) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Parses a field of a cron expression, a list of `*`, values and ranges, each
+/// with an optional step, e.g. `*/15`, `1-5` or `mon,wed,fri`
+fn parse_field(field: &str, name: &str, min: u64, max: u64, names: &[&str]) -> Result<u64, String> {
+    let invalid =
+        || format!("`{field}` is not a valid {name}, expected values from {min} to {max}");
+    let value = |value: &str| -> Result<u64, String> {
+        let value = value.to_lowercase();
+        let value = match names.iter().position(|name| *name == value) {
+            // Names start at the minimum, e.g. `jan` is 1 and `sun` is 0
+            Some(index) => index as u64 + min,
+            None => value.parse().map_err(|_| invalid())?,
+        };
+        if (min..=max).contains(&value) {
+            Ok(value)
+        } else {
+            Err(invalid())
+        }
+    };
+
+    let mut set = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u64>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(invalid()),
+            },
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/15` runs from 5 until the maximum
+                None if part.contains('/') => (value(range)?, max),
+                None => {
+                    let value = value(range)?;
+                    (value, value)
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+
+    Ok(set)
+}
+
+/// Converts days since the unix epoch into a `(year, month, day)` date, see
+/// <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Formats a time as e.g. `2024-05-01 09:00:00 UTC`
+pub fn format_utc(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (year, month, day) = civil_from_days(seconds / 86_400);
+    let seconds_of_day = seconds % 86_400;
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60
+    )
+}
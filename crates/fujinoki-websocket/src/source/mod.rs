@@ -6,6 +6,7 @@ use anyhow::Result;
 use fujinoki_core::structures::{
     commands::{CommandsMetadata, OptionCommandsDir},
     events::{EventsMetadata, OptionEventsDir},
+    tasks::{OptionTasksDir, TasksMetadata},
 };
 use turbopack_binding::{
     turbo::{
@@ -42,6 +43,24 @@ pub trait ContentSource {
         OptionCommandsDir::default().cell()
     }
 
+    /// The commands of `message-commands/`, which are only invoked by prefix
+    fn get_message_commands(self: Vc<Self>) -> Vc<CommandsMetadata> {
+        CommandsMetadata::default().cell()
+    }
+
+    fn get_message_commands_dir(self: Vc<Self>) -> Vc<OptionCommandsDir> {
+        OptionCommandsDir::default().cell()
+    }
+
+    /// The scheduled tasks of `tasks/`
+    fn get_tasks(self: Vc<Self>) -> Vc<TasksMetadata> {
+        TasksMetadata::default().cell()
+    }
+
+    fn get_tasks_dir(self: Vc<Self>) -> Vc<OptionTasksDir> {
+        OptionTasksDir::default().cell()
+    }
+
     /// Gets any content sources wrapped in this content source.
     fn get_children(self: Vc<Self>) -> Vc<ContentSources> {
         ContentSources::empty()
@@ -66,6 +85,10 @@ pub struct ContentSourceData {
     pub commands: Vc<CommandsMetadata>,
     pub events_dir: Vc<OptionEventsDir>,
     pub commands_dir: Vc<OptionCommandsDir>,
+    pub message_commands: Vc<CommandsMetadata>,
+    pub message_commands_dir: Vc<OptionCommandsDir>,
+    pub tasks: Vc<TasksMetadata>,
+    pub tasks_dir: Vc<OptionTasksDir>,
 }
 
 #[turbo_tasks::value(shared, serialization = "auto_for_input")]
@@ -102,6 +125,26 @@ impl ContentSource for ContentSourceData {
     fn get_commands_dir(&self) -> Vc<OptionCommandsDir> {
         self.commands_dir
     }
+
+    #[turbo_tasks::function]
+    fn get_message_commands(&self) -> Vc<CommandsMetadata> {
+        self.message_commands
+    }
+
+    #[turbo_tasks::function]
+    fn get_message_commands_dir(&self) -> Vc<OptionCommandsDir> {
+        self.message_commands_dir
+    }
+
+    #[turbo_tasks::function]
+    fn get_tasks(&self) -> Vc<TasksMetadata> {
+        self.tasks
+    }
+
+    #[turbo_tasks::function]
+    fn get_tasks_dir(&self) -> Vc<OptionTasksDir> {
+        self.tasks_dir
+    }
 }
 
 #[turbo_tasks::value(transparent)]
@@ -0,0 +1,293 @@
+//! Runs the same suite against every cache backend. The Redis test is ignored
+//! by default, as it needs a Redis-compatible server:
+//!
+//! ```sh
+//! FUJINOKI_TEST_REDIS_URL=redis://localhost:6379/15 cargo test -p fujinoki-websocket -- --ignored
+//! ```
+//!
+//! The tests clear the `fujinoki-test:*` keys.
+
+use discord_api::{
+    channel::Channel,
+    guild::{Guild, GuildMember, Role},
+    id::{GuildId, UserId},
+};
+use fujinoki_websocket::cache::{
+    backend::{
+        get_entity, set_entity, CacheBackend, CacheChange, CacheKey, EntityKind, MemoryBackend,
+    },
+    read_through,
+    redis::RedisBackend,
+    EntityScope,
+};
+use serde_json::json;
+
+const KINDS: [EntityKind; 4] = [
+    EntityKind::Guild,
+    EntityKind::Channel,
+    EntityKind::Role,
+    EntityKind::Member,
+];
+
+async fn run_suite(backend: &dyn CacheBackend) {
+    for kind in KINDS {
+        backend.clear(kind).await.unwrap();
+    }
+
+    get_set_delete(backend).await;
+    entries_and_count(backend).await;
+    apply_changes(backend).await;
+    models_round_trip(backend).await;
+
+    for kind in KINDS {
+        backend.clear(kind).await.unwrap();
+        assert_eq!(backend.count(kind).await.unwrap(), 0);
+    }
+}
+
+async fn get_set_delete(backend: &dyn CacheBackend) {
+    let kind = EntityKind::Guild;
+    assert_eq!(backend.get(kind, "1").await.unwrap(), None);
+
+    backend.set(kind, "1", b"first".to_vec()).await.unwrap();
+    assert_eq!(
+        backend.get(kind, "1").await.unwrap(),
+        Some(b"first".to_vec())
+    );
+
+    // Each kind is stored on its own
+    assert_eq!(backend.get(EntityKind::Channel, "1").await.unwrap(), None);
+
+    backend.set(kind, "1", b"second".to_vec()).await.unwrap();
+    assert_eq!(
+        backend.get(kind, "1").await.unwrap(),
+        Some(b"second".to_vec())
+    );
+
+    backend.delete(kind, "1").await.unwrap();
+    assert_eq!(backend.get(kind, "1").await.unwrap(), None);
+
+    // Deleting a missing entity is not an error
+    backend.delete(kind, "1").await.unwrap();
+}
+
+async fn entries_and_count(backend: &dyn CacheBackend) {
+    let kind = EntityKind::Channel;
+    for id in ["1", "2", "3"] {
+        backend.set(kind, id, id.as_bytes().to_vec()).await.unwrap();
+    }
+    assert_eq!(backend.count(kind).await.unwrap(), 3);
+
+    let mut entries = backend.entries(kind).await.unwrap();
+    entries.sort();
+    assert_eq!(
+        entries,
+        vec![
+            ("1".to_string(), b"1".to_vec()),
+            ("2".to_string(), b"2".to_vec()),
+            ("3".to_string(), b"3".to_vec()),
+        ]
+    );
+
+    backend.clear(kind).await.unwrap();
+    assert_eq!(backend.count(kind).await.unwrap(), 0);
+    assert!(backend.entries(kind).await.unwrap().is_empty());
+}
+
+async fn apply_changes(backend: &dyn CacheBackend) {
+    let kind = EntityKind::Role;
+    let set = |key: &str, value: &[u8]| CacheChange::Set {
+        kind,
+        key: key.to_string(),
+        value: value.to_vec(),
+    };
+
+    backend
+        .apply(vec![
+            set("1:2", b"a"),
+            set("1:3", b"b"),
+            CacheChange::Delete {
+                kind,
+                key: "1:2".to_string(),
+            },
+            set("1:3", b"c"),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(backend.get(kind, "1:2").await.unwrap(), None);
+    assert_eq!(backend.get(kind, "1:3").await.unwrap(), Some(b"c".to_vec()));
+    assert_eq!(backend.count(kind).await.unwrap(), 1);
+
+    backend.apply(vec![]).await.unwrap();
+    backend.clear(kind).await.unwrap();
+}
+
+async fn models_round_trip(backend: &dyn CacheBackend) {
+    let guild: Guild =
+        serde_json::from_str(include_str!("../../discord-api/fixtures/guild.json")).unwrap();
+    set_entity(backend, EntityKind::Guild, &guild.id, &guild)
+        .await
+        .unwrap();
+    let cached: Option<Guild> = get_entity(backend, EntityKind::Guild, &guild.id)
+        .await
+        .unwrap();
+    assert_eq!(cached, Some(guild.clone()));
+
+    let channels: Vec<Channel> =
+        serde_json::from_str(include_str!("../../discord-api/fixtures/channels.json")).unwrap();
+    for channel in &channels {
+        set_entity(backend, EntityKind::Channel, &channel.id, channel)
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        backend.count(EntityKind::Channel).await.unwrap(),
+        channels.len()
+    );
+    for channel in &channels {
+        let cached: Option<Channel> = get_entity(backend, EntityKind::Channel, &channel.id)
+            .await
+            .unwrap();
+        assert_eq!(cached.as_ref(), Some(channel));
+    }
+
+    let roles: Vec<Role> =
+        serde_json::from_str(include_str!("../../discord-api/fixtures/roles.json")).unwrap();
+    for role in &roles {
+        let key = (guild.id, role.id);
+        set_entity(backend, EntityKind::Role, &key, role)
+            .await
+            .unwrap();
+        let cached: Option<Role> = get_entity(backend, EntityKind::Role, &key).await.unwrap();
+        assert_eq!(cached.as_ref(), Some(role));
+    }
+
+    let member: GuildMember =
+        serde_json::from_str(include_str!("../../discord-api/fixtures/guild_member.json")).unwrap();
+    let key: (GuildId, UserId) = (guild.id, member.user.as_ref().unwrap().id);
+    set_entity(backend, EntityKind::Member, &key, &member)
+        .await
+        .unwrap();
+    let cached = backend
+        .get(EntityKind::Member, &key.cache_key())
+        .await
+        .unwrap();
+    assert_eq!(
+        cached.map(|value| serde_json::from_slice::<GuildMember>(&value).unwrap()),
+        Some(member)
+    );
+
+    // A value that isn't a model is reported instead of panicking
+    backend
+        .set(
+            EntityKind::Guild,
+            &guild.id.cache_key(),
+            b"not json".to_vec(),
+        )
+        .await
+        .unwrap();
+    assert!(get_entity::<Guild>(backend, EntityKind::Guild, &guild.id)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn memory_backend() {
+    run_suite(&MemoryBackend::new()).await;
+}
+
+#[tokio::test]
+async fn read_through_missing_entities() {
+    let backend = MemoryBackend::new();
+    let guild: Guild =
+        serde_json::from_str(include_str!("../../discord-api/fixtures/guild.json")).unwrap();
+    let member: GuildMember =
+        serde_json::from_str(include_str!("../../discord-api/fixtures/guild_member.json")).unwrap();
+    let user_id = member.user.as_ref().unwrap().id;
+
+    set_entity(&backend, EntityKind::Guild, &guild.id, &guild)
+        .await
+        .unwrap();
+    set_entity(&backend, EntityKind::Member, &(guild.id, user_id), &member)
+        .await
+        .unwrap();
+    for role in &guild.roles {
+        set_entity(&backend, EntityKind::Role, &(guild.id, role.id), role)
+            .await
+            .unwrap();
+    }
+
+    let scope = EntityScope {
+        guild_id: Some(guild.id),
+        channel_id: None,
+        user_id: Some(user_id),
+    };
+    let mut entities = json!({ "guilds": {}, "channels": {}, "roles": {}, "members": {} });
+    read_through(&backend, &scope, &mut entities).await.unwrap();
+
+    let guild_id = guild.id.to_string();
+    assert_eq!(entities["guilds"][&guild_id]["name"], json!(guild.name));
+    assert!(entities["members"][&guild_id][user_id.to_string()].is_object());
+    // Only the roles of the member are read
+    for role_id in &member.roles {
+        let role = &entities["roles"][&guild_id][role_id.to_string()];
+        assert_eq!(
+            role.is_object(),
+            guild.roles.iter().any(|role| role.id == *role_id)
+        );
+    }
+
+    // Entities that are already there aren't replaced
+    let mut entities = json!({ "guilds": { guild_id.clone(): "cached" } });
+    read_through(&backend, &scope, &mut entities).await.unwrap();
+    assert_eq!(entities["guilds"][&guild_id], json!("cached"));
+}
+
+#[tokio::test]
+#[ignore = "needs a Redis-compatible server at FUJINOKI_TEST_REDIS_URL"]
+async fn redis_backend() {
+    let url = std::env::var("FUJINOKI_TEST_REDIS_URL")
+        .expect("FUJINOKI_TEST_REDIS_URL should point to a Redis-compatible server");
+
+    let backend = RedisBackend::connect(&url, Some("fujinoki-test"))
+        .await
+        .unwrap();
+    run_suite(&backend).await;
+}
+
+#[tokio::test]
+async fn redis_backend_invalid_url() {
+    assert!(RedisBackend::connect("http://localhost:6379", None)
+        .await
+        .is_err());
+    assert!(RedisBackend::connect("not a url", None).await.is_err());
+}
+
+#[tokio::test]
+async fn redis_backend_reconnects() {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("redis://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let mut buf = [0; 1024];
+
+        // The first connection is closed while the command is sent, e.g. by a restart
+        let (mut first, _) = listener.accept().await.unwrap();
+        first.read(&mut buf).await.unwrap();
+        drop(first);
+
+        let (mut second, _) = listener.accept().await.unwrap();
+        second.read(&mut buf).await.unwrap();
+        second.write_all(b"$-1\r\n").await.unwrap();
+        second
+    });
+
+    let backend = RedisBackend::connect(&url, None).await.unwrap();
+    assert_eq!(backend.get(EntityKind::Guild, "1").await.unwrap(), None);
+    server.await.unwrap();
+}
@@ -0,0 +1,88 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use fujinoki_websocket::scheduler::schedule::{format_utc, Schedule};
+use serde_json::{json, Value as JsonValue};
+
+/// 2024-02-29 13:07:30 UTC, a thursday
+fn now() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(1_709_212_050)
+}
+
+fn next_run(schedule: JsonValue) -> Option<String> {
+    Schedule::parse(&schedule)
+        .unwrap()
+        .next_after(now())
+        .map(format_utc)
+}
+
+#[test]
+fn format() {
+    assert_eq!(format_utc(now()), "2024-02-29 13:07:30 UTC");
+    assert_eq!(format_utc(UNIX_EPOCH), "1970-01-01 00:00:00 UTC");
+}
+
+#[test]
+fn intervals() {
+    assert_eq!(next_run(json!("30s")).unwrap(), "2024-02-29 13:08:00 UTC");
+    assert_eq!(next_run(json!("1h30m")).unwrap(), "2024-02-29 14:37:30 UTC");
+    assert_eq!(next_run(json!("1d")).unwrap(), "2024-03-01 13:07:30 UTC");
+    assert_eq!(
+        Schedule::parse(&json!(2500)).unwrap(),
+        Schedule::Interval(Duration::from_millis(2500))
+    );
+
+    // Numbers that are too large for a duration are invalid as well
+    for invalid in [
+        json!("5"),
+        json!("m5"),
+        json!("0s"),
+        json!("5 m"),
+        json!(0),
+        json!(-5),
+        json!(1e300),
+    ] {
+        assert!(Schedule::parse(&invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn cron_expressions() {
+    let cases = [
+        ("*/15 * * * *", "2024-02-29 13:15:00 UTC"),
+        ("0 9 * * mon-fri", "2024-03-01 09:00:00 UTC"),
+        ("@daily", "2024-03-01 00:00:00 UTC"),
+        // Leap days
+        ("0 0 29 2 *", "2028-02-29 00:00:00 UTC"),
+        // The first of the month or a monday
+        ("0 12 1 * 1", "2024-03-01 12:00:00 UTC"),
+        ("30 8 * jan,jun sun", "2024-06-02 08:30:00 UTC"),
+        // 7 is also sunday
+        ("5/20 * * * 7", "2024-03-03 00:05:00 UTC"),
+    ];
+    for (expression, expected) in cases {
+        assert_eq!(
+            next_run(json!(expression)).as_deref(),
+            Some(expected),
+            "{expression}"
+        );
+    }
+
+    // Never matches
+    assert_eq!(next_run(json!("0 0 30 2 *")), None);
+}
+
+#[test]
+fn invalid_cron_expressions() {
+    for invalid in [
+        "* * *",
+        "* * * * * *",
+        "60 * * * *",
+        "1-0 * * * *",
+        "*/0 * * * *",
+        "* * * foo *",
+        "@sometimes",
+    ] {
+        assert!(Schedule::parse(&json!(invalid)).is_err(), "{invalid}");
+    }
+    assert!(Schedule::parse(&JsonValue::Null).is_err());
+}